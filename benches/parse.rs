@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use proc_route_parser::{Ipv4RouteEntry, Ipv4RouteEntryRef, Ipv6RouteEntry, Ipv6RouteEntryRef};
+use std::hint::black_box;
+
+const IPV4_LINE: &str = "eth0\t0000A8C0\t0101A8C0\t0003\t0\t0\t100\t00FFFFFF\t0\t0\t0";
+const IPV6_LINE: &str = "20010db8000000000000000000000000 40 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003     eth0";
+
+fn parse_lines(c: &mut Criterion) {
+    c.bench_function("ipv4 entry from_str", |b| {
+        b.iter(|| black_box(IPV4_LINE).parse::<Ipv4RouteEntry>().unwrap())
+    });
+    c.bench_function("ipv6 entry from_str", |b| {
+        b.iter(|| black_box(IPV6_LINE).parse::<Ipv6RouteEntry>().unwrap())
+    });
+    c.bench_function("ipv4 entry ref parse", |b| {
+        b.iter(|| Ipv4RouteEntryRef::parse(black_box(IPV4_LINE)).unwrap())
+    });
+    c.bench_function("ipv6 entry ref parse", |b| {
+        b.iter(|| Ipv6RouteEntryRef::parse(black_box(IPV6_LINE)).unwrap())
+    });
+}
+
+criterion_group!(benches, parse_lines);
+criterion_main!(benches);
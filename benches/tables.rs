@@ -0,0 +1,119 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteFlags, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteFlags, Ipv6RouteTable,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    net::{Ipv4Addr, Ipv6Addr},
+    path::PathBuf,
+};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+const INTERFACES: [&str; 4] = ["eth0", "eth1", "wlan0", "wg0"];
+
+/// Write a synthetic `/proc/net/route` with `len` distinct /24 routes
+fn ipv4_route_file(len: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("proc_route_parser_bench_route_{len}"));
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+    writeln!(file, "{}", Ipv4RouteEntry::PROC_HEADER).unwrap();
+    for i in 0..len {
+        let gateway = i % 2 == 0;
+        let mut flags = Ipv4RouteFlags::UP;
+        flags.set(Ipv4RouteFlags::GATEWAY, gateway);
+        let entry = Ipv4RouteEntry::builder(INTERFACES[i % INTERFACES.len()])
+            .dest(Ipv4Addr::from((i as u32) << 8))
+            .prefix_len(24)
+            .gateway(if gateway {
+                Ipv4Addr::new(192, 168, 0, 1)
+            } else {
+                Ipv4Addr::UNSPECIFIED
+            })
+            .flags(flags)
+            .metric((i % 1000) as u32)
+            .build()
+            .unwrap();
+        writeln!(file, "{}", entry.to_proc_line()).unwrap();
+    }
+    path
+}
+
+/// Write a synthetic `/proc/net/ipv6_route` with `len` distinct /64 routes
+fn ipv6_route_file(len: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("proc_route_parser_bench_ipv6_route_{len}"));
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+    for i in 0..len {
+        let gateway = i % 2 == 0;
+        let mut flags = Ipv6RouteFlags::UP;
+        flags.set(Ipv6RouteFlags::GATEWAY, gateway);
+        let entry = Ipv6RouteEntry::builder(INTERFACES[i % INTERFACES.len()])
+            .dest(Ipv6Addr::from(
+                (0x2001_0db8_u128 << 96) | ((i as u128) << 64),
+            ))
+            .dest_prefix(64)
+            .next_hop(if gateway {
+                Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)
+            } else {
+                Ipv6Addr::UNSPECIFIED
+            })
+            .flags(flags)
+            .metric(1024)
+            .build()
+            .unwrap();
+        writeln!(file, "{}", entry.to_proc_line()).unwrap();
+    }
+    path
+}
+
+fn parse_tables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ipv4 table");
+    group.sample_size(10);
+    for len in SIZES {
+        let path = ipv4_route_file(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("parse", len), &path, |b, path| {
+            b.iter(|| Ipv4RouteTable::open(path).unwrap().flatten().count())
+        });
+        group.bench_with_input(BenchmarkId::new("parse + decode", len), &path, |b, path| {
+            b.iter(|| {
+                Ipv4RouteTable::open(path)
+                    .unwrap()
+                    .flatten()
+                    .filter(|entry| {
+                        entry.flags.contains(Ipv4RouteFlags::GATEWAY)
+                            && entry.dest.octets()[0] < 128
+                    })
+                    .count()
+            })
+        });
+        std::fs::remove_file(path).unwrap();
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("ipv6 table");
+    group.sample_size(10);
+    for len in SIZES {
+        let path = ipv6_route_file(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("parse", len), &path, |b, path| {
+            b.iter(|| Ipv6RouteTable::open(path).unwrap().flatten().count())
+        });
+        group.bench_with_input(BenchmarkId::new("parse + decode", len), &path, |b, path| {
+            b.iter(|| {
+                Ipv6RouteTable::open(path)
+                    .unwrap()
+                    .flatten()
+                    .filter(|entry| {
+                        entry.flags.contains(Ipv6RouteFlags::GATEWAY)
+                            && entry.dest.segments()[2] < 0x8000
+                    })
+                    .count()
+            })
+        });
+        std::fs::remove_file(path).unwrap();
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_tables);
+criterion_main!(benches);
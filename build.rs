@@ -0,0 +1,50 @@
+#[cfg(feature = "cli")]
+#[allow(dead_code)]
+mod cli {
+    include!("src/bin/proc-route/cli.rs");
+}
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+    #[cfg(feature = "cli")]
+    generate_man_pages();
+}
+
+/// Generate the C header of the `ffi` module into `$PROC_ROUTE_INCLUDE_DIR`, or
+/// `$OUT_DIR/include` when it is unset
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=PROC_ROUTE_INCLUDE_DIR");
+    let dir = match std::env::var_os("PROC_ROUTE_INCLUDE_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => std::path::Path::new(&std::env::var_os("OUT_DIR").unwrap()).join("include"),
+    };
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+        .expect("cbindgen.toml should be valid");
+    // only the ffi module, not the constants and types of the rest of the crate
+    cbindgen::Builder::new()
+        .with_src(format!("{crate_dir}/src/ffi.rs"))
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate C bindings")
+        .write_to_file(dir.join("proc_route_parser.h"));
+}
+
+/// Render the man pages of `proc-route` and its subcommands into `$PROC_ROUTE_MAN_DIR`, or
+/// `$OUT_DIR/man` when it is unset
+#[cfg(feature = "cli")]
+fn generate_man_pages() {
+    use clap::CommandFactory;
+    println!("cargo:rerun-if-changed=src/bin/proc-route/cli.rs");
+    println!("cargo:rerun-if-env-changed=PROC_ROUTE_MAN_DIR");
+    let dir = match std::env::var_os("PROC_ROUTE_MAN_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => std::path::Path::new(&std::env::var_os("OUT_DIR").unwrap()).join("man"),
+    };
+    std::fs::create_dir_all(&dir).expect("the man page directory should be writable");
+    clap_mangen::generate_to(cli::Cli::command(), &dir).expect("Unable to write the man pages");
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proc_route_parser::fuzz_api;
+
+fuzz_target!(|text: &str| {
+    let _ = fuzz_api::hex_str_to_ipv4(text);
+    let _ = fuzz_api::hex_str_to_ipv6(text);
+    let mut out = vec![0u8; text.len() / 2];
+    let _ = fuzz_api::decode_hex_into(text, &mut out);
+});
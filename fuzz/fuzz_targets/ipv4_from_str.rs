@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proc_route_parser::Ipv4RouteEntry;
+
+fuzz_target!(|line: &str| {
+    if let Ok(entry) = line.parse::<Ipv4RouteEntry>() {
+        let _ = entry.to_proc_line();
+    }
+});
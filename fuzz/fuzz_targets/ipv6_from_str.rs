@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proc_route_parser::Ipv6RouteEntry;
+
+fuzz_target!(|line: &str| {
+    if let Ok(entry) = line.parse::<Ipv6RouteEntry>() {
+        let _ = entry.to_proc_line();
+    }
+});
@@ -0,0 +1,118 @@
+#ifndef PROC_ROUTE_PARSER_H
+#define PROC_ROUTE_PARSER_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Interface name buffer size, including the NUL terminator (`IFNAMSIZ`)
+ */
+#define PRP_IFNAMSIZ 16
+
+/**
+ * Returned in `error` when a line of the route file is malformed
+ */
+#define PRP_ERR_PARSE -1
+
+/**
+ * Returned in `error` by `prp_get_ipv6_routes` when IPv6 is disabled on the host
+ */
+#define PRP_ERR_IPV6_UNAVAILABLE -2
+
+typedef struct PrpIpv4Route {
+  /**
+   * NUL-terminated interface name
+   */
+  uint8_t name[PRP_IFNAMSIZ];
+  /**
+   * Addresses in network byte order
+   */
+  uint8_t dest[4];
+  uint8_t gateway[4];
+  uint8_t mask[4];
+  uint16_t flags;
+  uint32_t ref_count;
+  uint32_t use_count;
+  uint32_t metric;
+  /**
+   * As printed in the route line, the kernel sets no `RTF_MTU`, `RTF_WINDOW` or `RTF_IRTT` bit
+   */
+  uint32_t mtu;
+  uint32_t window;
+  uint32_t irtt;
+} PrpIpv4Route;
+
+/**
+ * Routes owned by the library, release them with the matching `prp_free_*` function.
+ *
+ * On failure `routes` is NULL and `error` holds a positive `errno` value or `PRP_ERR_PARSE`.
+ */
+typedef struct PrpIpv4RouteArray {
+  struct PrpIpv4Route *routes;
+  size_t len;
+  int error;
+} PrpIpv4RouteArray;
+
+typedef struct PrpIpv6Route {
+  /**
+   * Addresses in network byte order
+   */
+  uint8_t dest[16];
+  uint8_t dest_prefix;
+  uint8_t src[16];
+  uint8_t src_prefix;
+  uint8_t next_hop[16];
+  uint32_t metric;
+  uint32_t ref_count;
+  uint32_t use_count;
+  uint32_t flags;
+  /**
+   * NUL-terminated interface name
+   */
+  uint8_t name[PRP_IFNAMSIZ];
+} PrpIpv6Route;
+
+/**
+ * Routes owned by the library, release them with the matching `prp_free_*` function.
+ *
+ * On failure `routes` is NULL and `error` holds a positive `errno` value, `PRP_ERR_PARSE` or
+ * `PRP_ERR_IPV6_UNAVAILABLE`.
+ */
+typedef struct PrpIpv6RouteArray {
+  struct PrpIpv6Route *routes;
+  size_t len;
+  int error;
+} PrpIpv6RouteArray;
+
+/**
+ * Read and parse `/proc/net/route`
+ */
+struct PrpIpv4RouteArray prp_get_ipv4_routes(void);
+
+/**
+ * Read and parse `/proc/net/ipv6_route`
+ */
+struct PrpIpv6RouteArray prp_get_ipv6_routes(void);
+
+/**
+ * Release an array returned by `prp_get_ipv4_routes`
+ *
+ * # Safety
+ * `array` must come from `prp_get_ipv4_routes` and must not be freed twice.
+ */
+void prp_free_ipv4_routes(struct PrpIpv4RouteArray array);
+
+/**
+ * Release an array returned by `prp_get_ipv6_routes`
+ *
+ * # Safety
+ * `array` must come from `prp_get_ipv6_routes` and must not be freed twice.
+ */
+void prp_free_ipv6_routes(struct PrpIpv6RouteArray array);
+
+#endif  /* PROC_ROUTE_PARSER_H */
@@ -0,0 +1,44 @@
+use crate::{IpNet, Route};
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec::Vec};
+use core::net::IpAddr;
+
+/// Collapse the destinations of `entries` into the fewest CIDR networks.
+///
+/// Only destinations reached through the same gateway and interface are merged with each other:
+/// overlapping prefixes are reduced to the widest one and adjacent siblings are joined into their
+/// supernet. Networks are grouped by next hop and sorted within each group.
+pub fn summarize<R: Route>(entries: impl IntoIterator<Item = R>) -> Vec<IpNet> {
+    let mut groups: BTreeMap<(Option<IpAddr>, String), Vec<IpNet>> = BTreeMap::new();
+    for entry in entries {
+        groups
+            .entry((entry.gateway(), entry.interface().to_owned()))
+            .or_default()
+            .push(entry.network());
+    }
+    groups.into_values().flat_map(collapse).collect()
+}
+
+/// Minimal set of networks covering exactly the union of `nets`
+pub(crate) fn collapse(mut nets: Vec<IpNet>) -> Vec<IpNet> {
+    nets.sort();
+    let mut collapsed: Vec<IpNet> = Vec::with_capacity(nets.len());
+    for net in nets {
+        if collapsed.last().is_some_and(|last| last.contains_net(&net)) {
+            continue;
+        }
+        collapsed.push(net);
+        // join the last two networks while they are the two halves of the same supernet
+        while let [.., low, high] = collapsed[..] {
+            match (low.supernet(), high.supernet()) {
+                (Some(parent), Some(other))
+                    if parent == other && low.prefix_len() == high.prefix_len() =>
+                {
+                    collapsed.truncate(collapsed.len() - 2);
+                    collapsed.push(parent);
+                }
+                _ => break,
+            }
+        }
+    }
+    collapsed
+}
@@ -0,0 +1,196 @@
+use crate::{Metric, Route, aggregate::collapse};
+use alloc::{
+    borrow::ToOwned,
+    collections::{BTreeMap, btree_map::Entry},
+    string::String,
+    vec::Vec,
+};
+use core::{cmp::Reverse, net::IpAddr};
+
+/// A problem found by [`find_conflicts`], referring to routes by their index in the input slice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteConflict {
+    /// Two routes to the same prefix, for the same sources, through different next hops.
+    ///
+    /// With equal metrics only one of them is used, otherwise the other one is a fallback. IPv6
+    /// routes of equal metric are not reported, as the kernel spreads flows over them (ECMP).
+    DuplicatePrefix {
+        first: usize,
+        second: usize,
+        same_metric: bool,
+    },
+    /// Every address of `route` is matched by the more specific routes in `by`, so it is never used
+    Shadowed { route: usize, by: Vec<usize> },
+    /// No connected route on the same interface covers the gateway of `route`
+    UnreachableGateway { route: usize, gateway: IpAddr },
+}
+
+/// Look for duplicate, shadowed and unreachable routes. Routes that are not `UP` are ignored.
+pub fn find_conflicts<R: Route>(routes: &[R]) -> Vec<RouteConflict> {
+    let active: Vec<(usize, &R)> = routes
+        .iter()
+        .enumerate()
+        .filter(|(_, route)| route.is_up())
+        .collect();
+    let mut conflicts = Vec::new();
+
+    for (pos, &(first, a)) in active.iter().enumerate() {
+        for &(second, b) in &active[pos + 1..] {
+            let same_hop = a.gateway() == b.gateway() && a.interface() == b.interface();
+            let same_prefix = a.network() == b.network() && a.source_prefix() == b.source_prefix();
+            let same_metric = a.metric() == b.metric();
+            let multipath = same_metric && a.destination().is_ipv6();
+            if same_prefix && !same_hop && !multipath {
+                conflicts.push(RouteConflict::DuplicatePrefix {
+                    first,
+                    second,
+                    same_metric,
+                });
+            }
+        }
+    }
+
+    for &(route, a) in &active {
+        let network = a.network();
+        let by: Vec<usize> = active
+            .iter()
+            .filter(|(_, b)| {
+                b.prefix_len() > network.prefix_len() && network.contains_net(&b.network())
+            })
+            .map(|&(index, _)| index)
+            .collect();
+        let covered = collapse(by.iter().map(|&index| routes[index].network()).collect());
+        if covered.contains(&network) {
+            conflicts.push(RouteConflict::Shadowed { route, by });
+        }
+    }
+
+    for &(route, a) in &active {
+        let Some(gateway) = a.gateway() else {
+            continue;
+        };
+        let on_link = active.iter().any(|(_, b)| {
+            b.gateway().is_none() && b.interface() == a.interface() && b.network().contains(gateway)
+        });
+        if !on_link {
+            conflicts.push(RouteConflict::UnreachableGateway { route, gateway });
+        }
+    }
+    conflicts
+}
+
+/// A next hop found by [`gateways`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GatewayInfo {
+    pub addr: IpAddr,
+    /// Interface the gateway is reached through
+    pub iface: String,
+    /// Number of routes using this gateway on this interface
+    pub route_count: usize,
+    pub lowest_metric: Metric,
+}
+
+/// List every distinct gateway of `entries`, one per address and interface.
+///
+/// The most preferred gateway comes first: lowest metric, then most routes, then by address and
+/// interface. Pass [`RouteEntry`](crate::RouteEntry) values to cover both families at once.
+pub fn gateways<R: Route>(entries: impl IntoIterator<Item = R>) -> Vec<GatewayInfo> {
+    let mut found: BTreeMap<(IpAddr, String), GatewayInfo> = BTreeMap::new();
+    for entry in entries {
+        let Some(addr) = entry.gateway() else {
+            continue;
+        };
+        match found.entry((addr, entry.interface().to_owned())) {
+            Entry::Occupied(mut info) => {
+                let info = info.get_mut();
+                info.route_count += 1;
+                info.lowest_metric = info.lowest_metric.min(entry.metric());
+            }
+            Entry::Vacant(slot) => {
+                let iface = slot.key().1.clone();
+                slot.insert(GatewayInfo {
+                    addr,
+                    iface,
+                    route_count: 1,
+                    lowest_metric: entry.metric(),
+                });
+            }
+        }
+    }
+    let mut gateways: Vec<GatewayInfo> = found.into_values().collect();
+    // stable, so equal ranks keep the address and interface order of the map
+    gateways.sort_by_key(|info| (info.lowest_metric, Reverse(info.route_count)));
+    gateways
+}
+
+/// Aggregate counts of a route table, see [`summarize_table`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSummary {
+    pub total: usize,
+    /// Routes flagged `UP`
+    pub up: usize,
+    /// Routes through a gateway
+    pub gateway: usize,
+    /// Routes to a single address
+    pub host: usize,
+    /// Routes flagged `REJECT`
+    pub reject: usize,
+    /// Routes per output interface
+    pub by_interface: BTreeMap<String, usize>,
+    /// Routes per prefix length
+    pub by_prefix_len: BTreeMap<u8, usize>,
+    /// Whether an IPv4 default route is `UP`
+    pub ipv4_default: bool,
+    /// Whether an IPv6 default route is `UP`
+    pub ipv6_default: bool,
+}
+
+/// Count the routes of `entries` by flag, interface and prefix length.
+///
+/// Pass [`RouteEntry`](crate::RouteEntry) values to summarize both families at once.
+pub fn summarize_table<R: Route>(entries: impl IntoIterator<Item = R>) -> TableSummary {
+    let mut summary = TableSummary::default();
+    for entry in entries {
+        summary.total += 1;
+        summary.up += usize::from(entry.is_up());
+        summary.gateway += usize::from(entry.gateway().is_some());
+        summary.host += usize::from(entry.is_host());
+        summary.reject += usize::from(entry.is_reject());
+        *summary
+            .by_interface
+            .entry(entry.interface().to_owned())
+            .or_default() += 1;
+        let prefix_len = entry.network().prefix_len();
+        *summary.by_prefix_len.entry(prefix_len).or_default() += 1;
+        if prefix_len == 0 && entry.is_up() {
+            match entry.destination() {
+                IpAddr::V4(_) => summary.ipv4_default = true,
+                IpAddr::V6(_) => summary.ipv6_default = true,
+            }
+        }
+    }
+    summary
+}
+
+/// The `n` routes of `entries` with the highest [use counter](Route::use_count), most used first.
+///
+/// Ties keep the order of `entries`. Kernels since 3.6 print 0 for both counters of IPv4 routes,
+/// so only IPv6 routes are ranked meaningfully there.
+pub fn top_routes_by_use<R: Route>(entries: impl IntoIterator<Item = R>, n: usize) -> Vec<R> {
+    top_routes(entries, n, R::use_count)
+}
+
+/// The `n` routes of `entries` with the most [references](Route::ref_count), see
+/// [`top_routes_by_use`]
+pub fn top_routes_by_refcnt<R: Route>(entries: impl IntoIterator<Item = R>, n: usize) -> Vec<R> {
+    top_routes(entries, n, R::ref_count)
+}
+
+fn top_routes<R>(entries: impl IntoIterator<Item = R>, n: usize, count: fn(&R) -> u32) -> Vec<R> {
+    let mut entries: Vec<R> = entries.into_iter().collect();
+    entries.sort_by_key(|entry| Reverse(count(entry)));
+    entries.truncate(n);
+    entries
+}
@@ -0,0 +1,152 @@
+#[cfg(feature = "tracing")]
+use crate::trace;
+#[cfg(feature = "std")]
+use crate::{AddressFamily, LineError, TableStats, report::TableLines};
+use crate::{MacAddr, RouteParseError, utils::parse_mac};
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{net::Ipv4Addr, str::FromStr};
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+/// An entry of the IPv4 neighbor table in `/proc/net/arp`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArpEntry {
+    pub ip: Ipv4Addr,
+    /// ARP hardware type, 1 for Ethernet
+    pub hw_type: u16,
+    pub flags: ArpFlags,
+    /// `None` when the hardware address is not 6 bytes long, as on InfiniBand
+    pub hw_addr: Option<MacAddr>,
+    pub device: Arc<str>,
+}
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ArpFlags: u16 {
+        /// The hardware address is known (ATF_COM)
+        const COMPLETE = 0x02;
+        /// Static entry that never expires (ATF_PERM)
+        const PERMANENT = 0x04;
+        /// Proxy ARP entry published on behalf of another host (ATF_PUBL)
+        const PUBLISHED = 0x08;
+        /// Trailers requested, obsolete (ATF_USETRAILERS)
+        const USE_TRAILERS = 0x10;
+        /// Proxy entry for a whole network (ATF_NETMASK)
+        const NETMASK = 0x20;
+        /// Entry that must not be answered for (ATF_DONTPUB)
+        const DONT_PUBLISH = 0x40;
+    }
+}
+
+impl ArpEntry {
+    /// Header line of `/proc/net/arp`
+    pub const PROC_HEADER: &str =
+        "IP address       HW type     Flags       HW address            Mask     Device";
+
+    /// Whether the hardware address has been resolved
+    pub fn is_complete(&self) -> bool {
+        self.flags.contains(ArpFlags::COMPLETE)
+    }
+
+    /// Serialize the entry into a `/proc/net/arp` line (without the trailing newline)
+    pub fn to_proc_line(&self) -> String {
+        let hw_type = format!("0x{:x}", self.hw_type);
+        let flags = format!("0x{:x}", self.flags.bits());
+        let hw_addr = self.hw_addr.unwrap_or_default().to_string();
+        format!(
+            "{:<16} {hw_type:<12}{flags:<12}{hw_addr:<17}     {:<8} {}",
+            self.ip.to_string(),
+            "*",
+            self.device,
+        )
+    }
+}
+
+impl FromStr for ArpEntry {
+    type Err = RouteParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        const ARP_FIELD_COUNT: usize = 6;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [ip, hw_type, flags, hw_addr, _mask, device] = fields[..] else {
+            return Err(RouteParseError::InvalidFieldCount {
+                expected: ARP_FIELD_COUNT,
+                found: fields.len(),
+            });
+        };
+        let hex = |field: &str| u16::from_str_radix(field.trim_start_matches("0x"), 16);
+        Ok(Self {
+            ip: ip.parse()?,
+            hw_type: hex(hw_type)?,
+            flags: ArpFlags::from_bits_retain(hex(flags)?),
+            hw_addr: parse_mac(hw_addr),
+            device: Arc::from(device),
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+/// Iterator over the entries of a `/proc/net/arp` formatted source
+pub struct ArpTable<R = BufReader<File>> {
+    lines: TableLines<R>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "std")]
+impl ArpTable {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut table = Self::from_reader(File::open_buffered(&path)?);
+        table.lines.set_path(path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, path.as_ref());
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> ArpTable<R> {
+    /// Read the table from any buffered source, starting with the header line
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            lines: TableLines::new(reader, true, Some(AddressFamily::Ipv4)),
+            #[cfg(feature = "tracing")]
+            span: trace::table_span("arp"),
+        }
+    }
+
+    /// Counters of the lines read so far
+    pub fn stats(&self) -> TableStats {
+        self.lines.stats()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for ArpTable<R> {
+    type Item = Result<ArpEntry, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+        let (line_number, line) = self.lines.next_line()?;
+        let result = line
+            .map_err(|err| self.lines.error(line_number, String::new(), err))
+            .and_then(|line| {
+                line.parse()
+                    .map_err(|err| self.lines.error(line_number, line, err))
+            });
+        self.lines.record(result.is_ok());
+        Some(result)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Ipv4RouteEntry, Ipv6RouteEntry, LineError, ProcRoot};
+use std::{io::Result as IoResult, sync::Arc};
+
+/// Entries of one family as yielded by a [`RouteSource`]
+pub type RouteIter<'a, T> = Box<dyn Iterator<Item = Result<T, LineError>> + 'a>;
+
+/// Somewhere the route tables can be read from.
+///
+/// Take a `&dyn RouteSource` to let callers pick `/proc`, netlink or a fixture at runtime.
+pub trait RouteSource {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>>;
+
+    /// The IPv6 routes, none when IPv6 is disabled
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>>;
+}
+
+impl RouteSource for ProcRoot {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>> {
+        Ok(Box::new(self.ipv4_route_table()?))
+    }
+
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>> {
+        Ok(match self.open_ipv6_route_table()? {
+            Some(table) => Box::new(table),
+            None => Box::new(std::iter::empty()),
+        })
+    }
+}
+
+impl<S: RouteSource + ?Sized> RouteSource for &S {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>> {
+        (**self).ipv4_routes()
+    }
+
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>> {
+        (**self).ipv6_routes()
+    }
+}
+
+impl<S: RouteSource + ?Sized> RouteSource for Arc<S> {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>> {
+        (**self).ipv4_routes()
+    }
+
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>> {
+        (**self).ipv6_routes()
+    }
+}
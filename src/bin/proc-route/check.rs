@@ -0,0 +1,71 @@
+//! `proc-route check`, assertions on the routing state for scripts to test the exit status of
+
+use crate::{
+    GREEN, RED, RESET,
+    cli::{CheckArgs, Family},
+    parse_filter_arg, usage_error,
+};
+use clap::error::ErrorKind;
+use proc_route_parser::{AddressFamily, IpNet, RouteExpectation, Snapshot};
+use std::io::{self, Write};
+
+/// Whether the tables pass every check of `args`, printing a line per check unless `--quiet`
+pub fn run(args: &CheckArgs, color: bool) -> io::Result<bool> {
+    let expectation = expectation(args);
+    let snapshot = Snapshot::capture().map_err(io::Error::other)?;
+    let report = expectation.evaluate(&snapshot);
+    if !args.quiet {
+        let mut stdout = io::stdout().lock();
+        for outcome in &report.outcomes {
+            let status = match (outcome.passed, color) {
+                (true, false) => "pass".to_string(),
+                (false, false) => "FAIL".to_string(),
+                (true, true) => format!("{GREEN}pass{RESET}"),
+                (false, true) => format!("{RED}FAIL{RESET}"),
+            };
+            writeln!(stdout, "{status} {outcome}")?;
+        }
+    }
+    Ok(report.passed())
+}
+
+fn expectation(args: &CheckArgs) -> RouteExpectation {
+    let mut expectation = RouteExpectation::default();
+    if args.has_default {
+        expectation = expectation.and(RouteExpectation::has_default_route());
+    }
+    if let Some(iface) = &args.default_via {
+        expectation = expectation.and(RouteExpectation::default_via_iface(iface));
+    }
+    for &dest in &args.reaches {
+        expectation = expectation.and(RouteExpectation::reaches(dest));
+    }
+    for filter in args
+        .matches
+        .iter()
+        .filter_map(|expr| parse_filter_arg(Some(expr)))
+    {
+        expectation = expectation.and(RouteExpectation::route_matching(filter));
+    }
+    if let Some(family) = args.family {
+        expectation = expectation.family(match family {
+            Family::Ipv4 => AddressFamily::Ipv4,
+            Family::Ipv6 => AddressFamily::Ipv6,
+        });
+    }
+    for network in &args.no_route_to {
+        let Some(network) = parse_network(network) else {
+            usage_error(
+                ErrorKind::ValueValidation,
+                format!("'{network}' is not a network such as 169.254.0.0/16"),
+            )
+        };
+        expectation = expectation.and(RouteExpectation::no_route_to(network));
+    }
+    expectation
+}
+
+fn parse_network(value: &str) -> Option<IpNet> {
+    let (addr, len) = value.split_once('/')?;
+    IpNet::new(addr.parse().ok()?, len.parse().ok()?).ok()
+}
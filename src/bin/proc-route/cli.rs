@@ -0,0 +1,146 @@
+// The command line of proc-route. build.rs includes this file to render the man pages, so it
+// only uses clap and std.
+
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::{net::IpAddr, path::PathBuf};
+
+/// Print the kernel routing tables read from /proc/net
+#[derive(Debug, Parser)]
+#[command(
+    name = "proc-route",
+    version,
+    args_conflicts_with_subcommands = true,
+    long_about = "Print the kernel routing tables read from /proc/net in the style of \
+                  `route -n`, the routes of each interface together and default routes in bold."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub print: PrintArgs,
+    /// Never color the output. Colors are also off when stdout is not a terminal or the
+    /// NO_COLOR environment variable is set
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("family").args(["ipv4_only", "ipv6_only"])))]
+pub struct PrintArgs {
+    /// Only the IPv4 table
+    #[arg(short = '4')]
+    pub ipv4_only: bool,
+    /// Only the IPv6 table
+    #[arg(short = '6')]
+    pub ipv6_only: bool,
+    /// Write CSV instead of an aligned table, needs -4 or -6
+    #[arg(long, requires = "family")]
+    pub csv: bool,
+    /// Only the N routes with the highest use counts, most used first
+    #[arg(long, value_name = "N")]
+    pub top_used: Option<usize>,
+    /// Only the routes passing EXPR, such as
+    /// 'iface == eth0 && flags has GATEWAY && dest within 10.0.0.0/8'
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Compare two captures and print the added (+), removed (-) and changed (~) routes
+    #[command(
+        long_about = "Compare two captures and print the added (+), removed (-) and \
+                            changed (~) routes. A capture is a JSON snapshot, a copy of \
+                            /proc/net/route or /proc/net/ipv6_route, the output of \
+                            `ip -j route show` or `ip -6 -j route show`, or a directory laid \
+                            out like /proc/net."
+    )]
+    Diff { old: PathBuf, new: PathBuf },
+    /// Poll /proc/net and print a timestamped line for every route change
+    #[command(
+        long_about = "Poll /proc/net and print a timestamped line for every route \
+                            change until interrupted. With --filter only the changes of routes \
+                            passing the filter, before or after the change, are reported. With \
+                            --daemon it runs as a systemd service of Type=notify, reporting when \
+                            it is ready and pinging the watchdog if WatchdogSec= is set."
+    )]
+    Watch(WatchArgs),
+    /// Print the route the kernel would use to reach ADDRESS, like `ip route get`
+    Lookup { address: IpAddr },
+    /// Check the routing state, exiting with 0 when every check passes and 1 when one fails
+    #[command(
+        long_about = "Check the routing state for scripts and health checks. The exit \
+                            status is 0 when every check passes, 1 when one fails and 2 when the \
+                            arguments are wrong or the tables cannot be read."
+    )]
+    Check(CheckArgs),
+    /// Show both tables in a live, sortable and filterable view, needs the tui feature
+    Tui,
+    /// Print the completion script for SHELL
+    Completions { shell: Shell },
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Print each change as a JSON object, needs the serde feature
+    #[arg(long)]
+    pub json_lines: bool,
+    /// Only the changes of routes passing EXPR
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<String>,
+    /// Run COMMAND for each change, with {json} in its words replaced by the change as a JSON
+    /// object. The command is split on whitespace and run without a shell. Needs the serde
+    /// feature
+    #[arg(long, value_name = "COMMAND")]
+    pub exec: Option<String>,
+    /// Emit each change as a D-Bus signal on BUS, needs the dbus feature
+    #[arg(long, value_name = "BUS")]
+    pub dbus: Option<MessageBus>,
+    /// Notify systemd of the startup and shutdown and ping its watchdog, needs the systemd
+    /// feature
+    #[arg(long)]
+    pub daemon: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("checks").required(true).multiple(true)))]
+pub struct CheckArgs {
+    /// Only look at the IPv4 or IPv6 table. --reaches and --no-route-to always use the table of
+    /// their address
+    #[arg(long, value_name = "FAMILY")]
+    pub family: Option<Family>,
+    /// Check that there is a default route the kernel would use
+    #[arg(long, group = "checks")]
+    pub has_default: bool,
+    /// Check that the default route in use goes out of IFACE
+    #[arg(long, value_name = "IFACE", group = "checks")]
+    pub default_via: Option<String>,
+    /// Check that a route leads to ADDRESS, can be repeated
+    #[arg(long, value_name = "ADDRESS", group = "checks")]
+    pub reaches: Vec<IpAddr>,
+    /// Check that no route besides the default routes leads into NETWORK, such as
+    /// 169.254.0.0/16, can be repeated
+    #[arg(long, value_name = "NETWORK", group = "checks")]
+    pub no_route_to: Vec<String>,
+    /// Check that some route passes EXPR, can be repeated
+    #[arg(long, value_name = "EXPR", group = "checks")]
+    pub matches: Vec<String>,
+    /// Print nothing, only set the exit status
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Family {
+    #[value(name = "4")]
+    Ipv4,
+    #[value(name = "6")]
+    Ipv6,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MessageBus {
+    Session,
+    System,
+}
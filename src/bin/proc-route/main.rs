@@ -0,0 +1,504 @@
+use clap::{CommandFactory, Parser, error::ErrorKind};
+#[cfg(feature = "dbus")]
+use cli::MessageBus;
+use cli::{Cli, Command, PrintArgs, WatchArgs};
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+use proc_route_parser::SystemdNotifier;
+#[cfg(feature = "serde")]
+use proc_route_parser::{AddressFamily, RouteEvent, iproute2};
+#[cfg(feature = "dbus")]
+use proc_route_parser::{Bus, RouteMonitorService};
+use proc_route_parser::{
+    Column, Ipv4RouteTable, Ipv6RouteTable, LineError, PollingRouteWatcher, ProcRoot, Route,
+    RouteChange, RouteEntry, RouteFilter, RouteParseError, RouteTableOptions, RouteTables,
+    Snapshot, SnapshotDiff, TableRenderer, TableRow,
+    export::{CsvRow, to_csv},
+    get_ipv4_route_table, get_ipv6_route_table, top_routes_by_use,
+};
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+use std::sync::Arc;
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Cursor, IsTerminal, Write},
+    net::IpAddr,
+    path::Path,
+    process::ExitCode,
+    sync::mpsc::Receiver,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+mod check;
+mod cli;
+#[cfg(feature = "tui")]
+mod tui;
+
+/// How often watch re-reads the tables
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let color = use_color(cli.no_color);
+    let result = match cli.command {
+        None => {
+            let filter = parse_filter_arg(cli.print.filter.as_deref());
+            run(&cli.print, filter.as_ref(), color)
+        }
+        Some(Command::Diff { old, new }) => run_diff(&old, &new, color),
+        Some(Command::Watch(args)) => {
+            check_watch_args(&args);
+            let filter = parse_filter_arg(args.filter.as_deref());
+            run_watch(&args, filter, color)
+        }
+        Some(Command::Lookup { address }) => run_lookup(address),
+        Some(Command::Check(args)) => {
+            // 1 is left to failed checks
+            return match check::run(&args, color) {
+                Ok(true) => ExitCode::SUCCESS,
+                Ok(false) => ExitCode::FAILURE,
+                Err(err) => {
+                    eprintln!("proc-route: {err}");
+                    ExitCode::from(2)
+                }
+            };
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Tui) => tui::run(),
+        #[cfg(not(feature = "tui"))]
+        Some(Command::Tui) => {
+            usage_error(ErrorKind::InvalidSubcommand, "tui needs the tui feature")
+        }
+        Some(Command::Completions { shell }) => {
+            // generate panics on write errors, so buffer the script to report them
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "proc-route", &mut script);
+            io::stdout().write_all(&script)
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // the reader went away, as with `proc-route | head`
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("proc-route: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Whether to color the output: only for a terminal, and unless `--no-color` or a non-empty
+/// `NO_COLOR` (<https://no-color.org>) asks otherwise
+fn use_color(no_color: bool) -> bool {
+    !no_color
+        && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && io::stdout().is_terminal()
+}
+
+/// Exit with a usage error in the format of the ones clap reports itself
+pub(crate) fn usage_error(kind: ErrorKind, msg: impl Display) -> ! {
+    Cli::command().error(kind, msg).exit()
+}
+
+pub(crate) fn parse_filter_arg(expr: Option<&str>) -> Option<RouteFilter> {
+    let expr = expr?;
+    match expr.parse() {
+        Ok(filter) => Some(filter),
+        Err(err) => usage_error(
+            ErrorKind::ValueValidation,
+            format!("invalid filter '{expr}': {err}"),
+        ),
+    }
+}
+
+/// Reject an empty `--exec` and the options of watch whose features are not compiled in
+fn check_watch_args(args: &WatchArgs) {
+    if args
+        .exec
+        .as_ref()
+        .is_some_and(|command| command.trim().is_empty())
+    {
+        usage_error(ErrorKind::InvalidValue, "--exec takes a command");
+    }
+    let missing = if args.json_lines && !cfg!(feature = "serde") {
+        "--json-lines needs the serde feature"
+    } else if args.exec.is_some() && !cfg!(feature = "serde") {
+        "--exec needs the serde feature"
+    } else if args.dbus.is_some() && !cfg!(feature = "dbus") {
+        "--dbus needs the dbus feature"
+    } else if args.daemon && !cfg!(all(feature = "systemd", target_os = "linux")) {
+        "--daemon needs the systemd feature on Linux"
+    } else {
+        return;
+    };
+    usage_error(ErrorKind::ArgumentConflict, missing)
+}
+
+fn run(args: &PrintArgs, filter: Option<&RouteFilter>, color: bool) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    // grouping would break up the ranking of --top-used
+    let renderer = TableRenderer::new()
+        .color(color)
+        .group_by_interface(args.top_used.is_none());
+    if !args.ipv6_only {
+        let entries = get_ipv4_route_table()?.filter_map(skip_invalid);
+        let entries = select_routes(args, filter, entries);
+        print_heading(&mut stdout, args, color, "Kernel IPv4 routing table")?;
+        print_table(&mut stdout, args, &renderer, &entries)?;
+    }
+    if !args.ipv4_only {
+        let table = match get_ipv6_route_table() {
+            Ok(table) => table,
+            // a host without IPv6 only has the IPv4 table to show
+            Err(RouteParseError::Ipv6Unavailable) if !args.ipv6_only => return Ok(()),
+            Err(err) => return Err(io::Error::other(err)),
+        };
+        if !args.ipv6_only {
+            writeln!(stdout)?;
+        }
+        let entries = select_routes(args, filter, table.filter_map(skip_invalid));
+        print_heading(&mut stdout, args, color, "Kernel IPv6 routing table")?;
+        print_table(&mut stdout, args, &renderer, &entries)?;
+    }
+    Ok(())
+}
+
+/// The line above each table in the style of `route -n`, left out of CSV
+fn print_heading(
+    out: &mut impl Write,
+    args: &PrintArgs,
+    color: bool,
+    heading: &str,
+) -> io::Result<()> {
+    match (args.csv, color) {
+        (true, _) => Ok(()),
+        (false, true) => writeln!(out, "{BOLD}{heading}{RESET}"),
+        (false, false) => writeln!(out, "{heading}"),
+    }
+}
+
+fn print_table<E: TableRow + CsvRow>(
+    out: &mut impl Write,
+    args: &PrintArgs,
+    renderer: &TableRenderer,
+    entries: &[E],
+) -> io::Result<()> {
+    if args.csv {
+        to_csv(entries, out)
+    } else {
+        out.write_all(renderer.render(entries).as_bytes())
+    }
+}
+
+/// The routes to print: those passing `--filter`, and only the most used ones with `--top-used`
+fn select_routes<E: Route>(
+    args: &PrintArgs,
+    filter: Option<&RouteFilter>,
+    entries: impl Iterator<Item = E>,
+) -> Vec<E> {
+    let entries = entries.filter(|entry| filter.is_none_or(|f| f.matches(entry)));
+    match args.top_used {
+        Some(count) => top_routes_by_use(entries, count),
+        None => entries.collect(),
+    }
+}
+
+fn skip_invalid<E>(entry: Result<E, LineError>) -> Option<E> {
+    entry
+        .inspect_err(|err| eprintln!("proc-route: skipping {err}"))
+        .ok()
+}
+
+fn run_diff(old: &Path, new: &Path, color: bool) -> io::Result<()> {
+    let diff = load_capture(old)?.diff(&load_capture(new)?);
+    let mut stdout = io::stdout().lock();
+    if diff.is_empty() {
+        return writeln!(stdout, "no route changes");
+    }
+    for change in &diff.ipv4 {
+        writeln!(stdout, "{}", format_change(color, change))?;
+    }
+    for change in &diff.ipv6 {
+        writeln!(stdout, "{}", format_change(color, change))?;
+    }
+    Ok(())
+}
+
+fn run_watch(args: &WatchArgs, filter: Option<RouteFilter>, color: bool) -> io::Result<()> {
+    let watcher = PollingRouteWatcher::new(WATCH_INTERVAL);
+    #[cfg(feature = "serde")]
+    let watcher = match args.exec.clone() {
+        Some(command) => {
+            let run = move |event: &RouteEvent| exec_hook(&command, event);
+            match filter.clone() {
+                Some(filter) => watcher.on_matching_change(filter, run),
+                None => watcher.on_change(run),
+            }
+        }
+        None => watcher,
+    };
+    #[cfg(feature = "dbus")]
+    let watcher = match args.dbus {
+        Some(bus) => {
+            let bus = match bus {
+                MessageBus::Session => Bus::Session,
+                MessageBus::System => Bus::System,
+            };
+            RouteMonitorService::connect(bus)
+                .map_err(io::Error::other)?
+                .attach(watcher)
+        }
+        None => watcher,
+    };
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    let notifier = if args.daemon {
+        SystemdNotifier::from_env()?.map(Arc::new)
+    } else {
+        None
+    };
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    let watcher = match &notifier {
+        Some(notifier) => watcher.systemd_watchdog(notifier.clone()),
+        None => watcher,
+    };
+    let (_handle, changes) = watcher.start().map_err(io::Error::other)?;
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    if let Some(notifier) = &notifier {
+        notifier.ready()?;
+    }
+    let result = print_changes(args, filter.as_ref(), color, changes);
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    if let Some(notifier) = &notifier {
+        let _ = notifier.stopping();
+    }
+    result
+}
+
+/// Print the changes of watch until the watcher or stdout goes away
+fn print_changes(
+    args: &WatchArgs,
+    filter: Option<&RouteFilter>,
+    color: bool,
+    changes: Receiver<SnapshotDiff>,
+) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for diff in changes {
+        if args.json_lines {
+            #[cfg(feature = "serde")]
+            for event in diff.events() {
+                if passes(filter, &event.change) {
+                    writeln!(stdout, "{}", event_json(&event))?;
+                }
+            }
+            continue;
+        }
+        let timestamp = format_timestamp(diff.captured_at);
+        for change in diff.ipv4.iter().filter(|change| passes(filter, change)) {
+            writeln!(stdout, "{timestamp} {}", format_change(color, change))?;
+        }
+        for change in diff.ipv6.iter().filter(|change| passes(filter, change)) {
+            writeln!(stdout, "{timestamp} {}", format_change(color, change))?;
+        }
+    }
+    Ok(())
+}
+
+fn run_lookup(dest: IpAddr) -> io::Result<()> {
+    let tables = RouteTables::read(&ProcRoot::default()).map_err(io::Error::other)?;
+    let Some(route) = tables.lookup(dest) else {
+        return Err(io::Error::new(
+            io::ErrorKind::NetworkUnreachable,
+            format!("no route to {dest}"),
+        ));
+    };
+    let mut stdout = io::stdout().lock();
+    // the first line in the style of `ip route get`
+    let mut line = dest.to_string();
+    if let Some(gateway) = route.gateway() {
+        line += &format!(" via {gateway}");
+    }
+    writeln!(stdout, "{line} dev {}", route.interface())?;
+    let route = match &route {
+        RouteEntry::V4(entry) => describe(entry),
+        RouteEntry::V6(entry) => describe(entry),
+    };
+    writeln!(stdout, "    route {route}")
+}
+
+/// Whether `change` passes the `--filter` of watch, if any
+fn passes<E: Route>(filter: Option<&RouteFilter>, change: &RouteChange<E>) -> bool {
+    filter.is_none_or(|filter| change.matches(filter))
+}
+
+/// A change as a JSON object, tagged with the capture time and address family
+#[cfg(feature = "serde")]
+fn event_json(event: &RouteEvent) -> serde_json::Value {
+    let family = match event.family() {
+        AddressFamily::Ipv4 => "ipv4",
+        AddressFamily::Ipv6 => "ipv6",
+    };
+    let change = event.change.clone().map(|entry| match entry {
+        RouteEntry::V4(entry) => serde_json::to_value(entry),
+        RouteEntry::V6(entry) => serde_json::to_value(entry),
+    });
+    serde_json::json!({
+        "timestamp": format_timestamp(event.captured_at),
+        "family": family,
+        "change": change.map(|entry| entry.expect("route entries serialize")),
+    })
+}
+
+/// Run the `--exec` command for `event`, replacing `{json}` in its words with the event as JSON
+#[cfg(feature = "serde")]
+fn exec_hook(command: &str, event: &RouteEvent) {
+    let json = event_json(event).to_string();
+    let mut words = command
+        .split_whitespace()
+        .map(|word| word.replace("{json}", &json));
+    let Some(program) = words.next() else {
+        return;
+    };
+    match std::process::Command::new(&program).args(words).status() {
+        Ok(status) if !status.success() => eprintln!("proc-route: {program} failed: {status}"),
+        Ok(_) => {}
+        Err(err) => eprintln!("proc-route: cannot run {program}: {err}"),
+    }
+}
+
+/// RFC 3339 timestamp in UTC with millisecond precision
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+    // civil_from_days from Howard Hinnant's date algorithms, with the era starting on 0000-03-01
+    let days = days + 719_468;
+    let (era, day_of_era) = (days / 146_097, days % 146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Read a JSON snapshot, a single table file or a directory laid out like `/proc/net`
+fn load_capture(path: &Path) -> io::Result<Snapshot> {
+    if path.is_dir() {
+        return Snapshot::capture_from(path).map_err(|err| capture_error(path, err));
+    }
+    let contents = fs::read_to_string(path)?;
+    if contents.trim_start().starts_with('{') {
+        #[cfg(feature = "serde")]
+        return Snapshot::load(path).map_err(|err| capture_error(path, err));
+        #[cfg(not(feature = "serde"))]
+        return Err(capture_error(
+            path,
+            "reading JSON snapshots needs the serde feature",
+        ));
+    }
+    if contents.trim_start().starts_with('[') {
+        #[cfg(feature = "serde")]
+        return load_ip_json(path, &contents);
+        #[cfg(not(feature = "serde"))]
+        return Err(capture_error(
+            path,
+            "reading ip route JSON needs the serde feature",
+        ));
+    }
+    let mut snapshot = Snapshot {
+        captured_at: fs::metadata(path)?
+            .modified()
+            .unwrap_or_else(|_| SystemTime::now()),
+        ipv4: Vec::new(),
+        ipv6: Vec::new(),
+        arp: Vec::new(),
+    };
+    let reader = Cursor::new(contents.as_bytes());
+    // only /proc/net/route starts with a header line
+    if contents.starts_with("Iface") {
+        snapshot.ipv4 = Ipv4RouteTable::from_reader(reader, RouteTableOptions::new())
+            .filter_map(skip_invalid)
+            .collect();
+    } else {
+        snapshot.ipv6 = Ipv6RouteTable::from_reader(reader, RouteTableOptions::new())
+            .filter_map(skip_invalid)
+            .collect();
+    }
+    Ok(snapshot)
+}
+
+/// Read the output of `ip -j route show` or `ip -6 -j route show`, taking the routes as IPv6
+/// when they are not IPv4 ones
+#[cfg(feature = "serde")]
+fn load_ip_json(path: &Path, contents: &str) -> io::Result<Snapshot> {
+    let mut snapshot = Snapshot {
+        captured_at: fs::metadata(path)?
+            .modified()
+            .unwrap_or_else(|_| SystemTime::now()),
+        ipv4: Vec::new(),
+        ipv6: Vec::new(),
+        arp: Vec::new(),
+    };
+    match iproute2::parse_ipv4(contents) {
+        Ok(ipv4) => snapshot.ipv4 = ipv4,
+        Err(_) => {
+            snapshot.ipv6 =
+                iproute2::parse_ipv6(contents).map_err(|err| capture_error(path, err))?;
+        }
+    }
+    Ok(snapshot)
+}
+
+fn capture_error(path: &Path, err: impl std::fmt::Display) -> io::Error {
+    io::Error::other(format!("{}: {err}", path.display()))
+}
+
+fn format_change<E: Route + TableRow>(color: bool, change: &RouteChange<E>) -> String {
+    let (marker, ansi, line) = match change {
+        RouteChange::Added(entry) => ('+', GREEN, describe(entry)),
+        RouteChange::Removed(entry) => ('-', RED, describe(entry)),
+        RouteChange::Changed { old, new } => (
+            '~',
+            YELLOW,
+            format!("{} => {}", describe(old), describe(new)),
+        ),
+    };
+    if color {
+        format!("{ansi}{marker} {line}{RESET}")
+    } else {
+        format!("{marker} {line}")
+    }
+}
+
+/// One route in the style of `ip route`, followed by its `route -n` flag letters
+fn describe<E: Route + TableRow>(entry: &E) -> String {
+    let mut line = entry.network().to_string();
+    if let Some(gateway) = entry.gateway() {
+        line += &format!(" via {gateway}");
+    }
+    line += &format!(
+        " dev {} metric {} flags {}",
+        entry.interface(),
+        entry.metric(),
+        entry.cell(Column::Flags)
+    );
+    line
+}
@@ -0,0 +1,334 @@
+//! `proc-route tui`, a live view of both route tables
+
+use proc_route_parser::{
+    Column, PollingRouteWatcher, Route, RouteChange, RouteEntry, RouteFilter, RouteIndex, Snapshot,
+    SnapshotDiff, TableRow,
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table, TableState},
+};
+use std::{
+    cmp::Ordering,
+    io,
+    sync::mpsc::Receiver,
+    time::{Duration, Instant},
+};
+
+/// How often the watcher re-reads the tables
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long added and changed routes stay highlighted
+const HIGHLIGHT: Duration = Duration::from_secs(3);
+
+const COLUMNS: &[Column] = RouteEntry::DEFAULT_COLUMNS;
+
+const HELP: &str = "q quit  ↑↓ select  s sort  r reverse  / filter  c clear filter";
+
+pub fn run() -> io::Result<()> {
+    let snapshot = Snapshot::capture().map_err(io::Error::other)?;
+    let (_handle, changes) = PollingRouteWatcher::new(POLL_INTERVAL)
+        .start()
+        .map_err(io::Error::other)?;
+    let mut app = App::new(snapshot);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, &changes);
+    ratatui::restore();
+    result
+}
+
+struct App {
+    routes: Vec<RouteEntry>,
+    index: RouteIndex,
+    /// The routes passing the filter, in the shown order
+    rows: Vec<RouteEntry>,
+    state: TableState,
+    sort: Column,
+    descending: bool,
+    filter: Option<RouteFilter>,
+    /// The filter being typed after `/`
+    input: Option<String>,
+    /// Why the last typed filter was rejected
+    error: Option<String>,
+    /// Routes added or changed by the last updates, with when they were seen
+    recent: Vec<(RouteEntry, Instant)>,
+}
+
+impl App {
+    fn new(snapshot: Snapshot) -> Self {
+        let mut app = Self {
+            routes: Vec::new(),
+            index: RouteIndex::default(),
+            rows: Vec::new(),
+            state: TableState::default().with_selected(0),
+            sort: Column::Destination,
+            descending: false,
+            filter: None,
+            input: None,
+            error: None,
+            recent: Vec::new(),
+        };
+        app.load(snapshot);
+        app
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        changes: &Receiver<SnapshotDiff>,
+    ) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(Duration::from_millis(250))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key.code)
+            {
+                return Ok(());
+            }
+            let mut changed = false;
+            while let Ok(diff) = changes.try_recv() {
+                self.note_changes(&diff);
+                changed = true;
+            }
+            // the diff only lists the changes, so take the tables from a fresh read
+            if changed && let Ok(snapshot) = Snapshot::capture() {
+                self.load(snapshot);
+            }
+            self.recent.retain(|(_, seen)| seen.elapsed() < HIGHLIGHT);
+        }
+    }
+
+    fn load(&mut self, snapshot: Snapshot) {
+        self.index = RouteIndex::new(snapshot.ipv4.iter().cloned(), snapshot.ipv6.iter().cloned());
+        self.routes = snapshot
+            .ipv4
+            .into_iter()
+            .map(RouteEntry::V4)
+            .chain(snapshot.ipv6.into_iter().map(RouteEntry::V6))
+            .collect();
+        self.refresh();
+    }
+
+    fn note_changes(&mut self, diff: &SnapshotDiff) {
+        let now = Instant::now();
+        for event in diff.events() {
+            if let RouteChange::Added(route) | RouteChange::Changed { new: route, .. } =
+                event.change
+            {
+                self.recent.push((route, now));
+            }
+        }
+    }
+
+    /// Filter and sort the routes again, keeping the selection in range
+    fn refresh(&mut self) {
+        self.rows = self
+            .routes
+            .iter()
+            .filter(|route| {
+                self.filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.matches(*route))
+            })
+            .cloned()
+            .collect();
+        let sort = self.sort;
+        self.rows.sort_by(|a, b| compare(sort, a, b));
+        if self.descending {
+            self.rows.reverse();
+        }
+        let selected = self.state.selected().unwrap_or_default();
+        self.state
+            .select((!self.rows.is_empty()).then(|| selected.min(self.rows.len() - 1)));
+    }
+
+    /// Apply a key press, `false` to quit
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Some(input) = &mut self.input {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let input = self.input.take().unwrap_or_default();
+                    self.apply_filter(&input);
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return true;
+        }
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::PageDown => self.state.scroll_down_by(10),
+            KeyCode::PageUp => self.state.scroll_up_by(10),
+            KeyCode::Home | KeyCode::Char('g') => self.state.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.state.select_last(),
+            KeyCode::Char('s') => {
+                let next = COLUMNS
+                    .iter()
+                    .position(|&column| column == self.sort)
+                    .map_or(0, |i| i + 1);
+                self.sort = COLUMNS[next % COLUMNS.len()];
+                self.refresh();
+            }
+            KeyCode::Char('r') => {
+                self.descending = !self.descending;
+                self.refresh();
+            }
+            KeyCode::Char('/') => {
+                self.input = Some(
+                    self.filter
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                );
+            }
+            KeyCode::Char('c') => self.apply_filter(""),
+            _ => {}
+        }
+        true
+    }
+
+    fn apply_filter(&mut self, input: &str) {
+        self.error = None;
+        if input.trim().is_empty() {
+            self.filter = None;
+        } else {
+            match input.parse() {
+                Ok(filter) => self.filter = Some(filter),
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+        self.refresh();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(6),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let header = Row::new(COLUMNS.iter().map(|&column| {
+            let arrow = match (column == self.sort, self.descending) {
+                (false, _) => "",
+                (true, false) => " ▲",
+                (true, true) => " ▼",
+            };
+            format!("{}{arrow}", column.header())
+        }))
+        .bold();
+        let rows = self.rows.iter().map(|route| {
+            let row = Row::new(COLUMNS.iter().map(|&column| route.cell(column)));
+            if self.recent.iter().any(|(recent, _)| recent == route) {
+                row.fg(Color::Green)
+            } else if !route.is_up() || route.is_reject() {
+                row.fg(Color::DarkGray)
+            } else {
+                row
+            }
+        });
+        let widths = COLUMNS.iter().map(|&column| match column {
+            Column::Destination | Column::Gateway => Constraint::Fill(1),
+            Column::Iface => Constraint::Length(16),
+            Column::Flags => Constraint::Length(6),
+            _ => Constraint::Length(8),
+        });
+        let title = match &self.filter {
+            Some(filter) => format!(
+                " {} of {} routes matching {filter} ",
+                self.rows.len(),
+                self.routes.len()
+            ),
+            None => format!(" {} routes ", self.routes.len()),
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::new().reversed())
+            .block(Block::bordered().title(title));
+        frame.render_stateful_widget(table, table_area, &mut self.state);
+
+        let selected = self.state.selected().and_then(|i| self.rows.get(i));
+        let detail = Paragraph::new(selected.map(details).unwrap_or_default())
+            .block(Block::bordered().title(" Route "));
+        frame.render_widget(detail, detail_area);
+
+        let status = if let Some(input) = &self.input {
+            Line::from(format!("filter: {input}█"))
+        } else if let Some(error) = &self.error {
+            Line::from(error.as_str()).fg(Color::Red)
+        } else {
+            let defaults = self.index.interfaces_with_default_route();
+            let defaults = if defaults.is_empty() {
+                "no default route".to_string()
+            } else {
+                format!("default via {}", defaults.join(", "))
+            };
+            Line::from(format!("{defaults}  |  {HELP}"))
+        };
+        frame.render_widget(status, status_area);
+    }
+}
+
+/// The detail pane: the route in `ip route` style, its flags by name and the columns of its
+/// family that the table leaves out
+fn details(route: &RouteEntry) -> Vec<Line<'static>> {
+    let mut summary = route.network().to_string();
+    if let Some(gateway) = route.gateway() {
+        summary += &format!(" via {gateway}");
+    }
+    summary += &format!(" dev {} metric {}", route.interface(), route.metric());
+    let (flags, extra): (Vec<&str>, String) = match route {
+        RouteEntry::V4(entry) => (
+            entry.flags.iter_names().map(|(name, _)| name).collect(),
+            format!(
+                "MTU {}  Window {}  irtt {}",
+                entry.cell(Column::Mtu),
+                entry.cell(Column::Window),
+                entry.cell(Column::Irtt)
+            ),
+        ),
+        RouteEntry::V6(entry) => (
+            entry.flags.iter_names().map(|(name, _)| name).collect(),
+            format!(
+                "Source {}  Preference {:?}",
+                entry.cell(Column::Source),
+                entry.preference()
+            ),
+        ),
+    };
+    vec![
+        Line::from(summary).bold(),
+        Line::from(format!("Flags: {}", flags.join(" "))),
+        Line::from(extra),
+        Line::from(format!(
+            "Ref {}  Use {}",
+            route.ref_count(),
+            route.use_count()
+        )),
+    ]
+}
+
+/// Order of two rows by `column`, numerically for the numeric columns
+fn compare(column: Column, a: &RouteEntry, b: &RouteEntry) -> Ordering {
+    match column {
+        Column::Destination => a.network().cmp(&b.network()),
+        Column::Gateway => a.gateway().cmp(&b.gateway()),
+        Column::Metric => a.metric().cmp(&b.metric()),
+        Column::Ref => a.ref_count().cmp(&b.ref_count()),
+        Column::Use => a.use_count().cmp(&b.use_count()),
+        Column::Iface => a.interface().cmp(b.interface()),
+        _ => a.cell(column).cmp(&b.cell(column)),
+    }
+    .then_with(|| a.network().cmp(&b.network()))
+}
@@ -0,0 +1,294 @@
+//! Route tables read through `sysctl(CTL_NET, PF_ROUTE, 0, af, NET_RT_DUMP, 0)` on macOS and FreeBSD
+
+use crate::{
+    IfName, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, LineError,
+    RouteParseError, utils::NameInterner,
+};
+use std::{
+    ffi::CStr,
+    io, mem,
+    net::{Ipv4Addr, Ipv6Addr},
+    ptr,
+};
+
+/// `sizeof(struct rt_msghdr)`, after which the socket addresses of a message start
+#[cfg(target_os = "macos")]
+const RT_MSGHDR_LEN: usize = mem::size_of::<libc::rt_msghdr>();
+/// `sizeof(struct rt_msghdr)`: eight bytes of lengths and types, six ints and
+/// `rtm_inits` followed by the 14 `u_long`s of `struct rt_metrics`
+#[cfg(target_os = "freebsd")]
+const RT_MSGHDR_LEN: usize = 8 + 6 * 4 + 15 * mem::size_of::<libc::c_ulong>();
+
+/// Socket addresses in a routing message are padded to this alignment
+#[cfg(target_os = "macos")]
+const SA_ALIGN: usize = mem::size_of::<u32>();
+#[cfg(target_os = "freebsd")]
+const SA_ALIGN: usize = mem::size_of::<libc::c_long>();
+
+/// Get the IPv4 route table from the kernel routing socket dump.
+///
+/// The BSDs have no route metric or counters, so those are zero, and there is no MTU, window or IRTT.
+pub fn get_ipv4_route_table() -> io::Result<impl Iterator<Item = Result<Ipv4RouteEntry, LineError>>>
+{
+    let dump = route_dump(libc::AF_INET)?;
+    let mut names = NameInterner::default();
+    let entries: Vec<_> = messages(&dump)
+        .filter_map(|message| ipv4_entry(&message, &mut names))
+        .map(Ok)
+        .collect();
+    Ok(entries.into_iter())
+}
+
+/// Get the IPv6 route table from the kernel routing socket dump, see [`get_ipv4_route_table`]
+pub fn get_ipv6_route_table()
+-> Result<impl Iterator<Item = Result<Ipv6RouteEntry, LineError>>, RouteParseError> {
+    let dump = route_dump(libc::AF_INET6)?;
+    let mut names = NameInterner::default();
+    let entries: Vec<_> = messages(&dump)
+        .filter_map(|message| ipv6_entry(&message, &mut names))
+        .map(Ok)
+        .collect();
+    Ok(entries.into_iter())
+}
+
+fn route_dump(family: libc::c_int) -> io::Result<Vec<u8>> {
+    let mut mib = [
+        libc::CTL_NET,
+        libc::PF_ROUTE,
+        0,
+        family,
+        libc::NET_RT_DUMP,
+        0,
+    ];
+    loop {
+        let mut len = 0;
+        // SAFETY: a null buffer asks for the size of the dump only
+        let ret = unsafe {
+            libc::sysctl(
+                mib.as_mut_ptr(),
+                mib.len() as libc::c_uint,
+                ptr::null_mut(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buf = vec![0u8; len];
+        // SAFETY: the buffer holds `len` bytes and the kernel writes back how many it used
+        let ret = unsafe {
+            libc::sysctl(
+                mib.as_mut_ptr(),
+                mib.len() as libc::c_uint,
+                buf.as_mut_ptr().cast(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        match ret {
+            0 => {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            // routes were added between the two calls, ask for the size again
+            _ if io::Error::last_os_error().raw_os_error() == Some(libc::ENOMEM) => continue,
+            _ => return Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+/// The parts of an `rt_msghdr` and its socket addresses the entries are built from
+struct RouteMessage<'a> {
+    index: u16,
+    flags: i32,
+    dest: Option<&'a [u8]>,
+    gateway: Option<&'a [u8]>,
+    netmask: Option<&'a [u8]>,
+}
+
+/// Split a dump into its routing messages, stopping at the first truncated one
+fn messages(mut dump: &[u8]) -> impl Iterator<Item = RouteMessage<'_>> {
+    std::iter::from_fn(move || {
+        loop {
+            let msglen = usize::from(u16::from_ne_bytes(dump.get(..2)?.try_into().ok()?));
+            if msglen < RT_MSGHDR_LEN || msglen > dump.len() {
+                return None;
+            }
+            let (message, rest) = dump.split_at(msglen);
+            dump = rest;
+            if i32::from(message[3]) != libc::RTM_GET {
+                continue;
+            }
+            let field =
+                |offset: usize| i32::from_ne_bytes(message[offset..offset + 4].try_into().unwrap());
+            let mut parsed = RouteMessage {
+                index: u16::from_ne_bytes([message[4], message[5]]),
+                flags: field(8),
+                dest: None,
+                gateway: None,
+                netmask: None,
+            };
+            let addrs = field(12);
+            let mut sockaddrs = &message[RT_MSGHDR_LEN..];
+            for bit in 0..libc::RTAX_MAX {
+                if addrs & (1 << bit) == 0 {
+                    continue;
+                }
+                let Some(&len) = sockaddrs.first() else {
+                    break;
+                };
+                let len = usize::from(len);
+                let padded = if len == 0 {
+                    SA_ALIGN
+                } else {
+                    len.next_multiple_of(SA_ALIGN)
+                };
+                let sockaddr = &sockaddrs[..len.min(sockaddrs.len())];
+                match 1 << bit {
+                    libc::RTA_DST => parsed.dest = Some(sockaddr),
+                    libc::RTA_GATEWAY => parsed.gateway = Some(sockaddr),
+                    libc::RTA_NETMASK => parsed.netmask = Some(sockaddr),
+                    _ => {}
+                }
+                sockaddrs = sockaddrs.get(padded..).unwrap_or_default();
+            }
+            return Some(parsed);
+        }
+    })
+}
+
+/// The address bytes of a socket address of `family`
+fn sockaddr_bytes<const N: usize>(
+    sockaddr: &[u8],
+    family: libc::c_int,
+    offset: usize,
+) -> Option<[u8; N]> {
+    let sa_family = *sockaddr.get(1)?;
+    (i32::from(sa_family) == family).then(|| address_bytes(sockaddr, offset))
+}
+
+/// The address bytes at `offset`, zero-filled where the socket address was cut short.
+///
+/// Netmasks come that way, with the trailing zero bytes and often the family left out.
+fn address_bytes<const N: usize>(sockaddr: &[u8], offset: usize) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let available = sockaddr.get(offset..).unwrap_or_default();
+    let len = available.len().min(N);
+    bytes[..len].copy_from_slice(&available[..len]);
+    bytes
+}
+
+fn ipv4_entry(message: &RouteMessage, names: &mut NameInterner) -> Option<Ipv4RouteEntry> {
+    // sin_addr follows sin_len, sin_family and sin_port
+    const SIN_ADDR_OFFSET: usize = 4;
+    let dest = Ipv4Addr::from(sockaddr_bytes::<4>(
+        message.dest?,
+        libc::AF_INET,
+        SIN_ADDR_OFFSET,
+    )?);
+    let gateway = message
+        .gateway
+        .and_then(|gateway| sockaddr_bytes::<4>(gateway, libc::AF_INET, SIN_ADDR_OFFSET))
+        .map_or(Ipv4Addr::UNSPECIFIED, Ipv4Addr::from);
+    // host routes carry no netmask
+    let mask = match message
+        .netmask
+        .map(|mask| address_bytes::<4>(mask, SIN_ADDR_OFFSET))
+    {
+        Some(mask) => Ipv4Addr::from(mask),
+        None if message.flags & libc::RTF_HOST != 0 => Ipv4Addr::BROADCAST,
+        None => Ipv4Addr::UNSPECIFIED,
+    };
+    let mut ipv4_flags = Ipv4RouteFlags::from_bits_truncate(shared_flags(message.flags) as u16);
+    ipv4_flags.set(Ipv4RouteFlags::REJECT, is_reject(message.flags));
+    ipv4_flags.set(Ipv4RouteFlags::GATEWAY, !gateway.is_unspecified());
+    Some(Ipv4RouteEntry {
+        name: interface_name(message.index, names),
+        dest,
+        gateway,
+        flags: ipv4_flags,
+        ref_count: 0,
+        use_count: 0,
+        metric: 0,
+        mask,
+        mtu: None,
+        window: None,
+        irtt: None,
+    })
+}
+
+fn ipv6_entry(message: &RouteMessage, names: &mut NameInterner) -> Option<Ipv6RouteEntry> {
+    // sin6_addr follows sin6_len, sin6_family, sin6_port and sin6_flowinfo
+    const SIN6_ADDR_OFFSET: usize = 8;
+    let dest = sockaddr_bytes::<16>(message.dest?, libc::AF_INET6, SIN6_ADDR_OFFSET)?;
+    let next_hop = message
+        .gateway
+        .and_then(|gateway| sockaddr_bytes::<16>(gateway, libc::AF_INET6, SIN6_ADDR_OFFSET))
+        .map_or(Ipv6Addr::UNSPECIFIED, without_embedded_scope);
+    let dest_prefix = match message
+        .netmask
+        .map(|mask| address_bytes::<16>(mask, SIN6_ADDR_OFFSET))
+    {
+        Some(mask) => u128::from_be_bytes(mask).leading_ones() as u8,
+        None if message.flags & libc::RTF_HOST != 0 => 128,
+        None => 0,
+    };
+    let mut ipv6_flags = Ipv6RouteFlags::from_bits_truncate(shared_flags(message.flags) as u32);
+    ipv6_flags.set(Ipv6RouteFlags::REJECT, is_reject(message.flags));
+    ipv6_flags.set(Ipv6RouteFlags::GATEWAY, !next_hop.is_unspecified());
+    Some(Ipv6RouteEntry {
+        dest: without_embedded_scope(dest),
+        dest_prefix,
+        src: Ipv6Addr::UNSPECIFIED,
+        src_prefix: 0,
+        next_hop,
+        metric: 0,
+        ref_count: 0,
+        use_count: 0,
+        flags: ipv6_flags,
+        name: Some(interface_name(message.index, names)),
+    })
+}
+
+/// The `RTF_*` bits with the same value on Linux: up, gateway, host, dynamic and modified
+fn shared_flags(flags: i32) -> i32 {
+    flags
+        & (libc::RTF_UP
+            | libc::RTF_GATEWAY
+            | libc::RTF_HOST
+            | libc::RTF_DYNAMIC
+            | libc::RTF_MODIFIED)
+}
+
+fn is_reject(flags: i32) -> bool {
+    flags & (libc::RTF_REJECT | libc::RTF_BLACKHOLE) != 0
+}
+
+/// Clear the interface index the KAME stack embeds in the second word of link-local addresses
+fn without_embedded_scope(mut octets: [u8; 16]) -> Ipv6Addr {
+    let link_local_unicast = octets[0] == 0xfe && octets[1] & 0xc0 == 0x80;
+    let link_local_multicast = octets[0] == 0xff && octets[1] & 0x0f == 0x02;
+    if link_local_unicast || link_local_multicast {
+        octets[2] = 0;
+        octets[3] = 0;
+    }
+    Ipv6Addr::from(octets)
+}
+
+fn interface_name(index: u16, names: &mut NameInterner) -> IfName {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+    // SAFETY: the buffer is IF_NAMESIZE long, as if_indextoname requires
+    let name = unsafe { libc::if_indextoname(u32::from(index), buf.as_mut_ptr()) };
+    if name.is_null() {
+        return names.intern(&index.to_string());
+    }
+    // SAFETY: on success the buffer holds a NUL-terminated name
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_string_lossy();
+    if IfName::validate(&name).is_err() {
+        return names.intern(&index.to_string());
+    }
+    names.intern(&name)
+}
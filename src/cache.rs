@@ -0,0 +1,139 @@
+use crate::{
+    Ipv4RouteEntry, Ipv6RouteEntry, ProcRoot, Route, RouteEntry, RouteSource, SnapshotError,
+    effective_default_route_v4, effective_default_route_v6, select_best, select_best_from,
+};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Both route tables as read at one point in time
+#[derive(Debug, Clone)]
+pub struct RouteTables {
+    pub ipv4: Vec<Ipv4RouteEntry>,
+    pub ipv6: Vec<Ipv6RouteEntry>,
+    /// When the tables were read
+    pub read_at: Instant,
+}
+
+impl RouteTables {
+    /// Read both tables of `source`
+    pub fn read(source: &(impl RouteSource + ?Sized)) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            ipv4: source.ipv4_routes()?.collect::<Result<_, _>>()?,
+            ipv6: source.ipv6_routes()?.collect::<Result<_, _>>()?,
+            read_at: Instant::now(),
+        })
+    }
+
+    /// The route the kernel would use to reach `dest`, see [`select_best`]
+    pub fn lookup(&self, dest: IpAddr) -> Option<RouteEntry> {
+        match dest {
+            IpAddr::V4(_) => select_best(self.ipv4.iter().cloned(), dest).map(RouteEntry::V4),
+            IpAddr::V6(_) => select_best(self.ipv6.iter().cloned(), dest).map(RouteEntry::V6),
+        }
+    }
+
+    /// The IPv6 route the kernel would use to reach `dest` from `source`, see [`select_best_from`]
+    pub fn lookup_v6_from(
+        &self,
+        dest: Ipv6Addr,
+        source: Option<Ipv6Addr>,
+    ) -> Option<Ipv6RouteEntry> {
+        select_best_from(self.ipv6.iter().cloned(), dest, source)
+    }
+
+    /// Gateway of the IPv4 default route in use, or of the IPv6 one on a host without the former
+    pub fn default_gateway(&self) -> Option<IpAddr> {
+        effective_default_route_v4(self.ipv4.iter().cloned())
+            .winner
+            .and_then(|route| route.gateway())
+            .or_else(|| {
+                effective_default_route_v6(self.ipv6.iter().cloned())
+                    .winner
+                    .and_then(|route| route.gateway())
+            })
+    }
+}
+
+/// Hits and misses of a [`CachedRouteTable`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+    /// Reads answered from the cached tables
+    pub hits: u64,
+    /// Reads that had to read the tables from the source
+    pub misses: u64,
+}
+
+/// Route tables read from a [`RouteSource`] at most once per TTL.
+///
+/// Meant for hot paths doing many lookups: the tables are read on the first access after the TTL
+/// ran out or [`Self::invalidate`] was called, e.g. on every change reported by a
+/// [`PollingRouteWatcher`](crate::PollingRouteWatcher). Share it between threads behind an `Arc`.
+pub struct CachedRouteTable<S = ProcRoot> {
+    source: S,
+    ttl: Duration,
+    cached: Mutex<Option<Arc<RouteTables>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedRouteTable {
+    /// Cache the tables of `/proc`
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self::new(ProcRoot::default(), ttl)
+    }
+}
+
+impl<S: RouteSource> CachedRouteTable<S> {
+    pub fn new(source: S, ttl: Duration) -> Self {
+        Self {
+            source,
+            ttl,
+            cached: Mutex::new(None),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The cached tables, read again from the source once they are older than the TTL
+    pub fn tables(&self) -> Result<Arc<RouteTables>, SnapshotError> {
+        // held across the read so that concurrent misses read the source only once
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(tables) = &*cached
+            && tables.read_at.elapsed() < self.ttl
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(tables.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let tables = Arc::new(RouteTables::read(&self.source)?);
+        *cached = Some(tables.clone());
+        Ok(tables)
+    }
+
+    /// The route the kernel would use to reach `dest`, see [`select_best`]
+    pub fn lookup(&self, dest: IpAddr) -> Result<Option<RouteEntry>, SnapshotError> {
+        Ok(self.tables()?.lookup(dest))
+    }
+
+    /// Drop the cached tables, so that the next access reads the source
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{AddressFamily, PollingRouteWatcher, Route, RouteChange, RouteEvent};
+use zbus::blocking::Connection;
+
+/// The message bus a [`RouteMonitorService`] connects to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    /// The bus of the user's desktop session
+    Session,
+    /// The bus shared by the whole machine, which usually needs a policy file allowing the name
+    System,
+}
+
+/// Emits a D-Bus signal for every route change the watcher reports.
+///
+/// Each change is sent as `org.proc_route_parser.RouteMonitor.RouteChanged` from the object
+/// `/org/proc_route_parser/RouteMonitor`, with the arguments
+///
+/// - `change` (`s`): `added`, `removed` or `changed`
+/// - `family` (`s`): `ipv4` or `ipv6`
+/// - `destination` (`s`): the destination prefix, such as `0.0.0.0/0`
+/// - `gateway` (`s`): the next hop, empty for on-link routes
+/// - `interface` (`s`)
+/// - `metric` (`u`)
+///
+/// For a changed route they describe the route after the change.
+#[derive(Debug, Clone)]
+pub struct RouteMonitorService {
+    connection: Connection,
+}
+
+impl RouteMonitorService {
+    /// The well-known name the service requests, and the interface of its signals
+    pub const NAME: &str = "org.proc_route_parser.RouteMonitor";
+    pub const PATH: &str = "/org/proc_route_parser/RouteMonitor";
+    pub const SIGNAL: &str = "RouteChanged";
+
+    /// Connect to `bus` and own [`Self::NAME`] on it
+    pub fn connect(bus: Bus) -> zbus::Result<Self> {
+        let connection = match bus {
+            Bus::Session => Connection::session()?,
+            Bus::System => Connection::system()?,
+        };
+        connection.request_name(Self::NAME)?;
+        Ok(Self { connection })
+    }
+
+    /// Emit the signals over an existing connection, such as one to a private bus, without
+    /// requesting a name
+    pub fn with_connection(connection: Connection) -> Self {
+        Self { connection }
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Broadcast `event` as a [`Self::SIGNAL`] signal
+    pub fn emit(&self, event: &RouteEvent) -> zbus::Result<()> {
+        let change = match event.change {
+            RouteChange::Added(_) => "added",
+            RouteChange::Removed(_) => "removed",
+            RouteChange::Changed { .. } => "changed",
+        };
+        let family = match event.family() {
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+        };
+        let route = event.change.entry();
+        let gateway = route
+            .gateway()
+            .map(|gateway| gateway.to_string())
+            .unwrap_or_default();
+        self.connection.emit_signal(
+            None::<&str>,
+            Self::PATH,
+            Self::NAME,
+            Self::SIGNAL,
+            &(
+                change,
+                family,
+                route.network().to_string(),
+                gateway,
+                route.interface(),
+                route.metric().get(),
+            ),
+        )
+    }
+
+    /// Emit the changes `watcher` reports, from its thread
+    pub fn attach(&self, watcher: PollingRouteWatcher) -> PollingRouteWatcher {
+        let service = self.clone();
+        watcher.on_change(move |event| {
+            if let Err(_err) = service.emit(event) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_err, "failed to emit a route change signal");
+            }
+        })
+    }
+}
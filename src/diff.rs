@@ -0,0 +1,144 @@
+use crate::{
+    AddressFamily, Ipv4RouteEntry, Ipv6RouteEntry, Route, RouteEntry, RouteFilter, Snapshot,
+};
+use std::time::SystemTime;
+
+/// Difference in one route between two reads of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RouteChange<E> {
+    Added(E),
+    Removed(E),
+    /// Same destination, metric and interface, but a different gateway, flags or other settings
+    Changed {
+        old: E,
+        new: E,
+    },
+}
+
+impl<E> RouteChange<E> {
+    /// The entry as it is after the change, or as it was for a removed route
+    pub fn entry(&self) -> &E {
+        match self {
+            Self::Added(entry) | Self::Removed(entry) | Self::Changed { new: entry, .. } => entry,
+        }
+    }
+
+    /// The same change with `f` applied to its entries
+    pub fn map<T>(self, mut f: impl FnMut(E) -> T) -> RouteChange<T> {
+        match self {
+            Self::Added(entry) => RouteChange::Added(f(entry)),
+            Self::Removed(entry) => RouteChange::Removed(f(entry)),
+            Self::Changed { old, new } => RouteChange::Changed {
+                old: f(old),
+                new: f(new),
+            },
+        }
+    }
+}
+
+impl<E: Route> RouteChange<E> {
+    /// Whether the route passes `filter`, before or after a change
+    pub fn matches(&self, filter: &RouteFilter) -> bool {
+        match self {
+            Self::Added(entry) | Self::Removed(entry) => filter.matches(entry),
+            Self::Changed { old, new } => filter.matches(old) || filter.matches(new),
+        }
+    }
+}
+
+/// Compare two reads of a table.
+///
+/// Entries that are unchanged apart from their reference and use counters are not reported.
+pub fn diff_routes<E: Route + Clone>(old: &[E], new: &[E]) -> Vec<RouteChange<E>> {
+    let mut removed: Vec<&E> = old.iter().collect();
+    let mut added: Vec<&E> = Vec::new();
+    for entry in new {
+        match removed.iter().position(|old| old.same_route(entry)) {
+            Some(pos) => {
+                removed.swap_remove(pos);
+            }
+            None => added.push(entry),
+        }
+    }
+
+    let mut changes = Vec::new();
+    for new in added {
+        let same_slot = |old: &&E| {
+            old.network() == new.network()
+                && old.metric() == new.metric()
+                && old.interface() == new.interface()
+        };
+        match removed.iter().position(same_slot) {
+            Some(pos) => changes.push(RouteChange::Changed {
+                old: removed.swap_remove(pos).clone(),
+                new: new.clone(),
+            }),
+            None => changes.push(RouteChange::Added(new.clone())),
+        }
+    }
+    changes.extend(removed.into_iter().cloned().map(RouteChange::Removed));
+    changes
+}
+
+/// Route changes between two [`Snapshot`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotDiff {
+    /// Capture time of the newer snapshot
+    pub captured_at: SystemTime,
+    pub ipv4: Vec<RouteChange<Ipv4RouteEntry>>,
+    pub ipv6: Vec<RouteChange<Ipv6RouteEntry>>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    /// The changes of both families one by one, IPv4 first
+    pub fn events(&self) -> impl Iterator<Item = RouteEvent> + '_ {
+        let event = |change| RouteEvent {
+            captured_at: self.captured_at,
+            change,
+        };
+        let ipv4 = self
+            .ipv4
+            .iter()
+            .map(|change| change.clone().map(RouteEntry::V4));
+        let ipv6 = self
+            .ipv6
+            .iter()
+            .map(|change| change.clone().map(RouteEntry::V6));
+        ipv4.chain(ipv6).map(event)
+    }
+}
+
+/// A single route change of a [`SnapshotDiff`], as passed to watcher hooks
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteEvent {
+    /// Capture time of the snapshot that showed the change
+    pub captured_at: SystemTime,
+    pub change: RouteChange<RouteEntry>,
+}
+
+impl RouteEvent {
+    pub fn family(&self) -> AddressFamily {
+        match self.change.entry() {
+            RouteEntry::V4(_) => AddressFamily::Ipv4,
+            RouteEntry::V6(_) => AddressFamily::Ipv6,
+        }
+    }
+}
+
+impl Snapshot {
+    /// Route changes from this snapshot to `newer`
+    pub fn diff(&self, newer: &Snapshot) -> SnapshotDiff {
+        SnapshotDiff {
+            captured_at: newer.captured_at,
+            ipv4: diff_routes(&self.ipv4, &newer.ipv4),
+            ipv6: diff_routes(&self.ipv6, &newer.ipv6),
+        }
+    }
+}
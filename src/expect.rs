@@ -0,0 +1,288 @@
+use crate::{
+    AddressFamily, IpNet, Route, RouteEntry, RouteFilter, Snapshot, effective_default_route_v4,
+    effective_default_route_v6, select_best,
+};
+use std::{fmt, net::IpAddr};
+
+/// One assertion of a [`RouteExpectation`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteCheck {
+    /// There is a default route the kernel would use, in `family` or in either
+    HasDefaultRoute { family: Option<AddressFamily> },
+    /// The default route in use goes out of `iface`
+    DefaultViaIface {
+        iface: String,
+        family: Option<AddressFamily>,
+    },
+    /// The route the kernel would use to reach the address exists and is not a reject route
+    Reaches(IpAddr),
+    /// No route other than a default or reject route leads into the network
+    NoRouteTo(IpNet),
+    /// Some route passes `filter`
+    RouteMatching {
+        filter: RouteFilter,
+        family: Option<AddressFamily>,
+    },
+}
+
+impl RouteCheck {
+    /// Whether the tables of `snapshot` pass the check, with the routes that decided it
+    pub fn evaluate(&self, snapshot: &Snapshot) -> CheckOutcome {
+        let routes = match self {
+            Self::HasDefaultRoute { family } => default_routes(snapshot, *family),
+            Self::DefaultViaIface { iface, family } => {
+                let defaults = default_routes(snapshot, *family);
+                let (via_iface, others): (Vec<_>, Vec<_>) = defaults
+                    .into_iter()
+                    .partition(|route| route.interface() == iface);
+                // on failure, show where the default routes go instead
+                return CheckOutcome {
+                    check: self.clone(),
+                    passed: !via_iface.is_empty(),
+                    routes: if via_iface.is_empty() {
+                        others
+                    } else {
+                        via_iface
+                    },
+                };
+            }
+            Self::Reaches(dest) => {
+                let route = match dest {
+                    IpAddr::V4(_) => {
+                        select_best(snapshot.ipv4.iter().cloned(), *dest).map(RouteEntry::V4)
+                    }
+                    IpAddr::V6(_) => {
+                        select_best(snapshot.ipv6.iter().cloned(), *dest).map(RouteEntry::V6)
+                    }
+                };
+                route
+                    .filter(|route| !route.is_reject())
+                    .into_iter()
+                    .collect()
+            }
+            Self::NoRouteTo(network) => {
+                let family = match network.addr() {
+                    IpAddr::V4(_) => AddressFamily::Ipv4,
+                    IpAddr::V6(_) => AddressFamily::Ipv6,
+                };
+                let offending: Vec<_> = routes(snapshot, Some(family))
+                    .filter(|route| route.prefix_len() > 0 && !route.is_reject())
+                    .filter(|route| {
+                        let route = route.network();
+                        route.contains_net(network) || network.contains_net(&route)
+                    })
+                    .collect();
+                return CheckOutcome {
+                    check: self.clone(),
+                    passed: offending.is_empty(),
+                    routes: offending,
+                };
+            }
+            Self::RouteMatching { filter, family } => routes(snapshot, *family)
+                .filter(|route| filter.matches(route))
+                .collect(),
+        };
+        CheckOutcome {
+            check: self.clone(),
+            passed: !routes.is_empty(),
+            routes,
+        }
+    }
+
+    fn family_mut(&mut self) -> Option<&mut Option<AddressFamily>> {
+        match self {
+            Self::HasDefaultRoute { family }
+            | Self::DefaultViaIface { family, .. }
+            | Self::RouteMatching { family, .. } => Some(family),
+            Self::Reaches(_) | Self::NoRouteTo(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for RouteCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HasDefaultRoute { family } => write!(f, "{}default route", label(*family)),
+            Self::DefaultViaIface { iface, family } => {
+                write!(f, "{}default route via {iface}", label(*family))
+            }
+            Self::Reaches(dest) => write!(f, "route to {dest}"),
+            Self::NoRouteTo(network) => write!(f, "no route to {network}"),
+            Self::RouteMatching { filter, family } => {
+                write!(f, "{}route passing {filter}", label(*family))
+            }
+        }
+    }
+}
+
+/// A set of [`RouteCheck`]s the routing state is expected to pass, for tests and health checks.
+///
+/// ```
+/// # use proc_route_parser::{IpNet, RouteExpectation};
+/// # use std::net::Ipv4Addr;
+/// let link_local = IpNet::new(Ipv4Addr::new(169, 254, 0, 0).into(), 16).unwrap();
+/// let expectation = RouteExpectation::default_via_iface("wg0")
+///     .and(RouteExpectation::no_route_to(link_local));
+/// assert_eq!(expectation.checks().len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteExpectation {
+    checks: Vec<RouteCheck>,
+}
+
+impl RouteExpectation {
+    /// Expect a default route the kernel would use
+    pub fn has_default_route() -> Self {
+        RouteCheck::HasDefaultRoute { family: None }.into()
+    }
+
+    /// Expect the default route in use to go out of `iface`
+    pub fn default_via_iface(iface: impl Into<String>) -> Self {
+        RouteCheck::DefaultViaIface {
+            iface: iface.into(),
+            family: None,
+        }
+        .into()
+    }
+
+    /// Expect a usable route to `dest`
+    pub fn reaches(dest: IpAddr) -> Self {
+        RouteCheck::Reaches(dest).into()
+    }
+
+    /// Expect no route into `network` besides the default routes, such as none to the
+    /// link-local `169.254.0.0/16` on a host that should not fall back to it
+    pub fn no_route_to(network: IpNet) -> Self {
+        RouteCheck::NoRouteTo(network).into()
+    }
+
+    /// Expect some route to pass `filter`
+    pub fn route_matching(filter: RouteFilter) -> Self {
+        RouteCheck::RouteMatching {
+            filter,
+            family: None,
+        }
+        .into()
+    }
+
+    /// Both the checks of `self` and those of `other`
+    pub fn and(mut self, other: Self) -> Self {
+        self.checks.extend(other.checks);
+        self
+    }
+
+    /// Look only at the `family` table for the checks so far. Checks on an address or network
+    /// always use the table of its family.
+    pub fn family(mut self, family: AddressFamily) -> Self {
+        for check in &mut self.checks {
+            if let Some(check_family) = check.family_mut() {
+                *check_family = Some(family);
+            }
+        }
+        self
+    }
+
+    pub fn checks(&self) -> &[RouteCheck] {
+        &self.checks
+    }
+
+    /// Evaluate every check against the tables of `snapshot`
+    pub fn evaluate(&self, snapshot: &Snapshot) -> ExpectationReport {
+        ExpectationReport {
+            outcomes: self
+                .checks
+                .iter()
+                .map(|check| check.evaluate(snapshot))
+                .collect(),
+        }
+    }
+}
+
+impl From<RouteCheck> for RouteExpectation {
+    fn from(check: RouteCheck) -> Self {
+        Self {
+            checks: vec![check],
+        }
+    }
+}
+
+/// The result of one [`RouteCheck`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOutcome {
+    pub check: RouteCheck,
+    pub passed: bool,
+    /// The routes behind the result: the matching ones for a passed check, and for a failed
+    /// one the routes in the way, such as the default routes via other interfaces or the routes
+    /// into a network expected to have none
+    pub routes: Vec<RouteEntry>,
+}
+
+impl fmt::Display for CheckOutcome {
+    /// The check and the first routes behind its result, such as
+    /// `default route via wg0: 0.0.0.0/0 via 10.8.0.1 dev wg0`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.check)?;
+        for (i, route) in self.routes.iter().take(3).enumerate() {
+            let separator = if i == 0 { ": " } else { ", " };
+            write!(f, "{separator}{}", route.network())?;
+            if let Some(gateway) = route.gateway() {
+                write!(f, " via {gateway}")?;
+            }
+            write!(f, " dev {}", route.interface())?;
+        }
+        if self.routes.len() > 3 {
+            write!(f, " and {} more", self.routes.len() - 3)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcomes of a [`RouteExpectation`], one per check in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectationReport {
+    pub outcomes: Vec<CheckOutcome>,
+}
+
+impl ExpectationReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.passed)
+    }
+}
+
+/// The default routes the kernel would use, in the `family` table or in both
+fn default_routes(snapshot: &Snapshot, family: Option<AddressFamily>) -> Vec<RouteEntry> {
+    let mut defaults = Vec::new();
+    if family != Some(AddressFamily::Ipv6) {
+        let selection = effective_default_route_v4(snapshot.ipv4.iter().cloned());
+        defaults.extend(selection.winner.map(RouteEntry::V4));
+    }
+    if family != Some(AddressFamily::Ipv4) {
+        let selection = effective_default_route_v6(snapshot.ipv6.iter().cloned());
+        defaults.extend(selection.winner.map(RouteEntry::V6));
+    }
+    defaults
+}
+
+fn routes(
+    snapshot: &Snapshot,
+    family: Option<AddressFamily>,
+) -> impl Iterator<Item = RouteEntry> + '_ {
+    let ipv4 = snapshot.ipv4.iter().cloned().map(RouteEntry::V4);
+    let ipv6 = snapshot.ipv6.iter().cloned().map(RouteEntry::V6);
+    let ipv4 = ipv4.filter(move |_| family != Some(AddressFamily::Ipv6));
+    let ipv6 = ipv6.filter(move |_| family != Some(AddressFamily::Ipv4));
+    ipv4.chain(ipv6)
+}
+
+fn label(family: Option<AddressFamily>) -> &'static str {
+    match family {
+        None => "",
+        Some(AddressFamily::Ipv4) => "IPv4 ",
+        Some(AddressFamily::Ipv6) => "IPv6 ",
+    }
+}
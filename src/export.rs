@@ -0,0 +1,141 @@
+//! Serialization of route tables into formats for other tools
+
+use crate::{Ipv4RouteEntry, Ipv6RouteEntry, Route};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{self, Write},
+    net::IpAddr,
+};
+
+/// Entries that [`to_csv`] can write
+pub trait CsvRow {
+    /// Comma-separated column names
+    const CSV_HEADER: &'static str;
+
+    /// Write the entry as one record, without the line terminator
+    fn write_csv_row(&self, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+impl CsvRow for Ipv4RouteEntry {
+    const CSV_HEADER: &'static str =
+        "iface,destination,prefix_len,gateway,flags,metric,ref_count,use_count,mtu,window,irtt";
+
+    fn write_csv_row(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&self.name),
+            self.dest,
+            self.prefix_len(),
+            self.gateway,
+            self.flags,
+            self.metric,
+            self.ref_count,
+            self.use_count,
+            csv_optional(self.mtu),
+            csv_optional(self.window),
+            csv_optional(self.irtt),
+        )
+    }
+}
+
+impl CsvRow for Ipv6RouteEntry {
+    const CSV_HEADER: &'static str = "iface,destination,prefix_len,source,source_prefix_len,next_hop,flags,metric,ref_count,use_count";
+
+    fn write_csv_row(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{}",
+            csv_field(self.interface()),
+            self.dest,
+            self.dest_prefix,
+            self.src,
+            self.src_prefix,
+            self.next_hop,
+            self.flags,
+            self.metric,
+            self.ref_count,
+            self.use_count,
+        )
+    }
+}
+
+/// Write a header line followed by one CRLF-terminated record per entry, as RFC 4180 specifies.
+///
+/// Addresses are in their canonical text form (RFC 5952 for IPv6), the flags are the
+/// `|`-separated flag names and an absent MTU, window or IRTT is an empty field.
+pub fn to_csv<E: CsvRow, W: Write>(entries: &[E], mut writer: W) -> io::Result<()> {
+    write!(writer, "{}\r\n", E::CSV_HEADER)?;
+    for entry in entries {
+        entry.write_csv_row(&mut writer)?;
+        writer.write_all(b"\r\n")?;
+    }
+    writer.flush()
+}
+
+/// Quote a free-form text field if it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// An empty field for a value the entry does not have
+fn csv_optional(value: Option<u32>) -> String {
+    value.map_or_else(String::new, |value| value.to_string())
+}
+
+/// Render the entries as a Graphviz `digraph` of the host's routing topology.
+///
+/// The host, its interfaces and the gateways are the nodes. On-link routes are edges from the host
+/// to their interface, gateway routes are edges from the interface to the gateway, and each edge is
+/// labeled with the prefixes and metrics of the routes it stands for.
+pub fn dot<R: Route>(entries: &[R]) -> String {
+    let mut edges: BTreeMap<(&str, Option<IpAddr>), Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        edges
+            .entry((entry.interface(), entry.gateway()))
+            .or_default()
+            .push(format!("{} metric {}", entry.network(), entry.metric()));
+    }
+
+    let mut out = String::from("digraph routes {\n    rankdir=LR;\n    \"host\" [shape=box];\n");
+    let mut interfaces: Vec<&str> = edges.keys().map(|(iface, _)| *iface).collect();
+    interfaces.dedup();
+    for iface in interfaces {
+        let _ = writeln!(out, "    \"iface:{0}\" [label=\"{0}\"];", dot_escape(iface));
+    }
+    for (iface, gateway) in edges.keys() {
+        if let Some(gateway) = gateway {
+            let _ = writeln!(
+                out,
+                "    \"gw:{}:{gateway}\" [label=\"{gateway}\", shape=diamond];",
+                dot_escape(iface)
+            );
+        }
+    }
+    for ((iface, gateway), labels) in &edges {
+        let iface = dot_escape(iface);
+        let label = labels.join("\\n");
+        let _ = match gateway {
+            Some(gateway) => writeln!(
+                out,
+                "    \"iface:{iface}\" -> \"gw:{iface}:{gateway}\" [label=\"{label}\"];"
+            ),
+            None => writeln!(
+                out,
+                "    \"host\" -> \"iface:{iface}\" [label=\"{label}\"];"
+            ),
+        };
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Escape a name for use inside a quoted DOT identifier
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
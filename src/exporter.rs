@@ -0,0 +1,107 @@
+use crate::{Ipv6RouteFlags, ProcRoot};
+use prometheus::{
+    IntGaugeVec, Opts,
+    core::{Collector, Desc},
+    proto::MetricFamily,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Prometheus collector re-reading the route tables on every scrape.
+///
+/// Exposes `route_entries_total{family,table,iface}` and `default_route_present{family}`.
+/// A table that can't be read contributes no samples to the scrape.
+pub struct RouteMetricsCollector {
+    entries: IntGaugeVec,
+    default_route: IntGaugeVec,
+    root: ProcRoot,
+    // keeps concurrent scrapes from interleaving reset and collect
+    scrape: Mutex<()>,
+}
+
+impl RouteMetricsCollector {
+    /// Collect the tables of `/proc`
+    pub fn new() -> prometheus::Result<Self> {
+        Self::with_root(ProcRoot::default())
+    }
+
+    /// Collect the tables below `root`, e.g. a container's or a captured `/proc`
+    pub fn with_root(root: ProcRoot) -> prometheus::Result<Self> {
+        let entries = IntGaugeVec::new(
+            Opts::new("route_entries_total", "Number of routes per interface"),
+            &["family", "table", "iface"],
+        )?;
+        let default_route = IntGaugeVec::new(
+            Opts::new(
+                "default_route_present",
+                "Whether a default route is installed",
+            ),
+            &["family"],
+        )?;
+        Ok(Self {
+            entries,
+            default_route,
+            root,
+            scrape: Mutex::new(()),
+        })
+    }
+
+    fn refresh(&self) {
+        self.entries.reset();
+        self.default_route.reset();
+        if let Ok(table) = self.root.ipv4_route_table() {
+            let mut has_default = false;
+            let mut per_iface = HashMap::new();
+            for entry in table.flatten() {
+                has_default |= entry.is_default() && !entry.is_reject();
+                *per_iface.entry(entry.name.into()).or_insert(0) += 1;
+            }
+            self.publish("ipv4", has_default, per_iface);
+        }
+        if let Ok(table) = self.root.ipv6_route_table() {
+            let mut has_default = false;
+            let mut per_iface = HashMap::new();
+            for entry in table.flatten() {
+                // the kernel keeps an unreachable ::/0 placeholder on lo
+                has_default |=
+                    entry.dest_prefix == 0 && !entry.flags.contains(Ipv6RouteFlags::REJECT);
+                let iface = entry.name.map_or_else(|| "".into(), Into::into);
+                *per_iface.entry(iface).or_insert(0) += 1;
+            }
+            self.publish("ipv6", has_default, per_iface);
+        }
+    }
+
+    fn publish(&self, family: &str, has_default: bool, per_iface: HashMap<Arc<str>, i64>) {
+        // /proc only exposes the main routing table
+        for (iface, count) in per_iface {
+            self.entries
+                .with_label_values(&[family, "main", &iface])
+                .set(count);
+        }
+        self.default_route
+            .with_label_values(&[family])
+            .set(has_default as i64);
+    }
+}
+
+impl Collector for RouteMetricsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        let mut descs = self.entries.desc();
+        descs.extend(self.default_route.desc());
+        descs
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let _guard = self
+            .scrape
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.refresh();
+        let mut families = self.entries.collect();
+        families.extend(self.default_route.collect());
+        families
+    }
+}
@@ -0,0 +1,205 @@
+//! C bindings, see `include/proc_route_parser.h`
+
+use crate::{
+    Ipv4RouteEntry, Ipv6RouteEntry, LineError, RouteParseError, get_ipv4_route_table,
+    get_ipv6_route_table,
+};
+use std::{
+    ffi::c_int,
+    io,
+    ptr::{self, slice_from_raw_parts_mut},
+};
+
+/// Interface name buffer size, including the NUL terminator (`IFNAMSIZ`)
+pub const PRP_IFNAMSIZ: usize = 16;
+
+/// Returned in `error` when a line of the route file is malformed
+pub const PRP_ERR_PARSE: c_int = -1;
+
+/// Returned in `error` by `prp_get_ipv6_routes` when IPv6 is disabled on the host
+pub const PRP_ERR_IPV6_UNAVAILABLE: c_int = -2;
+
+#[repr(C)]
+pub struct PrpIpv4Route {
+    /// NUL-terminated interface name
+    pub name: [u8; PRP_IFNAMSIZ],
+    /// Addresses in network byte order
+    pub dest: [u8; 4],
+    pub gateway: [u8; 4],
+    pub mask: [u8; 4],
+    pub flags: u16,
+    pub ref_count: u32,
+    pub use_count: u32,
+    pub metric: u32,
+    /// As printed in the route line, the kernel sets no `RTF_MTU`, `RTF_WINDOW` or `RTF_IRTT` bit
+    pub mtu: u32,
+    pub window: u32,
+    pub irtt: u32,
+}
+
+#[repr(C)]
+pub struct PrpIpv6Route {
+    /// Addresses in network byte order
+    pub dest: [u8; 16],
+    pub dest_prefix: u8,
+    pub src: [u8; 16],
+    pub src_prefix: u8,
+    pub next_hop: [u8; 16],
+    pub metric: u32,
+    pub ref_count: u32,
+    pub use_count: u32,
+    pub flags: u32,
+    /// NUL-terminated interface name
+    pub name: [u8; PRP_IFNAMSIZ],
+}
+
+/// Routes owned by the library, release them with the matching `prp_free_*` function.
+///
+/// On failure `routes` is NULL and `error` holds a positive `errno` value or `PRP_ERR_PARSE`.
+#[repr(C)]
+pub struct PrpIpv4RouteArray {
+    pub routes: *mut PrpIpv4Route,
+    pub len: usize,
+    pub error: c_int,
+}
+
+/// Routes owned by the library, release them with the matching `prp_free_*` function.
+///
+/// On failure `routes` is NULL and `error` holds a positive `errno` value, `PRP_ERR_PARSE` or
+/// `PRP_ERR_IPV6_UNAVAILABLE`.
+#[repr(C)]
+pub struct PrpIpv6RouteArray {
+    pub routes: *mut PrpIpv6Route,
+    pub len: usize,
+    pub error: c_int,
+}
+
+impl From<&Ipv4RouteEntry> for PrpIpv4Route {
+    fn from(entry: &Ipv4RouteEntry) -> Self {
+        Self {
+            name: c_if_name(&entry.name),
+            dest: entry.dest.octets(),
+            gateway: entry.gateway.octets(),
+            mask: entry.mask.octets(),
+            flags: entry.flags.bits(),
+            ref_count: entry.ref_count,
+            use_count: entry.use_count,
+            metric: entry.metric,
+            mtu: entry.mtu_raw(),
+            window: entry.window_raw(),
+            irtt: entry.irtt_raw(),
+        }
+    }
+}
+
+impl From<&Ipv6RouteEntry> for PrpIpv6Route {
+    fn from(entry: &Ipv6RouteEntry) -> Self {
+        Self {
+            dest: entry.dest.octets(),
+            dest_prefix: entry.dest_prefix,
+            src: entry.src.octets(),
+            src_prefix: entry.src_prefix,
+            next_hop: entry.next_hop.octets(),
+            metric: entry.metric,
+            ref_count: entry.ref_count,
+            use_count: entry.use_count,
+            flags: entry.flags.bits(),
+            name: c_if_name(entry.name.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+/// Read and parse `/proc/net/route`
+#[unsafe(no_mangle)]
+pub extern "C" fn prp_get_ipv4_routes() -> PrpIpv4RouteArray {
+    match collect(
+        get_ipv4_route_table().map_err(RouteParseError::from),
+        |entry| PrpIpv4Route::from(&entry),
+    ) {
+        Ok(routes) => {
+            let len = routes.len();
+            PrpIpv4RouteArray {
+                routes: Box::into_raw(routes).cast(),
+                len,
+                error: 0,
+            }
+        }
+        Err(error) => PrpIpv4RouteArray {
+            routes: ptr::null_mut(),
+            len: 0,
+            error,
+        },
+    }
+}
+
+/// Read and parse `/proc/net/ipv6_route`
+#[unsafe(no_mangle)]
+pub extern "C" fn prp_get_ipv6_routes() -> PrpIpv6RouteArray {
+    match collect(get_ipv6_route_table(), |entry| PrpIpv6Route::from(&entry)) {
+        Ok(routes) => {
+            let len = routes.len();
+            PrpIpv6RouteArray {
+                routes: Box::into_raw(routes).cast(),
+                len,
+                error: 0,
+            }
+        }
+        Err(error) => PrpIpv6RouteArray {
+            routes: ptr::null_mut(),
+            len: 0,
+            error,
+        },
+    }
+}
+
+/// Release an array returned by `prp_get_ipv4_routes`
+///
+/// # Safety
+/// `array` must come from `prp_get_ipv4_routes` and must not be freed twice.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prp_free_ipv4_routes(array: PrpIpv4RouteArray) {
+    if !array.routes.is_null() {
+        drop(unsafe { Box::from_raw(slice_from_raw_parts_mut(array.routes, array.len)) });
+    }
+}
+
+/// Release an array returned by `prp_get_ipv6_routes`
+///
+/// # Safety
+/// `array` must come from `prp_get_ipv6_routes` and must not be freed twice.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prp_free_ipv6_routes(array: PrpIpv6RouteArray) {
+    if !array.routes.is_null() {
+        drop(unsafe { Box::from_raw(slice_from_raw_parts_mut(array.routes, array.len)) });
+    }
+}
+
+fn collect<E, T>(
+    table: Result<impl Iterator<Item = Result<E, LineError>>, RouteParseError>,
+    convert: impl Fn(E) -> T,
+) -> Result<Box<[T]>, c_int> {
+    let table = table.map_err(|err| error_code(&err))?;
+    table
+        .map(|entry| entry.map(&convert).map_err(|err| error_code(&err.source)))
+        .collect()
+}
+
+fn io_error_code(err: &io::Error) -> c_int {
+    err.raw_os_error().unwrap_or(libc::EIO)
+}
+
+fn error_code(err: &RouteParseError) -> c_int {
+    match err {
+        RouteParseError::Io(err) => io_error_code(err),
+        RouteParseError::Ipv6Unavailable => PRP_ERR_IPV6_UNAVAILABLE,
+        _ => PRP_ERR_PARSE,
+    }
+}
+
+/// Copy an interface name into a NUL-terminated buffer, truncating overlong names
+fn c_if_name(name: &str) -> [u8; PRP_IFNAMSIZ] {
+    let mut buf = [0u8; PRP_IFNAMSIZ];
+    let len = name.len().min(PRP_IFNAMSIZ - 1);
+    buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+    buf
+}
@@ -0,0 +1,399 @@
+use crate::{IpNet, Ipv4RouteFlags, Ipv6RouteFlags, Route};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{cmp::Ordering, fmt, net::IpAddr, str::FromStr};
+use thiserror::Error;
+
+/// Fields a filter can compare, as listed in [`parse_filter`]
+const FIELDS: &str = "iface, dest, gateway, metric, prefix, flags or family";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FilterError {
+    #[error("The filter ends where {expected} was expected")]
+    UnexpectedEnd { expected: &'static str },
+
+    #[error("Unexpected {token:?} at byte {position}, expected {expected}")]
+    UnexpectedToken {
+        token: String,
+        position: usize,
+        expected: &'static str,
+    },
+
+    #[error("Unknown field {0:?}, expected {FIELDS}")]
+    UnknownField(String),
+
+    #[error("Field {field} cannot be compared with {op}")]
+    UnsupportedOperator { field: &'static str, op: String },
+
+    #[error("Invalid {field} {value:?}")]
+    InvalidValue { field: &'static str, value: String },
+
+    #[error("Unknown route flag {0:?}")]
+    UnknownFlag(String),
+}
+
+/// A route filter written in a small expression language, see [`parse_filter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct RouteFilter {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Iface {
+        equal: bool,
+        name: String,
+    },
+    Dest(NetTest),
+    Gateway(NetTest),
+    /// `gateway == none` or `gateway != none`
+    OnLink {
+        equal: bool,
+    },
+    Metric(Ordering, bool, u32),
+    Prefix(Ordering, bool, u8),
+    Flag(String),
+    Family {
+        equal: bool,
+        ipv4: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NetTest {
+    /// The address or prefix is the network
+    Equal(bool, IpNet),
+    /// The address or prefix lies within the network
+    Within(IpNet),
+    /// The network holds the address
+    Contains(IpAddr),
+}
+
+/// Parse a route filter such as `iface == eth0 && flags has GATEWAY && dest within 10.0.0.0/8`.
+///
+/// A filter combines comparisons with `&&`, `||`, `!` and parentheses, `&&` binding tighter
+/// than `||`. The comparisons are:
+///
+/// - `iface == NAME`, `iface != NAME`
+/// - `dest == PREFIX`, `dest != PREFIX`, `dest within PREFIX` and `dest contains ADDRESS`, where
+///   `dest` is the destination prefix of the route and a bare address is a host prefix
+/// - the same for `gateway`, plus `gateway == none` for on-link routes, which pass every
+///   `gateway != ADDRESS`
+/// - `metric` and `prefix` (the prefix length) with `==`, `!=`, `<`, `<=`, `>` or `>=`
+/// - `flags has NAME`, with a flag name of either family such as `UP`, `REJECT` or `ADDR_CONF`
+/// - `family == ipv4`, `family == ipv6`
+pub fn parse_filter(filter: &str) -> Result<RouteFilter, FilterError> {
+    let mut parser = Parser {
+        tokens: tokenize(filter)?,
+        next: 0,
+    };
+    let expr = parser.or()?;
+    if let Some(token) = parser.peek() {
+        return Err(token.unexpected("&&, || or the end of the filter"));
+    }
+    Ok(RouteFilter {
+        source: filter.into(),
+        expr,
+    })
+}
+
+impl RouteFilter {
+    /// Whether `route` passes the filter
+    pub fn matches(&self, route: &impl Route) -> bool {
+        self.expr.matches(route)
+    }
+
+    /// The filter as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Expr {
+    fn matches(&self, route: &impl Route) -> bool {
+        match self {
+            Self::And(lhs, rhs) => lhs.matches(route) && rhs.matches(route),
+            Self::Or(lhs, rhs) => lhs.matches(route) || rhs.matches(route),
+            Self::Not(expr) => !expr.matches(route),
+            Self::Iface { equal, name } => (route.interface() == name) == *equal,
+            Self::Dest(test) => test.matches(route.network()),
+            Self::Gateway(test) => match route.gateway() {
+                Some(gateway) => test.matches(host_net(gateway)),
+                // an on-link route has no gateway, so it differs from every address
+                None => matches!(test, NetTest::Equal(false, _)),
+            },
+            Self::OnLink { equal } => route.gateway().is_none() == *equal,
+            Self::Metric(ordering, equal, metric) => {
+                compare(route.metric().get().cmp(metric), *ordering, *equal)
+            }
+            Self::Prefix(ordering, equal, len) => {
+                compare(route.prefix_len().cmp(len), *ordering, *equal)
+            }
+            Self::Flag(name) => route.has_flag(name),
+            Self::Family { equal, ipv4 } => (route.destination().is_ipv4() == *ipv4) == *equal,
+        }
+    }
+}
+
+impl NetTest {
+    fn matches(self, net: IpNet) -> bool {
+        match self {
+            Self::Equal(equal, other) => (net == other) == equal,
+            Self::Within(other) => other.contains_net(&net),
+            Self::Contains(addr) => net.contains(addr),
+        }
+    }
+}
+
+/// Whether `actual` satisfies the operator `ordering` and `equal` were parsed from: `<=` is
+/// `(Less, true)`, `!=` is `(Equal, false)`
+fn compare(actual: Ordering, ordering: Ordering, equal: bool) -> bool {
+    match ordering {
+        Ordering::Equal => (actual == Ordering::Equal) == equal,
+        _ => actual == ordering || equal && actual == Ordering::Equal,
+    }
+}
+
+fn host_net(addr: IpAddr) -> IpNet {
+    IpNet::new(addr, if addr.is_ipv4() { 32 } else { 128 }).expect("host prefix length is valid")
+}
+
+impl FromStr for RouteFilter {
+    type Err = FilterError;
+
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        parse_filter(filter)
+    }
+}
+
+impl TryFrom<String> for RouteFilter {
+    type Error = FilterError;
+
+    fn try_from(filter: String) -> Result<Self, Self::Error> {
+        filter.parse()
+    }
+}
+
+impl From<RouteFilter> for String {
+    fn from(filter: RouteFilter) -> Self {
+        filter.source
+    }
+}
+
+impl fmt::Display for RouteFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug)]
+struct Token<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Token<'_> {
+    fn unexpected(&self, expected: &'static str) -> FilterError {
+        FilterError::UnexpectedToken {
+            token: self.text.into(),
+            position: self.position,
+            expected,
+        }
+    }
+}
+
+/// Split into operators, parentheses and words, which run until whitespace or an operator
+fn tokenize(filter: &str) -> Result<Vec<Token<'_>>, FilterError> {
+    const OPERATORS: [&str; 11] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")"];
+    let mut tokens = Vec::new();
+    let mut rest = filter.trim_start();
+    while !rest.is_empty() {
+        let position = filter.len() - rest.len();
+        let len = match OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            Some(op) => op.len(),
+            None => rest
+                .find(|c: char| c.is_whitespace() || "&|=!<>()".contains(c))
+                .unwrap_or(rest.len()),
+        };
+        if len == 0 {
+            // a lone `&`, `|` or `=`
+            let text = &rest[..rest.chars().next().map_or(0, char::len_utf8)];
+            return Err(Token { text, position }.unexpected("an operator such as && or =="));
+        }
+        tokens.push(Token {
+            text: &rest[..len],
+            position,
+        });
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    next: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self, expected: &'static str) -> Result<&Token<'a>, FilterError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .ok_or(FilterError::UnexpectedEnd { expected })?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let found = self.peek().is_some_and(|token| token.text == text);
+        self.next += usize::from(found);
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.and()?;
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.unary()?;
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.or()?;
+            let token = self.advance(")")?;
+            if token.text != ")" {
+                return Err(token.unexpected(")"));
+            }
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, FilterError> {
+        let field = self.advance("a field")?.text;
+        let field = match field {
+            "iface" | "dev" => "iface",
+            "dest" | "dst" => "dest",
+            "gateway" | "via" => "gateway",
+            "metric" => "metric",
+            "prefix" => "prefix",
+            "flags" => "flags",
+            "family" => "family",
+            _ => return Err(FilterError::UnknownField(field.into())),
+        };
+        let op = self.advance("an operator")?.text;
+        let value = self.advance("a value")?.text;
+        let unsupported = || FilterError::UnsupportedOperator {
+            field,
+            op: op.into(),
+        };
+        let invalid = || FilterError::InvalidValue {
+            field,
+            value: value.into(),
+        };
+        let expr = match field {
+            "iface" => Expr::Iface {
+                equal: equality(op).ok_or_else(unsupported)?,
+                name: value.into(),
+            },
+            "gateway" if value == "none" => Expr::OnLink {
+                equal: equality(op).ok_or_else(unsupported)?,
+            },
+            "dest" | "gateway" => {
+                let test = match op {
+                    "contains" => NetTest::Contains(value.parse().map_err(|_| invalid())?),
+                    "within" => NetTest::Within(parse_net(value).ok_or_else(invalid)?),
+                    _ => NetTest::Equal(
+                        equality(op).ok_or_else(unsupported)?,
+                        parse_net(value).ok_or_else(invalid)?,
+                    ),
+                };
+                if field == "dest" {
+                    Expr::Dest(test)
+                } else {
+                    Expr::Gateway(test)
+                }
+            }
+            "metric" => {
+                let (ordering, equal) = ordering(op).ok_or_else(unsupported)?;
+                Expr::Metric(ordering, equal, value.parse().map_err(|_| invalid())?)
+            }
+            "prefix" => {
+                let (ordering, equal) = ordering(op).ok_or_else(unsupported)?;
+                Expr::Prefix(ordering, equal, value.parse().map_err(|_| invalid())?)
+            }
+            "flags" => {
+                if op != "has" {
+                    return Err(unsupported());
+                }
+                let name = value.to_ascii_uppercase();
+                if Ipv4RouteFlags::from_name(&name).is_none()
+                    && Ipv6RouteFlags::from_name(&name).is_none()
+                {
+                    return Err(FilterError::UnknownFlag(value.into()));
+                }
+                Expr::Flag(name)
+            }
+            _ => Expr::Family {
+                equal: equality(op).ok_or_else(unsupported)?,
+                ipv4: match value {
+                    "ipv4" | "inet" => true,
+                    "ipv6" | "inet6" => false,
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        Ok(expr)
+    }
+}
+
+/// `Some(true)` for `==`, `Some(false)` for `!=`
+fn equality(op: &str) -> Option<bool> {
+    match op {
+        "==" => Some(true),
+        "!=" => Some(false),
+        _ => None,
+    }
+}
+
+fn ordering(op: &str) -> Option<(Ordering, bool)> {
+    match op {
+        "<" => Some((Ordering::Less, false)),
+        "<=" => Some((Ordering::Less, true)),
+        ">" => Some((Ordering::Greater, false)),
+        ">=" => Some((Ordering::Greater, true)),
+        _ => equality(op).map(|equal| (Ordering::Equal, equal)),
+    }
+}
+
+/// A prefix such as `10.0.0.0/8`, or an address as a host prefix
+fn parse_net(value: &str) -> Option<IpNet> {
+    match value.split_once('/') {
+        Some((addr, len)) => IpNet::new(addr.parse().ok()?, len.parse().ok()?).ok(),
+        None => Some(host_net(value.parse().ok()?)),
+    }
+}
@@ -0,0 +1,179 @@
+use crate::ProcRoot;
+use core::num::ParseIntError;
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result as IoResult},
+    path::Path,
+};
+
+/// Counters of `net/rt6_stats`, the sizes of the IPv6 FIB and its dst cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ipv6RouteStats {
+    pub fib_nodes: u64,
+    pub route_nodes: u64,
+    pub route_alloc: u64,
+    pub route_entries: u64,
+    pub cached_routes: u64,
+    /// Live dst entries, the number `max_size` and `gc_thresh` are compared against
+    pub dst_entries: u64,
+    pub discarded_routes: u64,
+}
+
+/// Occupancy of one address family's routing structures against its sysctl limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FamilyHealth {
+    /// Entries in `net/route` or `net/ipv6_route`
+    pub routes: u64,
+    /// Live dst cache entries, `None` when the kernel does not report them
+    pub dst_entries: Option<u64>,
+    /// `sys/net/ipv{4,6}/route/max_size`
+    pub max_size: Option<u64>,
+    /// `sys/net/ipv{4,6}/route/gc_thresh`, `None` as well when disabled with `-1`
+    pub gc_thresh: Option<u64>,
+}
+
+impl FamilyHealth {
+    /// Dst entries as a percentage of `max_size`
+    pub fn utilization(&self) -> Option<f64> {
+        match (self.dst_entries, self.max_size) {
+            (Some(entries), Some(max_size)) if max_size > 0 => {
+                Some(entries as f64 * 100.0 / max_size as f64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the dst entries reached `gc_thresh`, so every new entry triggers garbage collection
+    pub fn gc_pressure(&self) -> bool {
+        matches!((self.dst_entries, self.gc_thresh), (Some(entries), Some(thresh)) if entries >= thresh)
+    }
+}
+
+/// Route table sizes next to the kernel's limits, see [`ProcRoot::route_table_health`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteTableHealth {
+    pub ipv4: FamilyHealth,
+    pub ipv6: FamilyHealth,
+    /// `None` when IPv6 is disabled
+    pub ipv6_stats: Option<Ipv6RouteStats>,
+}
+
+impl ProcRoot {
+    /// Count the routes and read the route cache limits.
+    ///
+    /// IPv4 dst entries come from the first column of `net/stat/rt_cache`, IPv6 ones from
+    /// `net/rt6_stats`. Files the running kernel does not have leave their fields `None`.
+    pub fn route_table_health(&self) -> IoResult<RouteTableHealth> {
+        let ipv4_routes = self.ipv4_route_table()?.count() as u64;
+        let ipv6_routes = match self.open_ipv6_route_table()? {
+            Some(table) => table.count() as u64,
+            None => 0,
+        };
+        let rt6_stats = self.net().join("rt6_stats");
+        let ipv6_stats = read_optional(&rt6_stats)?
+            .map(|text| parse_rt6_stats(&text).ok_or_else(|| malformed(&rt6_stats)))
+            .transpose()?;
+        let rt_cache = self.net().join("stat/rt_cache");
+        let ipv4_dst_entries = read_optional(&rt_cache)?
+            .map(|text| parse_rt_cache_entries(&text).map_err(|_| malformed(&rt_cache)))
+            .transpose()?
+            .flatten();
+        let limits = |family: &str| -> IoResult<(Option<u64>, Option<u64>)> {
+            let dir = self.path().join("sys/net").join(family).join("route");
+            Ok((
+                read_sysctl(&dir.join("max_size"))?,
+                read_sysctl(&dir.join("gc_thresh"))?,
+            ))
+        };
+        let (ipv4_max_size, ipv4_gc_thresh) = limits("ipv4")?;
+        let (ipv6_max_size, ipv6_gc_thresh) = limits("ipv6")?;
+
+        Ok(RouteTableHealth {
+            ipv4: FamilyHealth {
+                routes: ipv4_routes,
+                dst_entries: ipv4_dst_entries,
+                max_size: ipv4_max_size,
+                gc_thresh: ipv4_gc_thresh,
+            },
+            ipv6: FamilyHealth {
+                routes: ipv6_routes,
+                dst_entries: ipv6_stats.map(|stats| stats.dst_entries),
+                max_size: ipv6_max_size,
+                gc_thresh: ipv6_gc_thresh,
+            },
+            ipv6_stats,
+        })
+    }
+}
+
+/// The file's contents, or `None` when it does not exist
+fn read_optional(path: &Path) -> IoResult<Option<String>> {
+    match fs::read_to_string(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        text => text.map(Some),
+    }
+}
+
+/// A numeric sysctl, with negative values standing for "disabled"
+fn read_sysctl(path: &Path) -> IoResult<Option<u64>> {
+    let Some(text) = read_optional(path)? else {
+        return Ok(None);
+    };
+    let value: i64 = text.trim().parse().map_err(|_| malformed(path))?;
+    Ok(u64::try_from(value).ok())
+}
+
+fn parse_rt6_stats(text: &str) -> Option<Ipv6RouteStats> {
+    let fields = text
+        .split_whitespace()
+        .map(|field| u64::from_str_radix(field, 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [
+        fib_nodes,
+        route_nodes,
+        route_alloc,
+        route_entries,
+        cached_routes,
+        dst_entries,
+        discarded_routes,
+    ] = fields[..]
+    else {
+        return None;
+    };
+    Some(Ipv6RouteStats {
+        fib_nodes,
+        route_nodes,
+        route_alloc,
+        route_entries,
+        cached_routes,
+        dst_entries,
+        discarded_routes,
+    })
+}
+
+/// The `entries` column of `net/stat/rt_cache`, which every per-CPU row repeats
+fn parse_rt_cache_entries(text: &str) -> Result<Option<u64>, ParseIntError> {
+    let mut lines = text.lines();
+    if lines
+        .next()
+        .and_then(|header| header.split_whitespace().next())
+        != Some("entries")
+    {
+        return Ok(None);
+    }
+    lines
+        .next()
+        .and_then(|row| row.split_whitespace().next())
+        .map(|entries| u64::from_str_radix(entries, 16))
+        .transpose()
+}
+
+fn malformed(path: &Path) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("malformed {}", path.display()),
+    )
+}
@@ -0,0 +1,188 @@
+//! Route changes tracked over successive snapshots
+
+use crate::{IpNet, Route, Snapshot, SnapshotDiff};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
+
+/// The last few [`Snapshot`]s of a host, oldest first.
+///
+/// Once full, every new snapshot evicts the oldest one, so the answers cover only the retained
+/// window.
+#[derive(Debug, Clone)]
+pub struct RouteHistory {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+/// How often one destination prefix changed within a [`RouteHistory`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlapReport {
+    pub network: IpNet,
+    /// Number of consecutive snapshot pairs in which a route to the prefix was added, removed or changed
+    pub transitions: usize,
+    /// Capture time of the snapshot showing the first change
+    pub first_change: SystemTime,
+    /// Capture time of the snapshot showing the latest change
+    pub last_change: SystemTime,
+}
+
+/// Lookups that hit one route between two consecutive snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UseDelta {
+    pub network: IpNet,
+    pub gateway: Option<IpAddr>,
+    pub interface: String,
+    /// Increase of the use counter, correct across one wrap of the 32-bit counter
+    pub delta: u32,
+    /// Capture time of the newer snapshot
+    pub captured_at: SystemTime,
+    /// Time between the two snapshots, zero if the clock went backwards
+    pub interval: Duration,
+}
+
+impl UseDelta {
+    /// Lookups per second over the interval, `None` for snapshots taken at the same time
+    pub fn rate(&self) -> Option<f64> {
+        let seconds = self.interval.as_secs_f64();
+        (seconds > 0.0).then(|| f64::from(self.delta) / seconds)
+    }
+}
+
+/// Use counter deltas of the routes present in both snapshots.
+///
+/// A route is matched by [`Route::same_route`], so one whose settings changed in between is
+/// left out, as the kernel restarts the counter of a replaced route. IPv4 routes report no uses
+/// on kernels since 3.6, which print 0 for the counter.
+pub fn use_deltas(older: &Snapshot, newer: &Snapshot) -> Vec<UseDelta> {
+    let interval = newer
+        .captured_at
+        .duration_since(older.captured_at)
+        .unwrap_or_default();
+    let mut deltas = table_deltas(&older.ipv4, &newer.ipv4);
+    deltas.extend(table_deltas(&older.ipv6, &newer.ipv6));
+    deltas
+        .into_iter()
+        .map(|(route, delta)| UseDelta {
+            network: route.network(),
+            gateway: route.gateway(),
+            interface: route.interface().into(),
+            delta,
+            captured_at: newer.captured_at,
+            interval,
+        })
+        .collect()
+}
+
+fn table_deltas<'a, E: Route>(older: &[E], newer: &'a [E]) -> Vec<(&'a dyn Route, u32)> {
+    let mut unmatched: Vec<&E> = older.iter().collect();
+    let mut deltas: Vec<(&'a dyn Route, u32)> = Vec::new();
+    for entry in newer {
+        if let Some(pos) = unmatched.iter().position(|old| old.same_route(entry)) {
+            let old = unmatched.swap_remove(pos);
+            deltas.push((entry, entry.use_count().wrapping_sub(old.use_count())));
+        }
+    }
+    deltas
+}
+
+impl RouteHistory {
+    /// Keep up to `capacity` snapshots, at least one
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append the newest snapshot, evicting the oldest one when full
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Retained snapshots, oldest first
+    pub fn snapshots(&self) -> impl Iterator<Item = &Snapshot> {
+        self.snapshots.iter()
+    }
+
+    /// Capture times of the snapshots in which a default route of either family changed
+    pub fn default_route_changes(&self) -> Vec<SystemTime> {
+        self.diffs()
+            .filter(|diff| changed_networks(diff).any(|network| network.prefix_len() == 0))
+            .map(|diff| diff.captured_at)
+            .collect()
+    }
+
+    /// Changes of the routes to exactly `network`, `None` if they stayed the same throughout
+    pub fn flaps(&self, network: IpNet) -> Option<FlapReport> {
+        self.flap_reports(1)
+            .into_iter()
+            .find(|report| report.network == network)
+    }
+
+    /// Every prefix that changed at least `min_transitions` times, the most unstable first
+    pub fn flap_reports(&self, min_transitions: usize) -> Vec<FlapReport> {
+        let mut reports: BTreeMap<IpNet, FlapReport> = BTreeMap::new();
+        for diff in self.diffs() {
+            // a prefix counts once per pair, however many of its routes changed
+            let networks: BTreeSet<IpNet> = changed_networks(&diff).collect();
+            for network in networks {
+                reports
+                    .entry(network)
+                    .and_modify(|report| {
+                        report.transitions += 1;
+                        report.last_change = diff.captured_at;
+                    })
+                    .or_insert(FlapReport {
+                        network,
+                        transitions: 1,
+                        first_change: diff.captured_at,
+                        last_change: diff.captured_at,
+                    });
+            }
+        }
+        let mut reports: Vec<FlapReport> = reports
+            .into_values()
+            .filter(|report| report.transitions >= min_transitions.max(1))
+            .collect();
+        reports.sort_by_key(|report| Reverse(report.transitions));
+        reports
+    }
+
+    /// Use counter deltas between each snapshot and the next one, oldest first, see [`use_deltas`]
+    pub fn use_deltas(&self) -> Vec<UseDelta> {
+        self.pairs()
+            .flat_map(|(older, newer)| use_deltas(older, newer))
+            .collect()
+    }
+
+    /// Differences between each snapshot and the next one
+    fn diffs(&self) -> impl Iterator<Item = SnapshotDiff> {
+        self.pairs().map(|(older, newer)| older.diff(newer))
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&Snapshot, &Snapshot)> {
+        self.snapshots.iter().zip(self.snapshots.iter().skip(1))
+    }
+}
+
+fn changed_networks(diff: &SnapshotDiff) -> impl Iterator<Item = IpNet> {
+    let ipv4 = diff.ipv4.iter().map(|change| change.entry().network());
+    let ipv6 = diff.ipv6.iter().map(|change| change.entry().network());
+    ipv4.chain(ipv6)
+}
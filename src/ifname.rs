@@ -0,0 +1,141 @@
+use alloc::{string::String, sync::Arc};
+use core::{borrow::Borrow, fmt, ops::Deref, str::FromStr};
+use thiserror::Error;
+
+/// Longest interface name the kernel accepts, `IFNAMSIZ` less the terminating NUL
+pub const IFNAME_MAX_LEN: usize = 15;
+
+/// A name that cannot belong to a network interface
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IfNameError {
+    #[error("Interface name is empty")]
+    Empty,
+
+    #[error("Interface name {0:?} is longer than {IFNAME_MAX_LEN} bytes")]
+    TooLong(String),
+
+    #[error("Interface name {0:?} contains whitespace")]
+    Whitespace(String),
+}
+
+/// A network interface name: non-empty, at most [`IFNAME_MAX_LEN`] bytes and without whitespace.
+///
+/// Clones share the name, so entries interned by
+/// [`RouteTableOptions::intern_names`](crate::RouteTableOptions::intern_names) stay cheap.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct IfName(Arc<str>);
+
+impl IfName {
+    pub fn new(name: impl Into<Arc<str>>) -> Result<Self, IfNameError> {
+        let name = name.into();
+        Self::validate(&name)?;
+        Ok(Self(name))
+    }
+
+    /// Check `name` without allocating
+    pub fn validate(name: &str) -> Result<(), IfNameError> {
+        Self::validate_len(name, IFNAME_MAX_LEN)
+    }
+
+    /// Check `name`, allowing up to `max_len` bytes
+    pub(crate) fn validate_len(name: &str, max_len: usize) -> Result<(), IfNameError> {
+        if name.is_empty() {
+            return Err(IfNameError::Empty);
+        }
+        if name.len() > max_len {
+            return Err(IfNameError::TooLong(name.into()));
+        }
+        if name.contains(char::is_whitespace) {
+            return Err(IfNameError::Whitespace(name.into()));
+        }
+        Ok(())
+    }
+
+    /// Wrap a name the parser has already checked
+    pub(crate) fn new_unchecked(name: Arc<str>) -> Self {
+        Self(name)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for IfName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for IfName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for IfName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for IfName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for IfName {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for IfName {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl FromStr for IfName {
+    type Err = IfNameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::new(name)
+    }
+}
+
+impl TryFrom<&str> for IfName {
+    type Error = IfNameError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl TryFrom<String> for IfName {
+    type Error = IfNameError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl From<IfName> for String {
+    fn from(name: IfName) -> Self {
+        name.0.as_ref().into()
+    }
+}
+
+impl From<IfName> for Arc<str> {
+    fn from(name: IfName) -> Self {
+        name.0
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{IpNet, Ipv4RouteEntry, Ipv6RouteEntry, Route, RouteEntry};
+use std::collections::HashMap;
+
+/// Routes of both families grouped by output interface.
+///
+/// Built once per read of the tables, so that per-interface summaries need no regrouping. The
+/// routes of each interface are sorted by destination prefix, IPv4 before IPv6.
+#[derive(Debug, Clone, Default)]
+pub struct RouteIndex {
+    interfaces: HashMap<String, Vec<RouteEntry>>,
+}
+
+impl RouteIndex {
+    pub fn new(
+        ipv4: impl IntoIterator<Item = Ipv4RouteEntry>,
+        ipv6: impl IntoIterator<Item = Ipv6RouteEntry>,
+    ) -> Self {
+        let entries = ipv4
+            .into_iter()
+            .map(RouteEntry::from)
+            .chain(ipv6.into_iter().map(RouteEntry::from));
+        let mut interfaces = Self::by_interface(entries);
+        for routes in interfaces.values_mut() {
+            routes.sort_by_key(Route::network);
+        }
+        Self { interfaces }
+    }
+
+    /// Group `entries` by output interface, keeping their order within each group
+    pub fn by_interface<R: Route>(entries: impl IntoIterator<Item = R>) -> HashMap<String, Vec<R>> {
+        let mut groups: HashMap<String, Vec<R>> = HashMap::new();
+        for entry in entries {
+            match groups.get_mut(entry.interface()) {
+                Some(group) => group.push(entry),
+                None => {
+                    groups.insert(entry.interface().to_owned(), vec![entry]);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Routes through `iface`, empty for an interface without any
+    pub fn routes(&self, iface: &str) -> &[RouteEntry] {
+        self.interfaces.get(iface).map_or(&[], Vec::as_slice)
+    }
+
+    /// Interfaces with at least one route, in no particular order
+    pub fn interfaces(&self) -> impl Iterator<Item = &str> {
+        self.interfaces.keys().map(String::as_str)
+    }
+
+    /// Interfaces carrying a default route of either family, sorted by name
+    pub fn interfaces_with_default_route(&self) -> Vec<&str> {
+        self.interfaces_where(|route| route.prefix_len() == 0)
+    }
+
+    /// Interfaces with a route to exactly `network`, sorted by name
+    pub fn interfaces_for(&self, network: IpNet) -> Vec<&str> {
+        self.interfaces_where(|route| route.network() == network)
+    }
+
+    fn interfaces_where(&self, mut predicate: impl FnMut(&RouteEntry) -> bool) -> Vec<&str> {
+        let mut interfaces: Vec<&str> = self
+            .interfaces
+            .iter()
+            .filter(|(_, routes)| routes.iter().any(&mut predicate))
+            .map(|(iface, _)| iface.as_str())
+            .collect();
+        interfaces.sort_unstable();
+        interfaces
+    }
+}
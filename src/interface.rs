@@ -0,0 +1,138 @@
+use crate::{MacAddr, Route, utils::parse_mac};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Operational state of an interface as reported in `/sys/class/net/<iface>/operstate` (RFC 2863)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperState {
+    Unknown,
+    NotPresent,
+    Down,
+    LowerLayerDown,
+    Testing,
+    Dormant,
+    Up,
+}
+
+impl OperState {
+    fn from_sysfs(state: &str) -> Self {
+        match state {
+            "notpresent" => Self::NotPresent,
+            "down" => Self::Down,
+            "lowerlayerdown" => Self::LowerLayerDown,
+            "testing" => Self::Testing,
+            "dormant" => Self::Dormant,
+            "up" => Self::Up,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Kernel state of the interface a route points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceInfo {
+    pub index: u32,
+    /// `None` for interfaces without a link-layer address, such as tunnels
+    pub mac: Option<MacAddr>,
+    pub mtu: u32,
+    pub oper_state: OperState,
+}
+
+impl InterfaceInfo {
+    /// Whether the interface can carry traffic.
+    ///
+    /// Drivers that do not track their state (loopback, many virtual devices) report
+    /// [`OperState::Unknown`], which is treated as up the same way `ip link` does.
+    pub fn is_up(&self) -> bool {
+        matches!(self.oper_state, OperState::Up | OperState::Unknown)
+    }
+}
+
+/// A route entry paired with the state of its output interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrichedRouteEntry<E> {
+    pub entry: E,
+    /// `None` when the interface no longer exists or could not be read
+    pub interface: Option<InterfaceInfo>,
+}
+
+impl<E> EnrichedRouteEntry<E> {
+    /// Whether the output interface exists and is up
+    pub fn is_usable(&self) -> bool {
+        self.interface.as_ref().is_some_and(InterfaceInfo::is_up)
+    }
+}
+
+/// Looks up interfaces in `/sys/class/net`, reading each one at most once
+#[derive(Debug)]
+pub struct InterfaceResolver {
+    root: PathBuf,
+    cache: HashMap<Arc<str>, Option<InterfaceInfo>>,
+}
+
+impl Default for InterfaceResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InterfaceResolver {
+    pub fn new() -> Self {
+        Self::with_root("/sys/class/net")
+    }
+
+    /// Read interfaces from `root` instead of `/sys/class/net`
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn resolve(&mut self, name: &str) -> Option<InterfaceInfo> {
+        if let Some(info) = self.cache.get(name) {
+            return info.clone();
+        }
+        let info = read_interface(&self.root.join(name));
+        self.cache.insert(Arc::from(name), info.clone());
+        info
+    }
+
+    /// Name of the interface with `index`, found by reading every interface under the root
+    pub fn name_of(&mut self, index: u32) -> Option<String> {
+        let names = fs::read_dir(&self.root).ok()?;
+        names
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .find(|name| self.resolve(name).is_some_and(|info| info.index == index))
+    }
+
+    pub fn enrich<E: Route>(&mut self, entry: E) -> EnrichedRouteEntry<E> {
+        let interface = self.resolve(entry.interface());
+        EnrichedRouteEntry { entry, interface }
+    }
+
+    /// Forget cached interfaces so the next lookups see fresh state
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+fn read_interface(dir: &Path) -> Option<InterfaceInfo> {
+    let read = |attr: &str| fs::read_to_string(dir.join(attr)).ok();
+    let index = read("ifindex")?.trim().parse().ok()?;
+    let mtu = read("mtu")?.trim().parse().ok()?;
+    let mac = read("address").and_then(|address| parse_mac(address.trim()));
+    let oper_state = read("operstate")
+        .map(|state| OperState::from_sysfs(state.trim()))
+        .unwrap_or(OperState::Unknown);
+    Some(InterfaceInfo {
+        index,
+        mac,
+        mtu,
+        oper_state,
+    })
+}
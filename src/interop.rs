@@ -0,0 +1,100 @@
+use crate::{
+    InterfaceResolver, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, Route,
+    RouteEntry,
+};
+use std::net::IpAddr;
+
+/// The destination, gateway and metric of the entry.
+///
+/// net-route names interfaces by index, which is left unset here; fill it in with
+/// [`InterfaceResolver::to_net_route`].
+impl From<&Ipv4RouteEntry> for net_route::Route {
+    fn from(entry: &Ipv4RouteEntry) -> Self {
+        net_route_of(entry)
+    }
+}
+
+/// The destination, gateway, metric and source prefix of the entry, see the IPv4 conversion
+impl From<&Ipv6RouteEntry> for net_route::Route {
+    fn from(entry: &Ipv6RouteEntry) -> Self {
+        let route = net_route_of(entry);
+        #[cfg(target_os = "linux")]
+        let route = match entry.src_prefix {
+            0 => route,
+            src_prefix => route.with_source(entry.src.into(), src_prefix),
+        };
+        route
+    }
+}
+
+impl From<&RouteEntry> for net_route::Route {
+    fn from(entry: &RouteEntry) -> Self {
+        match entry {
+            RouteEntry::V4(entry) => entry.into(),
+            RouteEntry::V6(entry) => entry.into(),
+        }
+    }
+}
+
+fn net_route_of(entry: &impl Route) -> net_route::Route {
+    let route = net_route::Route::new(entry.destination(), entry.prefix_len());
+    let route = match entry.gateway() {
+        Some(gateway) => route.with_gateway(gateway),
+        None => route,
+    };
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    let route = route.with_metric(entry.metric().get());
+    route
+}
+
+impl InterfaceResolver {
+    /// Convert `entry` into a net-route route through the index of its interface, `None` when
+    /// the interface does not exist
+    pub fn to_net_route(&mut self, entry: &RouteEntry) -> Option<net_route::Route> {
+        let index = self.resolve(entry.interface())?.index;
+        Some(net_route::Route::from(entry).with_ifindex(index))
+    }
+
+    /// Convert a route listed or reported by net-route into an entry flagged `UP`, `None` when
+    /// its interface is unknown or its fields do not make a valid entry
+    pub fn from_net_route(&mut self, route: &net_route::Route) -> Option<RouteEntry> {
+        let name = self.name_of(route.ifindex?)?;
+        #[cfg(any(target_os = "linux", target_os = "windows"))]
+        let metric = route.metric.unwrap_or_default();
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        let metric = 0;
+        match (route.destination, route.gateway) {
+            (IpAddr::V4(dest), gateway @ (None | Some(IpAddr::V4(_)))) => {
+                let mut flags = Ipv4RouteFlags::UP;
+                flags.set(Ipv4RouteFlags::HOST, route.prefix == 32);
+                let mut builder = Ipv4RouteEntry::builder(name)
+                    .dest(dest)
+                    .prefix_len(route.prefix)
+                    .metric(metric);
+                if let Some(IpAddr::V4(gateway)) = gateway {
+                    flags |= Ipv4RouteFlags::GATEWAY;
+                    builder = builder.gateway(gateway);
+                }
+                builder.flags(flags).build().ok().map(RouteEntry::V4)
+            }
+            (IpAddr::V6(dest), gateway @ (None | Some(IpAddr::V6(_)))) => {
+                let mut flags = Ipv6RouteFlags::UP;
+                flags.set(Ipv6RouteFlags::HOST, route.prefix == 128);
+                let mut builder = Ipv6RouteEntry::builder(name)
+                    .dest(dest)
+                    .dest_prefix(route.prefix)
+                    .metric(metric);
+                if let Some(IpAddr::V6(gateway)) = gateway {
+                    flags |= Ipv6RouteFlags::GATEWAY;
+                    builder = builder.next_hop(gateway);
+                }
+                #[cfg(target_os = "linux")]
+                if let Some(IpAddr::V6(src)) = route.source {
+                    builder = builder.src(src).src_prefix(route.source_prefix);
+                }
+                builder.flags(flags).build().ok().map(RouteEntry::V6)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! The JSON of `ip -j route show` and `ip -6 -j route show`, for captures from hosts where only
+//! iproute2 output was recorded.
+//!
+//! Routes map to the entries `/proc/net` would list for them: IPv4 routes outside the main table
+//! are left out, multipath IPv4 routes keep their first next hop while IPv6 ones become one entry
+//! per next hop, and `unreachable`, `prohibit` and `blackhole` routes are flagged `REJECT`. The
+//! counters, which iproute2 does not print, are 0.
+
+use crate::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, Ipv6RoutePref, Route,
+    Snapshot, ValidationError,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::SystemTime,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IpJsonError {
+    #[error("Malformed ip route JSON")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Route {index} has the destination {dst:?}, not a prefix of the table's family")]
+    InvalidDestination { index: usize, dst: String },
+
+    #[error("Route {index} has a next hop of the other address family")]
+    GatewayFamily { index: usize },
+
+    #[error("Route {index} is invalid")]
+    InvalidEntry {
+        index: usize,
+        #[source]
+        source: ValidationError,
+    },
+}
+
+/// One element of the array `ip -j route show` prints
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JsonRoute {
+    /// Only printed for routes other than unicast ones
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    route_type: Option<String>,
+    dst: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gateway: Option<IpAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dev: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    table: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metric: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pref: Option<String>,
+    /// `{"mtu": 1400}` objects, where a locked value prints as `["lock", 1400]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    metrics: Vec<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default)]
+    flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    nexthops: Vec<JsonNextHop>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonNextHop {
+    #[serde(default)]
+    gateway: Option<IpAddr>,
+    #[serde(default)]
+    dev: Option<String>,
+    #[serde(default)]
+    flags: Vec<String>,
+}
+
+impl JsonRoute {
+    fn is_reject(&self) -> bool {
+        matches!(
+            self.route_type.as_deref(),
+            Some("unreachable" | "prohibit" | "blackhole")
+        )
+    }
+
+    /// The next hops as `(gateway, dev, dead)`, the route's own when it is not multipath
+    fn hops(&self) -> Vec<(Option<IpAddr>, Option<&str>, bool)> {
+        let dead = |flags: &[String]| flags.iter().any(|flag| flag == "dead");
+        if self.nexthops.is_empty() {
+            return vec![(self.gateway, self.dev.as_deref(), dead(&self.flags))];
+        }
+        self.nexthops
+            .iter()
+            .map(|hop| (hop.gateway, hop.dev.as_deref(), dead(&hop.flags)))
+            .collect()
+    }
+
+    fn mtu(&self) -> Option<u32> {
+        let mtu = self.metrics.iter().find_map(|metrics| metrics.get("mtu"))?;
+        let value = match mtu {
+            serde_json::Value::Array(locked) => locked.last()?,
+            value => value,
+        };
+        value.as_u64()?.try_into().ok()
+    }
+}
+
+/// Parse the output of `ip -j route show`
+pub fn parse_ipv4(json: &str) -> Result<Vec<Ipv4RouteEntry>, IpJsonError> {
+    let routes: Vec<JsonRoute> = serde_json::from_str(json)?;
+    let mut entries = Vec::new();
+    for (index, route) in routes.iter().enumerate() {
+        if route.table.as_deref().is_some_and(|table| table != "main") {
+            continue;
+        }
+        let dst = parse_dst(&route.dst, IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+            .filter(|dst| dst.addr().is_ipv4())
+            .ok_or_else(|| IpJsonError::InvalidDestination {
+                index,
+                dst: route.dst.clone(),
+            })?;
+        let IpAddr::V4(dest) = dst.addr() else {
+            unreachable!("filtered for IPv4 above");
+        };
+        // /proc/net/route only shows the first next hop of a multipath route
+        let (gateway, dev, dead) = route.hops().swap_remove(0);
+        let mut flags = Ipv4RouteFlags::empty();
+        flags.set(Ipv4RouteFlags::UP, !dead);
+        flags.set(Ipv4RouteFlags::HOST, dst.prefix_len() == 32);
+        flags.set(Ipv4RouteFlags::REJECT, route.is_reject());
+        let mut builder = Ipv4RouteEntry::builder(dev.unwrap_or("*"))
+            .dest(dest)
+            .prefix_len(dst.prefix_len())
+            .metric(route.metric.unwrap_or_default());
+        match gateway {
+            Some(IpAddr::V4(gateway)) => {
+                flags |= Ipv4RouteFlags::GATEWAY;
+                builder = builder.gateway(gateway);
+            }
+            Some(IpAddr::V6(_)) => return Err(IpJsonError::GatewayFamily { index }),
+            None => {}
+        }
+        if let Some(mtu) = route.mtu() {
+            builder = builder.mtu(mtu);
+        }
+        let entry = builder
+            .flags(flags)
+            .build()
+            .map_err(|source| IpJsonError::InvalidEntry { index, source })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Parse the output of `ip -6 -j route show`
+pub fn parse_ipv6(json: &str) -> Result<Vec<Ipv6RouteEntry>, IpJsonError> {
+    let routes: Vec<JsonRoute> = serde_json::from_str(json)?;
+    let mut entries = Vec::new();
+    for (index, route) in routes.iter().enumerate() {
+        let invalid_prefix = |dst: &str| IpJsonError::InvalidDestination {
+            index,
+            dst: dst.into(),
+        };
+        let unspecified = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+        let dst = parse_dst(&route.dst, unspecified)
+            .filter(|dst| dst.addr().is_ipv6())
+            .ok_or_else(|| invalid_prefix(&route.dst))?;
+        let src = match &route.from {
+            Some(from) => parse_dst(from, unspecified)
+                .filter(|src| src.addr().is_ipv6())
+                .ok_or_else(|| invalid_prefix(from))?,
+            None => IpNet::new(unspecified, 0).expect("prefix length fits"),
+        };
+        let (IpAddr::V6(dest), IpAddr::V6(src_addr)) = (dst.addr(), src.addr()) else {
+            unreachable!("filtered for IPv6 above");
+        };
+        let preference = match route.pref.as_deref() {
+            Some("high") => Ipv6RoutePref::High,
+            Some("low") => Ipv6RoutePref::Low,
+            _ => Ipv6RoutePref::Medium,
+        };
+        let mut flags = Ipv6RouteFlags::empty();
+        flags.set(Ipv6RouteFlags::REJECT, route.is_reject());
+        flags.set(Ipv6RouteFlags::NO_NEXT_HOP, route.is_reject());
+        flags.set(
+            Ipv6RouteFlags::LOCAL,
+            route.route_type.as_deref() == Some("local"),
+        );
+        flags.set(
+            Ipv6RouteFlags::ANYCAST,
+            route.route_type.as_deref() == Some("anycast"),
+        );
+        flags.set(Ipv6RouteFlags::EXPIRES, route.expires.is_some());
+        if route.protocol.as_deref() == Some("ra") {
+            flags |= Ipv6RouteFlags::ADDR_CONF;
+            flags.set(Ipv6RouteFlags::DEFAULT, dst.prefix_len() == 0);
+        }
+        for (gateway, dev, dead) in route.hops() {
+            let mut flags = flags;
+            flags.set(Ipv6RouteFlags::UP, !dead);
+            // Entries need a valid name to build; one without a device gets `None` below
+            let mut builder = Ipv6RouteEntry::builder(dev.unwrap_or("lo"))
+                .dest(dest)
+                .dest_prefix(dst.prefix_len())
+                .src(src_addr)
+                .src_prefix(src.prefix_len())
+                .metric(route.metric.unwrap_or_default());
+            match gateway {
+                Some(IpAddr::V6(gateway)) => {
+                    flags |= Ipv6RouteFlags::GATEWAY;
+                    builder = builder.next_hop(gateway);
+                }
+                Some(IpAddr::V4(_)) => return Err(IpJsonError::GatewayFamily { index }),
+                None => {}
+            }
+            let mut entry = builder
+                .flags(flags)
+                .preference(preference)
+                .build()
+                .map_err(|source| IpJsonError::InvalidEntry { index, source })?;
+            if dev.is_none() {
+                entry.name = None;
+            }
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Print IPv4 entries the way `ip -j route show` does
+pub fn ipv4_to_json(entries: &[Ipv4RouteEntry]) -> String {
+    let routes: Vec<JsonRoute> = entries
+        .iter()
+        .map(|entry| JsonRoute {
+            route_type: entry.is_reject().then(|| "unreachable".into()),
+            dev: (&*entry.name != "*").then(|| entry.name.to_string()),
+            metrics: entry
+                .mtu
+                .map(|mtu| serde_json::Map::from_iter([("mtu".into(), mtu.into())]))
+                .into_iter()
+                .collect(),
+            ..JsonRoute::common(entry)
+        })
+        .collect();
+    serde_json::to_string(&routes).expect("routes serialize")
+}
+
+/// Print IPv6 entries the way `ip -6 -j route show` does, one route per entry
+pub fn ipv6_to_json(entries: &[Ipv6RouteEntry]) -> String {
+    let routes: Vec<JsonRoute> = entries
+        .iter()
+        .map(|entry| {
+            let route_type = if entry.is_reject() {
+                Some("unreachable")
+            } else if entry.flags.contains(Ipv6RouteFlags::LOCAL) {
+                Some("local")
+            } else if entry.flags.contains(Ipv6RouteFlags::ANYCAST) {
+                Some("anycast")
+            } else {
+                None
+            };
+            let pref = match entry.preference() {
+                Ipv6RoutePref::High => "high",
+                Ipv6RoutePref::Low => "low",
+                Ipv6RoutePref::Medium | Ipv6RoutePref::Invalid => "medium",
+            };
+            JsonRoute {
+                route_type: route_type.map(Into::into),
+                from: (entry.src_prefix > 0).then(|| entry.source_network().to_string()),
+                dev: entry.name.as_deref().map(Into::into),
+                protocol: entry
+                    .flags
+                    .contains(Ipv6RouteFlags::ADDR_CONF)
+                    .then(|| "ra".into()),
+                expires: entry.flags.contains(Ipv6RouteFlags::EXPIRES).then_some(0),
+                pref: Some(pref.into()),
+                ..JsonRoute::common(entry)
+            }
+        })
+        .collect();
+    serde_json::to_string(&routes).expect("routes serialize")
+}
+
+impl JsonRoute {
+    /// The fields both families print the same way
+    fn common(entry: &impl Route) -> Self {
+        let network = entry.network();
+        let max_len = if network.addr().is_ipv4() { 32 } else { 128 };
+        let dst = match network.prefix_len() {
+            0 => "default".into(),
+            len if len == max_len => network.addr().to_string(),
+            _ => network.to_string(),
+        };
+        Self {
+            dst,
+            gateway: entry.gateway(),
+            metric: Some(entry.metric().get()),
+            flags: if entry.is_up() {
+                Vec::new()
+            } else {
+                vec!["dead".into()]
+            },
+            ..Self::default()
+        }
+    }
+}
+
+/// `default`, an address or a prefix, with `unspecified` giving the family of `default`
+fn parse_dst(dst: &str, unspecified: IpAddr) -> Option<IpNet> {
+    if dst == "default" {
+        return IpNet::new(unspecified, 0).ok();
+    }
+    match dst.split_once('/') {
+        Some((addr, len)) => IpNet::new(addr.parse().ok()?, len.parse().ok()?).ok(),
+        None => {
+            let addr: IpAddr = dst.parse().ok()?;
+            IpNet::new(addr, if addr.is_ipv4() { 32 } else { 128 }).ok()
+        }
+    }
+}
+
+impl Snapshot {
+    /// A snapshot of the outputs of `ip -j route show` and `ip -6 -j route show`, captured now
+    /// and without ARP entries
+    pub fn from_ip_json(ipv4: &str, ipv6: &str) -> Result<Self, IpJsonError> {
+        Ok(Self {
+            captured_at: SystemTime::now(),
+            ipv4: parse_ipv4(ipv4)?,
+            ipv6: parse_ipv6(ipv6)?,
+            arp: Vec::new(),
+        })
+    }
+}
@@ -1,33 +1,135 @@
+#[cfg(feature = "tracing")]
+use crate::trace;
+#[cfg(feature = "std")]
 use crate::{
-    RouteParseError,
-    utils::{hex_char_to_u8, hex_str_to_bytes, hex_str_to_ipv4},
+    AddressFamily, LineError, ParseReport, RouteTableOptions, TableStats, UnknownFlagBits,
+    report::TableLines, utils::NameInterner,
 };
+use crate::{
+    IFNAME_MAX_LEN, IfName, RouteParseError, ValidationError,
+    schema::{Schema, SplitLine, TableColumn, in_column},
+    utils::{hex_str_to_array, hex_str_to_ipv4, ipv4_to_hex_str},
+};
+use alloc::{format, string::String, sync::Arc};
+use core::{fmt, net::Ipv4Addr, num::NonZeroU32, str::FromStr, time::Duration};
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "std")]
 use std::{
     fs::File,
-    io::{self, BufRead, BufReader, Lines},
-    iter::Skip,
-    net::Ipv4Addr,
+    io::{self, BufRead, BufReader},
     path::Path,
-    str::FromStr,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ipv4RouteEntry {
-    pub name: String,
+    pub name: IfName,
+    pub dest: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    pub flags: Ipv4RouteFlags,
+    pub ref_count: u32,
+    pub use_count: u32,
+    pub metric: u32,
+    pub mask: Ipv4Addr,
+    /// Path MTU, `None` when the line has 0 and [`Ipv4RouteFlags::MTU`] is unset. The kernel
+    /// prints the MTU of a route without ever setting the flag.
+    pub mtu: Option<u32>,
+    /// TCP window clamp, `None` when the line has 0 and [`Ipv4RouteFlags::WINDOW`] is unset
+    pub window: Option<u32>,
+    /// Initial round trip time, `None` when the line has 0 and [`Ipv4RouteFlags::IRTT`] is unset
+    pub irtt: Option<u32>,
+}
+
+/// [`Ipv4RouteEntry`] borrowing the interface name from the parsed line.
+///
+/// The MTU, window and IRTT are the values printed in the line, whether or not their flags are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv4RouteEntryRef<'a> {
+    pub name: &'a str,
     pub dest: Ipv4Addr,
     pub gateway: Ipv4Addr,
     pub flags: Ipv4RouteFlags,
-    pub ref_count: u8,
-    pub use_count: u8,
-    pub metric: u8,
+    pub ref_count: u32,
+    pub use_count: u32,
+    pub metric: u32,
     pub mask: Ipv4Addr,
-    pub mtu: u8,
-    pub window: u8,
-    pub irtt: u8,
+    pub mtu: u32,
+    pub window: u32,
+    pub irtt: u32,
+}
+
+impl<'a> Ipv4RouteEntryRef<'a> {
+    /// Parse and [validate](Self::validate) a route line without allocating
+    pub fn parse(line: &'a str) -> Result<Self, RouteParseError> {
+        let entry = Self::parse_unvalidated(line)?;
+        entry.validate()?;
+        Ok(entry)
+    }
+
+    /// Parse a route line, keeping values that [`Self::validate`] would reject
+    pub fn parse_unvalidated(line: &'a str) -> Result<Self, RouteParseError> {
+        parse_fields(line, None, IFNAME_MAX_LEN)
+    }
+
+    /// Check the interface name, that the mask is contiguous and that a gateway comes with the
+    /// `GATEWAY` flag
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_with_name_len(IFNAME_MAX_LEN)
+    }
+
+    pub(crate) fn validate_with_name_len(
+        &self,
+        max_name_len: usize,
+    ) -> Result<(), ValidationError> {
+        IfName::validate_len(self.name, max_name_len)?;
+        let mask = u32::from(self.mask);
+        if mask.leading_ones() + mask.trailing_zeros() != 32 {
+            return Err(ValidationError::NonContiguousMask(self.mask));
+        }
+        if !self.gateway.is_unspecified() && !self.flags.contains(Ipv4RouteFlags::GATEWAY) {
+            return Err(ValidationError::GatewayWithoutFlag(self.gateway.into()));
+        }
+        Ok(())
+    }
+
+    pub fn to_owned(&self) -> Ipv4RouteEntry {
+        Ipv4RouteEntry::from(self.clone())
+    }
+
+    fn into_owned_with_name(self, name: IfName) -> Ipv4RouteEntry {
+        Ipv4RouteEntry {
+            name,
+            dest: self.dest,
+            gateway: self.gateway,
+            flags: self.flags,
+            ref_count: self.ref_count,
+            use_count: self.use_count,
+            metric: self.metric,
+            mask: self.mask,
+            mtu: optional(self.mtu, self.flags.contains(Ipv4RouteFlags::MTU)),
+            window: optional(self.window, self.flags.contains(Ipv4RouteFlags::WINDOW)),
+            irtt: optional(self.irtt, self.flags.contains(Ipv4RouteFlags::IRTT)),
+        }
+    }
+}
+
+/// A printed value that is `None` only when it is 0 and its flag is unset, so that
+/// [`Ipv4RouteEntry::to_proc_line`] writes back what was read
+fn optional(value: u32, flagged: bool) -> Option<u32> {
+    (flagged || value != 0).then_some(value)
+}
+
+impl From<Ipv4RouteEntryRef<'_>> for Ipv4RouteEntry {
+    fn from(entry: Ipv4RouteEntryRef<'_>) -> Self {
+        let name = IfName::new_unchecked(entry.name.into());
+        entry.into_owned_with_name(name)
+    }
 }
 
 bitflags::bitflags! {
-    #[derive(Debug,Clone)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Ipv4RouteFlags : u16 {
         /// Route is active and available (RTF_UP)
         /// Indicates the route is valid and operational
@@ -62,26 +164,322 @@ bitflags::bitflags! {
     }
 }
 
-pub struct Ipv4RouteTable {
-    lines: Skip<Lines<BufReader<File>>>,
+impl Ipv4RouteFlags {
+    /// Bits without a named flag
+    pub fn unknown_bits(self) -> u16 {
+        self.bits() & !Self::all().bits()
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn decode(self, handling: UnknownFlagBits) -> Result<Self, RouteParseError> {
+        match handling {
+            UnknownFlagBits::Retain => Ok(self),
+            UnknownFlagBits::Drop => Ok(self & Self::all()),
+            UnknownFlagBits::Reject if self.unknown_bits() != 0 => {
+                Err(RouteParseError::UnknownFlagBits(self.unknown_bits().into()))
+            }
+            UnknownFlagBits::Reject => Ok(self),
+        }
+    }
+}
+
+/// Keeps bits without a named flag so that no information from the route file is lost
+impl From<u16> for Ipv4RouteFlags {
+    fn from(bits: u16) -> Self {
+        Self::from_bits_retain(bits)
+    }
+}
+
+/// Names of the set flags joined by `|`, unknown bits printed in hex, e.g. `UP | GATEWAY | 0x8000`
+impl fmt::Display for Ipv4RouteFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        bitflags::parser::to_writer(self, f)
+    }
+}
+
+/// Reach of an IPv4 route (`RT_SCOPE_*`), from narrowest to widest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ipv4RouteScope {
+    /// On-link routes into `127.0.0.0/8`, which never leave this host
+    Host,
+    /// Other on-link routes, whose destinations are neighbours on the interface
+    Link,
+    /// Routes through a gateway and reject routes
+    Universe,
+}
+
+/// A column of `/proc/net/route`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ipv4RouteColumn {
+    Iface,
+    Destination,
+    Gateway,
+    Flags,
+    RefCnt,
+    Use,
+    Metric,
+    Mask,
+    Mtu,
+    Window,
+    Irtt,
+}
+
+impl Ipv4RouteColumn {
+    /// Every column, in the order the kernel prints them
+    pub const ALL: [Self; IPV4_ROUTE_FIELD_COUNT] = [
+        Self::Iface,
+        Self::Destination,
+        Self::Gateway,
+        Self::Flags,
+        Self::RefCnt,
+        Self::Use,
+        Self::Metric,
+        Self::Mask,
+        Self::Mtu,
+        Self::Window,
+        Self::Irtt,
+    ];
+
+    /// The column's name in the header line
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Iface => "Iface",
+            Self::Destination => "Destination",
+            Self::Gateway => "Gateway",
+            Self::Flags => "Flags",
+            Self::RefCnt => "RefCnt",
+            Self::Use => "Use",
+            Self::Metric => "Metric",
+            Self::Mask => "Mask",
+            Self::Mtu => "MTU",
+            Self::Window => "Window",
+            Self::Irtt => "IRTT",
+        }
+    }
+}
+
+impl TableColumn for Ipv4RouteColumn {
+    const ALL: &'static [Self] = &Self::ALL;
+
+    fn name(self) -> &'static str {
+        self.name()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Column order of a `/proc/net/route` source, as detected from its header line
+pub type Ipv4RouteLayout = Schema<Ipv4RouteColumn>;
+
+#[cfg(feature = "std")]
+/// Iterator over the entries of a `/proc/net/route` formatted source
+pub struct Ipv4RouteTable<R = BufReader<File>> {
+    lines: TableLines<R>,
+    layout: Option<Ipv4RouteLayout>,
+    names: Option<NameInterner>,
+    skip_validation: bool,
+    unknown_flag_bits: UnknownFlagBits,
+    max_name_len: usize,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
+#[cfg(feature = "std")]
 impl Ipv4RouteTable {
     pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
-        let reader = File::open_buffered(path)?;
-        let lines = reader.lines().skip(1);
-        Ok(Self { lines })
+        Self::open_with(path, RouteTableOptions::default())
+    }
+
+    pub fn open_with(path: impl AsRef<Path>, options: RouteTableOptions) -> io::Result<Self> {
+        let mut table = Self::from_reader(File::open_buffered(&path)?, options);
+        table.lines.set_path(path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, path.as_ref());
+        Ok(table)
+    }
+
+    /// Open the table in lenient mode, which skips malformed lines and records them in a [`ParseReport`]
+    pub fn open_lenient(path: impl AsRef<Path>) -> io::Result<LenientIpv4RouteTable> {
+        Ok(Self::open(path)?.lenient())
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Ipv4RouteTable<BufReader<GzDecoder<File>>> {
+    /// Open a gzip-compressed capture such as the `route.gz` of a sosreport
+    pub fn open_compressed(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open_compressed_with(path, RouteTableOptions::default())
+    }
+
+    pub fn open_compressed_with(
+        path: impl AsRef<Path>,
+        options: RouteTableOptions,
+    ) -> io::Result<Self> {
+        let reader = BufReader::new(GzDecoder::new(File::open(&path)?));
+        let mut table = Self::from_reader(reader, options);
+        table.lines.set_path(path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, path.as_ref());
+        Ok(table)
     }
 }
 
-impl Iterator for Ipv4RouteTable {
-    type Item = Result<Ipv4RouteEntry, RouteParseError>;
+#[cfg(feature = "std")]
+impl<R: BufRead> Ipv4RouteTable<R> {
+    /// Read the table from any buffered source, starting with the header line
+    pub fn from_reader(reader: R, options: RouteTableOptions) -> Self {
+        Self {
+            lines: TableLines::new(reader, false, Some(AddressFamily::Ipv4))
+                .with_limits(options.limits),
+            layout: None,
+            names: options.intern_names.then(NameInterner::default),
+            skip_validation: options.skip_validation,
+            unknown_flag_bits: options.unknown_flag_bits,
+            max_name_len: options.max_ifname_len(),
+            #[cfg(feature = "tracing")]
+            span: trace::table_span("route"),
+        }
+    }
+
+    /// Switch to lenient mode, see [`Ipv4RouteTable::open_lenient`]
+    pub fn lenient(self) -> LenientIpv4RouteTable<R> {
+        LenientIpv4RouteTable {
+            table: self,
+            report: ParseReport::default(),
+        }
+    }
+
+    /// Yield only the entries, silently skipping lines that fail to parse
+    pub fn entries_lossy(self) -> impl Iterator<Item = Ipv4RouteEntry> {
+        self.entries_lossy_with(|_| {})
+    }
+
+    /// Like [`Ipv4RouteTable::entries_lossy`], handing every skipped line to `on_error`
+    pub fn entries_lossy_with(
+        self,
+        mut on_error: impl FnMut(LineError),
+    ) -> impl Iterator<Item = Ipv4RouteEntry> {
+        self.filter_map(move |entry| entry.map_err(&mut on_error).ok())
+    }
+
+    /// Counters of the lines read so far
+    pub fn stats(&self) -> TableStats {
+        self.lines.stats()
+    }
+
+    /// The layout detected from the header line, `None` until the first call to `next` reads it.
+    ///
+    /// A malformed header is reported as an [`RouteParseError::InvalidHeader`] item and leaves the
+    /// [default](Ipv4RouteLayout::default) layout in place.
+    pub fn layout(&self) -> Option<&Ipv4RouteLayout> {
+        self.layout.as_ref()
+    }
+
+    fn read_header(&mut self) -> Option<Result<(), LineError>> {
+        let (line_number, line_result) = self.lines.next_line()?;
+        let layout = line_result
+            .map_err(|err| self.lines.error(line_number, String::new(), err))
+            .and_then(|header| {
+                Ipv4RouteLayout::from_header(&header)
+                    .map_err(|err| self.lines.error(line_number, header, err.into()))
+            });
+        match layout {
+            Ok(layout) => {
+                self.layout = Some(layout);
+                Some(Ok(()))
+            }
+            Err(err) => {
+                self.layout = Some(Ipv4RouteLayout::default());
+                self.lines.record(false);
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for Ipv4RouteTable<R> {
+    type Item = Result<Ipv4RouteEntry, LineError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.lines.next().map(|line_result| {
-            let line = line_result?;
-            line.parse::<Ipv4RouteEntry>()
-        })
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+        if self.layout.is_none()
+            && let Err(err) = self.read_header()?
+        {
+            return Some(Err(err));
+        }
+        let (line_number, line_result) = self.lines.next_line()?;
+        let result = line_result
+            .map_err(|err| self.lines.error(line_number, String::new(), err))
+            .and_then(|line| {
+                let layout = self.layout.as_ref();
+                let parsed =
+                    parse_fields(&line, layout, self.max_name_len).and_then(|mut entry| {
+                        entry.flags = entry.flags.decode(self.unknown_flag_bits)?;
+                        if !self.skip_validation {
+                            entry.validate_with_name_len(self.max_name_len)?;
+                        }
+                        Ok(entry)
+                    });
+                let parsed = parsed.map(|entry| match &mut self.names {
+                    Some(names) => {
+                        let name = names.intern(entry.name);
+                        entry.into_owned_with_name(name)
+                    }
+                    None => Ipv4RouteEntry::from(entry),
+                });
+                parsed.map_err(|err| self.lines.error(line_number, line, err))
+            });
+        self.lines.record(result.is_ok());
+        Some(result)
+    }
+}
+
+#[cfg(feature = "std")]
+/// An [`Ipv4RouteTable`] yielding only the valid entries, see [`Ipv4RouteTable::open_lenient`]
+pub struct LenientIpv4RouteTable<R = BufReader<File>> {
+    table: Ipv4RouteTable<R>,
+    report: ParseReport,
+}
+
+#[cfg(feature = "std")]
+impl<R> LenientIpv4RouteTable<R> {
+    /// Malformed lines encountered so far
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Counters of the lines read so far, see [`Ipv4RouteTable::stats`]
+    pub fn stats(&self) -> TableStats {
+        self.table.lines.stats()
+    }
+
+    pub fn into_report(self) -> ParseReport {
+        self.report
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for LenientIpv4RouteTable<R> {
+    type Item = Ipv4RouteEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.table.next()? {
+                Ok(entry) => return Some(entry),
+                Err(err) => {
+                    // a failed read leaves nothing sensible to resume from
+                    let fatal = matches!(err.source, RouteParseError::Io(_));
+                    self.report.record(err);
+                    if fatal {
+                        return None;
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -89,34 +487,391 @@ impl FromStr for Ipv4RouteEntry {
     type Err = RouteParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let fields: Vec<&str> = line.split_whitespace().collect();
-        const IPV4_ROUTE_FILED_COUNT: usize = 11;
-        if fields.len() < IPV4_ROUTE_FILED_COUNT {
-            return Err(RouteParseError::InvalidFieldCount {
-                expected: IPV4_ROUTE_FILED_COUNT,
-                found: fields.len(),
+        Ipv4RouteEntryRef::parse(line).map(Ipv4RouteEntry::from)
+    }
+}
+
+const IPV4_ROUTE_FIELD_COUNT: usize = 11;
+
+fn parse_fields<'a>(
+    line: &'a str,
+    layout: Option<&Ipv4RouteLayout>,
+    max_name_len: usize,
+) -> Result<Ipv4RouteEntryRef<'a>, RouteParseError> {
+    let SplitLine {
+        fields,
+        found,
+        width,
+    } = Schema::split(layout, line);
+    if found < width {
+        return Err(RouteParseError::InvalidFieldCount {
+            expected: width,
+            found,
+        });
+    }
+    let [
+        name,
+        dest,
+        gateway,
+        flags,
+        ref_count,
+        use_count,
+        metric,
+        mask,
+        mtu,
+        window,
+        irtt,
+    ] = fields;
+    RawIpv4RouteEntry {
+        name,
+        dest,
+        gateway,
+        flags,
+        ref_count,
+        use_count,
+        metric,
+        mask,
+        mtu,
+        window,
+        irtt,
+    }
+    .parse_with_name_len(max_name_len)
+}
+
+/// The columns of a `/proc/net/route` line exactly as the kernel printed them.
+///
+/// Useful to check the typed conversion against a captured file; [`RawIpv4RouteEntry::parse`]
+/// converts the fields on demand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawIpv4RouteEntry<'a> {
+    pub name: &'a str,
+    /// Little-endian hex
+    pub dest: &'a str,
+    /// Little-endian hex
+    pub gateway: &'a str,
+    /// Hex
+    pub flags: &'a str,
+    pub ref_count: &'a str,
+    pub use_count: &'a str,
+    pub metric: &'a str,
+    /// Little-endian hex
+    pub mask: &'a str,
+    pub mtu: &'a str,
+    pub window: &'a str,
+    pub irtt: &'a str,
+}
+
+impl<'a> RawIpv4RouteEntry<'a> {
+    /// Split a route line into its columns without converting them
+    pub fn split(line: &'a str) -> Result<Self, RouteParseError> {
+        let mut fields = line.split_whitespace();
+        let mut columns = [""; IPV4_ROUTE_FIELD_COUNT];
+        for (found, column) in columns.iter_mut().enumerate() {
+            *column = fields.next().ok_or(RouteParseError::InvalidFieldCount {
+                expected: IPV4_ROUTE_FIELD_COUNT,
+                found,
+            })?;
+        }
+        let [
+            name,
+            dest,
+            gateway,
+            flags,
+            ref_count,
+            use_count,
+            metric,
+            mask,
+            mtu,
+            window,
+            irtt,
+        ] = columns;
+        Ok(Self {
+            name,
+            dest,
+            gateway,
+            flags,
+            ref_count,
+            use_count,
+            metric,
+            mask,
+            mtu,
+            window,
+            irtt,
+        })
+    }
+
+    /// Convert the columns into a typed entry
+    pub fn parse(&self) -> Result<Ipv4RouteEntryRef<'a>, RouteParseError> {
+        self.parse_with_name_len(IFNAME_MAX_LEN)
+    }
+
+    fn parse_with_name_len(
+        &self,
+        max_name_len: usize,
+    ) -> Result<Ipv4RouteEntryRef<'a>, RouteParseError> {
+        IfName::validate_len(self.name, max_name_len)?;
+        use Ipv4RouteColumn as Column;
+        Ok(Ipv4RouteEntryRef {
+            name: self.name,
+            dest: in_column(Column::Destination, hex_str_to_ipv4(self.dest))?,
+            gateway: in_column(Column::Gateway, hex_str_to_ipv4(self.gateway))?,
+            flags: Ipv4RouteFlags::from_bits_retain(u16::from_be_bytes(in_column(
+                Column::Flags,
+                hex_str_to_array(self.flags),
+            )?)),
+            ref_count: in_column(Column::RefCnt, self.ref_count.parse())?,
+            use_count: in_column(Column::Use, self.use_count.parse())?,
+            metric: in_column(Column::Metric, self.metric.parse())?,
+            mask: in_column(Column::Mask, hex_str_to_ipv4(self.mask))?,
+            mtu: in_column(Column::Mtu, self.mtu.parse())?,
+            window: in_column(Column::Window, self.window.parse())?,
+            irtt: in_column(Column::Irtt, self.irtt.parse())?,
+        })
+    }
+}
+
+impl Ipv4RouteEntry {
+    /// Header line of `/proc/net/route`, padded the same way the kernel pads it
+    pub const PROC_HEADER: &str = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT                                                       ";
+
+    /// Serialize the entry into a `/proc/net/route` line (without the trailing newline)
+    pub fn to_proc_line(&self) -> String {
+        let line = format!(
+            "{}\t{}\t{}\t{:04X}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            ipv4_to_hex_str(self.dest),
+            ipv4_to_hex_str(self.gateway),
+            self.flags.bits(),
+            self.ref_count,
+            self.use_count,
+            self.metric,
+            ipv4_to_hex_str(self.mask),
+            self.mtu_raw(),
+            self.window_raw(),
+            self.irtt_raw(),
+        );
+        // the kernel pads every record to 127 columns
+        format!("{line:<127}")
+    }
+
+    /// The default route `0.0.0.0/0` through `gateway`, as `ip route add default via` installs it
+    pub fn default_via(gateway: Ipv4Addr, name: IfName) -> Self {
+        Self {
+            name,
+            dest: Ipv4Addr::UNSPECIFIED,
+            gateway,
+            flags: Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY,
+            ref_count: 0,
+            use_count: 0,
+            metric: 0,
+            mask: Ipv4Addr::UNSPECIFIED,
+            mtu: None,
+            window: None,
+            irtt: None,
+        }
+    }
+
+    /// Start building an entry bound to the interface `name`
+    pub fn builder(name: impl Into<Arc<str>>) -> Ipv4RouteEntryBuilder {
+        Ipv4RouteEntryBuilder::new(name)
+    }
+
+    /// The `Flags` column as the kernel printed it, unless the table was read with
+    /// [`UnknownFlagBits::Drop`](crate::UnknownFlagBits::Drop)
+    pub fn flags_raw(&self) -> u16 {
+        self.flags.bits()
+    }
+
+    /// The `MTU` column as the kernel printed it
+    pub fn mtu_raw(&self) -> u32 {
+        self.mtu.unwrap_or(0)
+    }
+
+    /// The `Window` column as the kernel printed it
+    pub fn window_raw(&self) -> u32 {
+        self.window.unwrap_or(0)
+    }
+
+    /// The `IRTT` column as the kernel printed it
+    pub fn irtt_raw(&self) -> u32 {
+        self.irtt.unwrap_or(0)
+    }
+
+    /// Bits of the `Flags` column this crate has no name for, e.g. to log them, see
+    /// [`Ipv4RouteFlags::unknown_bits`]
+    pub fn unknown_flag_bits(&self) -> u16 {
+        self.flags.unknown_bits()
+    }
+
+    /// Number of leading one bits in the mask
+    pub fn prefix_len(&self) -> u8 {
+        u32::from(self.mask).leading_ones() as u8
+    }
+
+    /// Whether this is a default route: destination `0.0.0.0` with mask `0.0.0.0`
+    pub fn is_default(&self) -> bool {
+        self.dest.is_unspecified() && self.mask.is_unspecified()
+    }
+
+    /// Whether the route targets a single host, either flagged `HOST` or with a /32 mask
+    pub fn is_host_route(&self) -> bool {
+        self.flags.contains(Ipv4RouteFlags::HOST) || self.mask == Ipv4Addr::BROADCAST
+    }
+
+    /// Whether packets are forwarded to [`Self::gateway`] rather than delivered on-link
+    pub fn uses_gateway(&self) -> bool {
+        self.flags.contains(Ipv4RouteFlags::GATEWAY)
+    }
+
+    /// Whether matching packets are dropped with an ICMP unreachable
+    pub fn is_reject(&self) -> bool {
+        self.flags.contains(Ipv4RouteFlags::REJECT)
+    }
+
+    /// Scope the kernel gives the route, see [`Ipv4RouteScope`]
+    pub fn scope(&self) -> Ipv4RouteScope {
+        if self.uses_gateway() || self.is_reject() {
+            Ipv4RouteScope::Universe
+        } else if self.dest.is_loopback() && self.prefix_len() >= 8 {
+            Ipv4RouteScope::Host
+        } else {
+            Ipv4RouteScope::Link
+        }
+    }
+
+    /// [`Self::mtu`], `None` as well when the kernel prints 0 for "unset"
+    pub fn path_mtu(&self) -> Option<NonZeroU32> {
+        self.mtu.and_then(NonZeroU32::new)
+    }
+
+    /// [`Self::irtt`], which the kernel prints in milliseconds rather than jiffies
+    pub fn initial_rtt(&self) -> Option<Duration> {
+        self.irtt.map(|irtt| Duration::from_millis(irtt.into()))
+    }
+}
+
+/// Builder for synthetic [`Ipv4RouteEntry`] values.
+///
+/// Every field except the interface name defaults to zero, and the flags default to `UP`.
+#[derive(Debug, Clone)]
+pub struct Ipv4RouteEntryBuilder {
+    name: Arc<str>,
+    dest: Ipv4Addr,
+    prefix_len: u8,
+    gateway: Ipv4Addr,
+    flags: Ipv4RouteFlags,
+    ref_count: u32,
+    use_count: u32,
+    metric: u32,
+    mtu: Option<u32>,
+    window: Option<u32>,
+    irtt: Option<u32>,
+}
+
+impl Ipv4RouteEntryBuilder {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Self {
+            name: name.into(),
+            dest: Ipv4Addr::UNSPECIFIED,
+            prefix_len: 0,
+            gateway: Ipv4Addr::UNSPECIFIED,
+            flags: Ipv4RouteFlags::UP,
+            ref_count: 0,
+            use_count: 0,
+            metric: 0,
+            mtu: None,
+            window: None,
+            irtt: None,
+        }
+    }
+
+    pub fn dest(mut self, dest: Ipv4Addr) -> Self {
+        self.dest = dest;
+        self
+    }
+
+    /// Prefix length of the destination, the mask is derived from it
+    pub fn prefix_len(mut self, prefix_len: u8) -> Self {
+        self.prefix_len = prefix_len;
+        self
+    }
+
+    /// Gateway address, requires [`Ipv4RouteFlags::GATEWAY`] to be set as well
+    pub fn gateway(mut self, gateway: Ipv4Addr) -> Self {
+        self.gateway = gateway;
+        self
+    }
+
+    pub fn flags(mut self, flags: Ipv4RouteFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn ref_count(mut self, ref_count: u32) -> Self {
+        self.ref_count = ref_count;
+        self
+    }
+
+    pub fn use_count(mut self, use_count: u32) -> Self {
+        self.use_count = use_count;
+        self
+    }
+
+    pub fn metric(mut self, metric: u32) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Path MTU, setting [`Ipv4RouteFlags::MTU`] as well
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    /// TCP window clamp, setting [`Ipv4RouteFlags::WINDOW`] as well
+    pub fn window(mut self, window: u32) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Initial round trip time, setting [`Ipv4RouteFlags::IRTT`] as well
+    pub fn irtt(mut self, irtt: u32) -> Self {
+        self.irtt = Some(irtt);
+        self
+    }
+
+    pub fn build(self) -> Result<Ipv4RouteEntry, ValidationError> {
+        const MAX_PREFIX_LEN: u8 = 32;
+        let name = IfName::new(self.name)?;
+        if self.prefix_len > MAX_PREFIX_LEN {
+            return Err(ValidationError::PrefixTooLong {
+                len: self.prefix_len,
+                max: MAX_PREFIX_LEN,
             });
         }
-        let get_field = |i: usize| {
-            fields
-                .get(i)
-                .cloned()
-                .ok_or(RouteParseError::MissingField(i))
-        };
+        if !self.gateway.is_unspecified() && !self.flags.contains(Ipv4RouteFlags::GATEWAY) {
+            return Err(ValidationError::GatewayWithoutFlag(self.gateway.into()));
+        }
+        let mask = u32::MAX
+            .checked_shl((MAX_PREFIX_LEN - self.prefix_len) as u32)
+            .unwrap_or(0);
+        // the flags say which of the optional values are present
+        let mut flags = self.flags;
+        flags.set(Ipv4RouteFlags::MTU, self.mtu.is_some());
+        flags.set(Ipv4RouteFlags::WINDOW, self.window.is_some());
+        flags.set(Ipv4RouteFlags::IRTT, self.irtt.is_some());
         Ok(Ipv4RouteEntry {
-            name: get_field(0)?.to_string(),
-            dest: hex_str_to_ipv4(get_field(1)?)?,
-            gateway: hex_str_to_ipv4(get_field(2)?)?,
-            flags: Ipv4RouteFlags::from_bits_retain(u16::from_be_bytes(
-                (*hex_str_to_bytes(get_field(3)?)?).try_into()?,
-            )),
-            ref_count: hex_char_to_u8(get_field(4)?.as_bytes()[0])?,
-            use_count: hex_char_to_u8(get_field(5)?.as_bytes()[0])?,
-            metric: hex_char_to_u8(get_field(6)?.as_bytes()[0])?,
-            mask: hex_str_to_ipv4(get_field(7)?)?,
-            mtu: hex_char_to_u8(get_field(8)?.as_bytes()[0])?,
-            window: hex_char_to_u8(get_field(9)?.as_bytes()[0])?,
-            irtt: hex_char_to_u8(get_field(10)?.as_bytes()[0])?,
+            name,
+            dest: self.dest,
+            gateway: self.gateway,
+            flags,
+            ref_count: self.ref_count,
+            use_count: self.use_count,
+            metric: self.metric,
+            mask: Ipv4Addr::from(mask),
+            mtu: self.mtu,
+            window: self.window,
+            irtt: self.irtt,
         })
     }
 }
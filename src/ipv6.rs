@@ -1,17 +1,29 @@
+#[cfg(feature = "tracing")]
+use crate::trace;
+#[cfg(feature = "std")]
 use crate::{
-    RouteParseError,
+    AddressFamily, LineError, ParseReport, RouteTableOptions, TableStats, UnknownFlagBits,
+    report::TableLines, utils::NameInterner,
+};
+use crate::{
+    IFNAME_MAX_LEN, IfName, IpNet, RouteParseError, ValidationError,
     ipv4::Ipv4RouteFlags,
-    utils::{hex_char_pair_to_byte, hex_str_to_bytes, hex_str_to_ipv6},
+    schema::{Schema, SplitLine, TableColumn, in_column},
+    utils::{hex_str_to_array, hex_str_to_ipv6, ipv6_to_hex_str},
 };
+use alloc::{format, string::String, sync::Arc};
+use core::{fmt, net::Ipv6Addr, str::FromStr};
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "std")]
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Lines, Result as IoResult},
-    net::Ipv6Addr,
+    io::{BufRead, BufReader, Result as IoResult},
     path::Path,
-    str::FromStr,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ipv6RouteEntry {
     pub dest: Ipv6Addr,
     pub dest_prefix: u8,
@@ -22,42 +34,138 @@ pub struct Ipv6RouteEntry {
     pub ref_count: u32,
     pub use_count: u32,
     pub flags: Ipv6RouteFlags,
-    pub name: String,
+    /// `None` for routes without a device, which the kernel prints with an empty name
+    pub name: Option<IfName>,
+}
+
+/// [`Ipv6RouteEntry`] borrowing the interface name from the parsed line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv6RouteEntryRef<'a> {
+    pub dest: Ipv6Addr,
+    pub dest_prefix: u8,
+    pub src: Ipv6Addr,
+    pub src_prefix: u8,
+    pub next_hop: Ipv6Addr,
+    pub metric: u32,
+    pub ref_count: u32,
+    pub use_count: u32,
+    pub flags: Ipv6RouteFlags,
+    pub name: &'a str,
+}
+
+impl<'a> Ipv6RouteEntryRef<'a> {
+    /// Parse and [validate](Self::validate) a route line without allocating
+    pub fn parse(line: &'a str) -> Result<Self, RouteParseError> {
+        let entry = Self::parse_with(line, false)?;
+        entry.validate()?;
+        Ok(entry)
+    }
+
+    /// Parse a route line, keeping values that [`Self::validate`] would reject
+    pub fn parse_unvalidated(line: &'a str) -> Result<Self, RouteParseError> {
+        Self::parse_with(line, false)
+    }
+
+    /// Parse and validate a route line, accepting a missing device name and ignoring extra trailing columns
+    pub fn parse_tolerant(line: &'a str) -> Result<Self, RouteParseError> {
+        let entry = Self::parse_with(line, true)?;
+        entry.validate()?;
+        Ok(entry)
+    }
+
+    /// Check the interface name, unless empty, that both prefix lengths fit an IPv6 address and
+    /// that a next hop comes with the `GATEWAY` flag
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_with_name_len(IFNAME_MAX_LEN)
+    }
+
+    pub(crate) fn validate_with_name_len(
+        &self,
+        max_name_len: usize,
+    ) -> Result<(), ValidationError> {
+        if !self.name.is_empty() {
+            IfName::validate_len(self.name, max_name_len)?;
+        }
+        const MAX_PREFIX_LEN: u8 = 128;
+        for len in [self.dest_prefix, self.src_prefix] {
+            if len > MAX_PREFIX_LEN {
+                return Err(ValidationError::PrefixTooLong {
+                    len,
+                    max: MAX_PREFIX_LEN,
+                });
+            }
+        }
+        if !self.next_hop.is_unspecified() && !self.flags.contains(Ipv6RouteFlags::GATEWAY) {
+            return Err(ValidationError::GatewayWithoutFlag(self.next_hop.into()));
+        }
+        Ok(())
+    }
+
+    fn parse_with(line: &'a str, tolerant: bool) -> Result<Self, RouteParseError> {
+        parse_fields(line, tolerant, None, IFNAME_MAX_LEN)
+    }
+
+    pub fn to_owned(&self) -> Ipv6RouteEntry {
+        Ipv6RouteEntry::from(self.clone())
+    }
+
+    fn into_owned_with_name(self, name: Option<IfName>) -> Ipv6RouteEntry {
+        Ipv6RouteEntry {
+            name,
+            dest: self.dest,
+            dest_prefix: self.dest_prefix,
+            src: self.src,
+            src_prefix: self.src_prefix,
+            next_hop: self.next_hop,
+            metric: self.metric,
+            ref_count: self.ref_count,
+            use_count: self.use_count,
+            flags: self.flags,
+        }
+    }
+}
+
+impl From<Ipv6RouteEntryRef<'_>> for Ipv6RouteEntry {
+    fn from(entry: Ipv6RouteEntryRef<'_>) -> Self {
+        let name = (!entry.name.is_empty()).then(|| IfName::new_unchecked(entry.name.into()));
+        entry.into_owned_with_name(name)
+    }
 }
 
 bitflags::bitflags! {
-    #[derive(Debug,Clone)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Ipv6RouteFlags:u32 {
         /// Route is active and available (RTF_UP)
         /// Indicates the route is valid and operational
-        const UP = Ipv4RouteFlags::UP.bits() as u32;
+        const UP = 0x00000001;
         /// Route uses a gateway (RTF_GATEWAY)
         /// When set, the nexthop field contains a valid gateway address
-        const GATEWAY = Ipv4RouteFlags::GATEWAY.bits() as u32;
+        const GATEWAY = 0x00000002;
         /// Host route (specific to single host) (RTF_HOST)
         /// Indicates the destination is a complete host address
-        const HOST = Ipv4RouteFlags::HOST.bits() as u32;
+        const HOST = 0x00000004;
         /// Reinstate route for dynamic routing (RTF_REINSTATE)
         /// Used by routing daemons to restore routes after link recovery
-        const REINSTATE = Ipv4RouteFlags::REINSTATE.bits() as u32;
+        const REINSTATE = 0x00000008;
         /// Dynamically installed route (RTF_DYNAMIC)
         /// Created by routing daemon or redirect, not static configuration
-        const DYNAMIC = Ipv4RouteFlags::DYNAMIC.bits() as u32;
+        const DYNAMIC = 0x00000010;
         /// Modified route (RTF_MODIFIED)
         /// Altered by ICMP redirect or other dynamic update
-        const MODIFIED = Ipv4RouteFlags::MODIFIED.bits() as u32;
+        const MODIFIED = 0x00000020;
         /// MTU field is valid (RTF_MTU)
         /// Specifies Path MTU Discovery information for this route
-        const MTU = Ipv4RouteFlags::MTU.bits() as u32;
+        const MTU = 0x00000040;
         /// Window field is valid (RTF_WINDOW)
         /// Contains TCP window clamp value for this route
-        const WINDOW = Ipv4RouteFlags::WINDOW.bits() as u32;
+        const WINDOW = 0x00000080;
         /// Initial RTT estimate (RTF_IRTT)
         /// Contains TCP initial round trip time estimate (in milliseconds)
-        const IRTT = Ipv4RouteFlags::IRTT.bits() as u32;
+        const IRTT = 0x00000100;
         /// Reject route (RTF_REJECT)
         /// Packets will be dropped with ICMP unreachable error
-        const REJECT = Ipv4RouteFlags::REJECT.bits() as u32;
+        const REJECT = 0x00000200;
         /// Default route learned via Neighbor Discovery (ND) protocol.
         /// Corresponds to `RTF_DEFAULT` (0x00010000).
         const DEFAULT = 0x00010000;
@@ -92,18 +200,6 @@ bitflags::bitflags! {
         /// Policy-based routing entry (non-standard path selection).
         /// Corresponds to `RTF_POLICY` (0x04000000).
         const POLICY = 0x04000000;
-        /// Reserved preference value (should not be used).
-        /// Part of RTF_PREF mask (0x18000000).
-        const PREF_RESERVED = 0;
-        /// High priority route preference (value 1 << 27).
-        /// Part of RTF_PREF mask (0x18000000).
-        const PREF_HIGH = 1 << 27;
-        /// Medium priority route preference (value 2 << 27).
-        /// Part of RTF_PREF mask (0x18000000).
-        const PREF_MEDIUM = 2 << 27;
-        /// Low priority route preference (value 3 << 27).
-        /// Part of RTF_PREF mask (0x18000000).
-        const PREF_LOW = 3 << 27;
         /// Per-CPU route cache entry (kernel-managed optimization).
         /// Corresponds to `RTF_PCPU` (0x40000000).
         /// Note: Read-only flag, cannot be set by userspace.
@@ -114,15 +210,255 @@ bitflags::bitflags! {
     }
 }
 
-pub struct Ipv6RouteTable {
-    line_iter: Lines<BufReader<File>>,
+impl Ipv6RouteFlags {
+    /// Bits without a named flag, leaving out the router preference
+    pub fn unknown_bits(self) -> u32 {
+        self.bits() & !(Self::all().bits() | PREF_MASK)
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn decode(self, handling: UnknownFlagBits) -> Result<Self, RouteParseError> {
+        match handling {
+            UnknownFlagBits::Retain => Ok(self),
+            UnknownFlagBits::Drop => Ok(Self::from_bits_retain(self.bits() & !self.unknown_bits())),
+            UnknownFlagBits::Reject if self.unknown_bits() != 0 => {
+                Err(RouteParseError::UnknownFlagBits(self.unknown_bits()))
+            }
+            UnknownFlagBits::Reject => Ok(self),
+        }
+    }
 }
 
+/// Keeps bits without a named flag so that no information from the route file is lost
+impl From<u32> for Ipv6RouteFlags {
+    fn from(bits: u32) -> Self {
+        Self::from_bits_retain(bits)
+    }
+}
+
+/// Names of the set flags joined by `|`, unknown bits printed in hex, e.g. `UP | GATEWAY | 0x8000`
+impl fmt::Display for Ipv6RouteFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        bitflags::parser::to_writer(self, f)
+    }
+}
+
+/// The flags below `0x10000` share their values with the IPv4 ones
+impl From<Ipv4RouteFlags> for Ipv6RouteFlags {
+    fn from(flags: Ipv4RouteFlags) -> Self {
+        Self::from_bits_retain(flags.bits().into())
+    }
+}
+
+/// The two-bit router preference field of the flags (RTF_PREF mask), see [`Ipv6RoutePref`]
+const PREF_MASK: u32 = 0x18000000;
+const PREF_SHIFT: u32 = 27;
+
+/// A column of `/proc/net/ipv6_route`, which has no header line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ipv6RouteColumn {
+    Destination,
+    DestPrefix,
+    Source,
+    SourcePrefix,
+    NextHop,
+    Metric,
+    RefCnt,
+    Use,
+    Flags,
+    Device,
+}
+
+impl Ipv6RouteColumn {
+    /// Every column, in the order the kernel prints them
+    pub const ALL: [Self; IPV6_ROUTE_FIELD_COUNT] = [
+        Self::Destination,
+        Self::DestPrefix,
+        Self::Source,
+        Self::SourcePrefix,
+        Self::NextHop,
+        Self::Metric,
+        Self::RefCnt,
+        Self::Use,
+        Self::Flags,
+        Self::Device,
+    ];
+
+    /// The column's name in a header line written for it, as taken by [`Schema::from_header`]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Destination => "Destination",
+            Self::DestPrefix => "DestPrefix",
+            Self::Source => "Source",
+            Self::SourcePrefix => "SourcePrefix",
+            Self::NextHop => "NextHop",
+            Self::Metric => "Metric",
+            Self::RefCnt => "RefCnt",
+            Self::Use => "Use",
+            Self::Flags => "Flags",
+            Self::Device => "Device",
+        }
+    }
+}
+
+impl TableColumn for Ipv6RouteColumn {
+    const ALL: &'static [Self] = &Self::ALL;
+
+    fn name(self) -> &'static str {
+        self.name()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Column order of a `/proc/net/ipv6_route` source
+pub type Ipv6RouteLayout = Schema<Ipv6RouteColumn>;
+
+/// Layouts kernels are known to print `/proc/net/ipv6_route` with, told apart by their width
+const IPV6_ROUTE_LAYOUTS: &[&[Ipv6RouteColumn]] = &[&Ipv6RouteColumn::ALL];
+
+impl Ipv6RouteLayout {
+    /// The known layout of lines with `count` fields, if any
+    pub fn for_field_count(count: usize) -> Option<Self> {
+        IPV6_ROUTE_LAYOUTS
+            .iter()
+            .find(|columns| columns.len() == count)
+            .map(|columns| {
+                Self::from_columns(columns.iter().copied().map(Some))
+                    .expect("registered layouts hold every column")
+            })
+    }
+}
+
+#[cfg(feature = "std")]
+/// Iterator over the entries of a `/proc/net/ipv6_route` formatted source
+pub struct Ipv6RouteTable<R = BufReader<File>> {
+    lines: TableLines<R>,
+    layout: Option<Ipv6RouteLayout>,
+    names: Option<NameInterner>,
+    tolerant: bool,
+    /// Entries with any of these flags are dropped
+    skip_flags: Ipv6RouteFlags,
+    skip_validation: bool,
+    unknown_flag_bits: UnknownFlagBits,
+    max_name_len: usize,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "std")]
 impl Ipv6RouteTable {
     pub fn open(file_path: impl AsRef<Path>) -> IoResult<Self> {
-        let reader = File::open_buffered(file_path)?;
-        let line_iter = reader.lines();
-        Ok(Self { line_iter })
+        Self::open_with(file_path, RouteTableOptions::default())
+    }
+
+    pub fn open_with(file_path: impl AsRef<Path>, options: RouteTableOptions) -> IoResult<Self> {
+        let mut table = Self::from_reader(File::open_buffered(&file_path)?, options);
+        table.lines.set_path(file_path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, file_path.as_ref());
+        Ok(table)
+    }
+
+    /// Open the table in lenient mode, which skips malformed lines and records them in a [`ParseReport`]
+    pub fn open_lenient(file_path: impl AsRef<Path>) -> IoResult<LenientIpv6RouteTable> {
+        Ok(Self::open(file_path)?.lenient())
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Ipv6RouteTable<BufReader<GzDecoder<File>>> {
+    /// Open a gzip-compressed capture such as the `ipv6_route.gz` of a sosreport
+    pub fn open_compressed(file_path: impl AsRef<Path>) -> IoResult<Self> {
+        Self::open_compressed_with(file_path, RouteTableOptions::default())
+    }
+
+    pub fn open_compressed_with(
+        file_path: impl AsRef<Path>,
+        options: RouteTableOptions,
+    ) -> IoResult<Self> {
+        let reader = BufReader::new(GzDecoder::new(File::open(&file_path)?));
+        let mut table = Self::from_reader(reader, options);
+        table.lines.set_path(file_path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, file_path.as_ref());
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Ipv6RouteTable<R> {
+    /// Read the table from any buffered source
+    pub fn from_reader(reader: R, options: RouteTableOptions) -> Self {
+        Self {
+            lines: TableLines::new(reader, false, Some(AddressFamily::Ipv6))
+                .with_limits(options.limits),
+            max_name_len: options.max_ifname_len(),
+            layout: options.ipv6_layout,
+            names: options.intern_names.then(NameInterner::default),
+            tolerant: options.tolerant,
+            skip_flags: if options.skip_ipv6_cache {
+                Ipv6RouteFlags::CACHE
+            } else {
+                Ipv6RouteFlags::empty()
+            },
+            skip_validation: options.skip_validation,
+            unknown_flag_bits: options.unknown_flag_bits,
+            #[cfg(feature = "tracing")]
+            span: trace::table_span("ipv6_route"),
+        }
+    }
+
+    /// Drop cloned `RTF_CACHE` entries, such as the PMTU exceptions the kernel keeps per destination
+    pub fn without_cache_entries(mut self) -> Self {
+        self.skip_flags |= Ipv6RouteFlags::CACHE;
+        self
+    }
+
+    /// Drop the `RTF_LOCAL` and `RTF_ANYCAST` host entries of the local table.
+    ///
+    /// Together with [`Ipv6RouteTable::without_cache_entries`] this leaves the unicast routes
+    /// `ip -6 route show` prints, plus the `ff00::/8` multicast route and the null entry on `lo`.
+    pub fn without_local(mut self) -> Self {
+        self.skip_flags |= Ipv6RouteFlags::LOCAL | Ipv6RouteFlags::ANYCAST;
+        self
+    }
+
+    /// Switch to lenient mode, see [`Ipv6RouteTable::open_lenient`]
+    pub fn lenient(self) -> LenientIpv6RouteTable<R> {
+        LenientIpv6RouteTable {
+            table: self,
+            report: ParseReport::default(),
+        }
+    }
+
+    /// Yield only the entries, silently skipping lines that fail to parse
+    pub fn entries_lossy(self) -> impl Iterator<Item = Ipv6RouteEntry> {
+        self.entries_lossy_with(|_| {})
+    }
+
+    /// Like [`Ipv6RouteTable::entries_lossy`], handing every skipped line to `on_error`
+    pub fn entries_lossy_with(
+        self,
+        mut on_error: impl FnMut(LineError),
+    ) -> impl Iterator<Item = Ipv6RouteEntry> {
+        self.filter_map(move |entry| entry.map_err(&mut on_error).ok())
+    }
+
+    /// Counters of the lines read so far
+    pub fn stats(&self) -> TableStats {
+        self.lines.stats()
+    }
+
+    /// The layout lines are parsed with.
+    ///
+    /// Without [`RouteTableOptions::ipv6_layout`] it is looked up by the width of the first line
+    /// with [`Ipv6RouteLayout::for_field_count`], falling back to the
+    /// [default](Ipv6RouteLayout::default) one, so this is `None` until the first call to `next`.
+    pub fn layout(&self) -> Option<&Ipv6RouteLayout> {
+        self.layout.as_ref()
     }
 }
 
@@ -130,44 +466,547 @@ impl FromStr for Ipv6RouteEntry {
     type Err = RouteParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let fields: Vec<&str> = line.split_whitespace().collect();
-        if fields.len() < 10 {
-            return Err(RouteParseError::InvalidFieldCount {
-                expected: 10,
-                found: fields.len(),
-            });
+        Ipv6RouteEntryRef::parse(line).map(Ipv6RouteEntry::from)
+    }
+}
+
+const IPV6_ROUTE_FIELD_COUNT: usize = 10;
+
+fn parse_fields<'a>(
+    line: &'a str,
+    tolerant: bool,
+    layout: Option<&Ipv6RouteLayout>,
+    max_name_len: usize,
+) -> Result<Ipv6RouteEntryRef<'a>, RouteParseError> {
+    let SplitLine {
+        fields,
+        found,
+        width,
+    } = Schema::split(layout, line);
+    // the kernel prints an empty device name for routes without a device
+    let device_last =
+        layout.is_none_or(|layout| layout.columns().last() == Some(&Some(Ipv6RouteColumn::Device)));
+    let missing_device = tolerant && device_last && found + 1 == width;
+    if (found < width && !missing_device) || (found > width && !tolerant) {
+        return Err(RouteParseError::InvalidFieldCount {
+            expected: width,
+            found,
+        });
+    }
+    let [
+        dest,
+        dest_prefix,
+        src,
+        src_prefix,
+        next_hop,
+        metric,
+        ref_count,
+        use_count,
+        flags,
+        name,
+    ] = fields;
+    RawIpv6RouteEntry {
+        dest,
+        dest_prefix,
+        src,
+        src_prefix,
+        next_hop,
+        metric,
+        ref_count,
+        use_count,
+        flags,
+        name,
+    }
+    .parse_with_name_len(max_name_len)
+}
+
+/// The columns of a `/proc/net/ipv6_route` line exactly as the kernel printed them.
+///
+/// Useful to check the typed conversion against a captured file; [`RawIpv6RouteEntry::parse`]
+/// converts the fields on demand. All columns but the name are hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawIpv6RouteEntry<'a> {
+    pub dest: &'a str,
+    pub dest_prefix: &'a str,
+    pub src: &'a str,
+    pub src_prefix: &'a str,
+    pub next_hop: &'a str,
+    pub metric: &'a str,
+    pub ref_count: &'a str,
+    pub use_count: &'a str,
+    pub flags: &'a str,
+    pub name: &'a str,
+}
+
+impl<'a> RawIpv6RouteEntry<'a> {
+    /// Split a route line into its columns without converting them
+    pub fn split(line: &'a str) -> Result<Self, RouteParseError> {
+        let mut fields = line.split_whitespace();
+        let mut columns = [""; IPV6_ROUTE_FIELD_COUNT];
+        for (found, column) in columns.iter_mut().enumerate() {
+            *column = fields.next().ok_or(RouteParseError::InvalidFieldCount {
+                expected: IPV6_ROUTE_FIELD_COUNT,
+                found,
+            })?;
         }
-        let get_field = |i: usize| {
-            fields
-                .get(i)
-                .cloned()
-                .ok_or(RouteParseError::MissingField(i))
-        };
+        let [
+            dest,
+            dest_prefix,
+            src,
+            src_prefix,
+            next_hop,
+            metric,
+            ref_count,
+            use_count,
+            flags,
+            name,
+        ] = columns;
+        Ok(Self {
+            dest,
+            dest_prefix,
+            src,
+            src_prefix,
+            next_hop,
+            metric,
+            ref_count,
+            use_count,
+            flags,
+            name,
+        })
+    }
 
-        Ok(Ipv6RouteEntry {
-            dest: hex_str_to_ipv6(get_field(0)?)?,
-            dest_prefix: hex_char_pair_to_byte(get_field(1)?.as_bytes().try_into()?)?,
-            src: hex_str_to_ipv6(get_field(2)?)?,
-            src_prefix: hex_char_pair_to_byte(get_field(3)?.as_bytes().try_into()?)?,
-            next_hop: hex_str_to_ipv6(get_field(4)?)?,
-            metric: u32::from_be_bytes((*hex_str_to_bytes(get_field(5)?)?).try_into()?),
-            ref_count: u32::from_be_bytes((*hex_str_to_bytes(get_field(6)?)?).try_into()?),
-            use_count: u32::from_be_bytes((*hex_str_to_bytes(get_field(7)?)?).try_into()?),
-            flags: Ipv6RouteFlags::from_bits_retain(u32::from_be_bytes(
-                (*hex_str_to_bytes(get_field(8)?)?).try_into()?,
-            )),
-            name: get_field(9)?.to_string(),
+    /// Convert the columns into a typed entry
+    pub fn parse(&self) -> Result<Ipv6RouteEntryRef<'a>, RouteParseError> {
+        self.parse_with_name_len(IFNAME_MAX_LEN)
+    }
+
+    fn parse_with_name_len(
+        &self,
+        max_name_len: usize,
+    ) -> Result<Ipv6RouteEntryRef<'a>, RouteParseError> {
+        use Ipv6RouteColumn as Column;
+        let [dest_prefix] = in_column(Column::DestPrefix, hex_str_to_array(self.dest_prefix))?;
+        let [src_prefix] = in_column(Column::SourcePrefix, hex_str_to_array(self.src_prefix))?;
+        let hex_u32 =
+            |column, field| in_column(column, hex_str_to_array(field)).map(u32::from_be_bytes);
+        if !self.name.is_empty() {
+            IfName::validate_len(self.name, max_name_len)?;
+        }
+        Ok(Ipv6RouteEntryRef {
+            dest: in_column(Column::Destination, hex_str_to_ipv6(self.dest))?,
+            dest_prefix,
+            src: in_column(Column::Source, hex_str_to_ipv6(self.src))?,
+            src_prefix,
+            next_hop: in_column(Column::NextHop, hex_str_to_ipv6(self.next_hop))?,
+            metric: hex_u32(Column::Metric, self.metric)?,
+            ref_count: hex_u32(Column::RefCnt, self.ref_count)?,
+            use_count: hex_u32(Column::Use, self.use_count)?,
+            flags: Ipv6RouteFlags::from_bits_retain(hex_u32(Column::Flags, self.flags)?),
+            name: self.name,
         })
     }
 }
 
-impl Iterator for Ipv6RouteTable {
-    type Item = Result<Ipv6RouteEntry, RouteParseError>;
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for Ipv6RouteTable<R> {
+    type Item = Result<Ipv6RouteEntry, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+        loop {
+            let (line_number, line_result) = self.lines.next_line()?;
+            let line = match line_result {
+                Ok(line) => line,
+                Err(err) => {
+                    self.lines.record(false);
+                    return Some(Err(self.lines.error(line_number, String::new(), err)));
+                }
+            };
+            let layout = self.layout.get_or_insert_with(|| {
+                Ipv6RouteLayout::for_field_count(line.split_whitespace().count())
+                    .unwrap_or_default()
+            });
+            let parsed = parse_fields(&line, self.tolerant, Some(layout), self.max_name_len);
+            let parsed = parsed.and_then(|mut entry| {
+                entry.flags = entry.flags.decode(self.unknown_flag_bits)?;
+                if !self.skip_validation {
+                    entry.validate_with_name_len(self.max_name_len)?;
+                }
+                Ok(entry)
+            });
+            self.lines.record(parsed.is_ok());
+            let entry = match parsed {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(self.lines.error(line_number, line, err))),
+            };
+            if entry.flags.intersects(self.skip_flags) {
+                continue;
+            }
+            return Some(Ok(match &mut self.names {
+                Some(names) => {
+                    let name = (!entry.name.is_empty()).then(|| names.intern(entry.name));
+                    entry.into_owned_with_name(name)
+                }
+                None => Ipv6RouteEntry::from(entry),
+            }));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// An [`Ipv6RouteTable`] yielding only the valid entries, see [`Ipv6RouteTable::open_lenient`]
+pub struct LenientIpv6RouteTable<R = BufReader<File>> {
+    table: Ipv6RouteTable<R>,
+    report: ParseReport,
+}
+
+#[cfg(feature = "std")]
+impl<R> LenientIpv6RouteTable<R> {
+    /// Malformed lines encountered so far
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Counters of the lines read so far, see [`Ipv6RouteTable::stats`]
+    pub fn stats(&self) -> TableStats {
+        self.table.lines.stats()
+    }
+
+    pub fn into_report(self) -> ParseReport {
+        self.report
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for LenientIpv6RouteTable<R> {
+    type Item = Ipv6RouteEntry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.line_iter.next().map(|line_result| {
-            let line = line_result?;
-            line.parse::<Ipv6RouteEntry>()
+        loop {
+            match self.table.next()? {
+                Ok(entry) => return Some(entry),
+                Err(err) => {
+                    // a failed read leaves nothing sensible to resume from
+                    let fatal = matches!(err.source, RouteParseError::Io(_));
+                    self.report.record(err);
+                    if fatal {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Ipv6RouteEntry {
+    /// Serialize the entry into a `/proc/net/ipv6_route` line (without the trailing newline)
+    pub fn to_proc_line(&self) -> String {
+        format!(
+            "{} {:02x} {} {:02x} {} {:08x} {:08x} {:08x} {:08x} {:>8}",
+            ipv6_to_hex_str(self.dest),
+            self.dest_prefix,
+            ipv6_to_hex_str(self.src),
+            self.src_prefix,
+            ipv6_to_hex_str(self.next_hop),
+            self.metric,
+            self.ref_count,
+            self.use_count,
+            self.flags.bits(),
+            self.name.as_deref().unwrap_or_default(),
+        )
+    }
+
+    /// Metric the kernel gives routes added without one (`IP6_RT_PRIO_USER`)
+    pub const USER_METRIC: u32 = 1024;
+
+    /// Metric of the routes the kernel adds for configured addresses (`IP6_RT_PRIO_ADDRCONF`)
+    pub const ADDRCONF_METRIC: u32 = 256;
+
+    /// The link-local prefix `fe80::/64`
+    pub const LINK_LOCAL_PREFIX: (Ipv6Addr, u8) = (Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0), 64);
+
+    /// The default route `::/0` through `next_hop`, as `ip -6 route add default via` installs it
+    pub fn default_via(next_hop: Ipv6Addr, name: IfName) -> Self {
+        Self {
+            dest: Ipv6Addr::UNSPECIFIED,
+            dest_prefix: 0,
+            src: Ipv6Addr::UNSPECIFIED,
+            src_prefix: 0,
+            next_hop,
+            metric: Self::USER_METRIC,
+            ref_count: 0,
+            use_count: 0,
+            flags: Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY,
+            name: Some(name),
+        }
+    }
+
+    /// The `fe80::/64` route the kernel adds to every interface with IPv6 enabled
+    pub fn link_local_route(name: IfName) -> Self {
+        let (dest, dest_prefix) = Self::LINK_LOCAL_PREFIX;
+        Self {
+            dest,
+            dest_prefix,
+            src: Ipv6Addr::UNSPECIFIED,
+            src_prefix: 0,
+            next_hop: Ipv6Addr::UNSPECIFIED,
+            metric: Self::ADDRCONF_METRIC,
+            ref_count: 0,
+            use_count: 0,
+            flags: Ipv6RouteFlags::UP,
+            name: Some(name),
+        }
+    }
+
+    /// Start building an entry bound to the interface `name`
+    pub fn builder(name: impl Into<Arc<str>>) -> Ipv6RouteEntryBuilder {
+        Ipv6RouteEntryBuilder::new(name)
+    }
+
+    /// The flags column as the kernel printed it, router preference included, unless the table
+    /// was read with [`UnknownFlagBits::Drop`](crate::UnknownFlagBits::Drop)
+    pub fn flags_raw(&self) -> u32 {
+        self.flags.bits()
+    }
+
+    /// Bits of the flags column this crate has no name for, e.g. to log them, see
+    /// [`Ipv6RouteFlags::unknown_bits`]
+    pub fn unknown_flag_bits(&self) -> u32 {
+        self.flags.unknown_bits()
+    }
+
+    /// Source prefix the route is restricted to (`from` of `ip -6 route`), `::/0` for any source
+    pub fn source_network(&self) -> IpNet {
+        IpNet::new(self.src.into(), self.src_prefix.min(128)).expect("prefix length is clamped")
+    }
+
+    /// Whether packets from `source` may take the route. Without a source only routes that
+    /// accept any source match, as in the kernel's lookup with an unspecified source address.
+    pub fn matches_source(&self, source: Option<Ipv6Addr>) -> bool {
+        match source {
+            Some(source) => self.source_network().contains(source.into()),
+            None => self.src_prefix == 0,
+        }
+    }
+
+    /// Scope of the destination, see [`Ipv6RouteScope`]
+    pub fn scope(&self) -> Ipv6RouteScope {
+        if self
+            .flags
+            .intersects(Ipv6RouteFlags::LOCAL | Ipv6RouteFlags::ANYCAST)
+            || self.dest.is_loopback() && self.dest_prefix == 128
+        {
+            return Ipv6RouteScope::Host;
+        }
+        if self.is_multicast_route() && self.dest_prefix >= 16 {
+            // the low nibble of the second byte is the multicast scope
+            return match self.dest.octets()[1] & 0x0f {
+                0x1 => Ipv6RouteScope::Host,
+                0x2 => Ipv6RouteScope::Link,
+                0x3..=0x5 => Ipv6RouteScope::Site,
+                _ => Ipv6RouteScope::Global,
+            };
+        }
+        if self.is_link_local() {
+            Ipv6RouteScope::Link
+        } else if self.is_ula() || self.covered_by(0xfec0 << 112, 10) {
+            Ipv6RouteScope::Site
+        } else {
+            Ipv6RouteScope::Global
+        }
+    }
+
+    /// Router preference (RFC 4191) stored in bits 27–28 of the flags
+    pub fn preference(&self) -> Ipv6RoutePref {
+        Ipv6RoutePref::from_bits((self.flags.bits() & PREF_MASK) >> PREF_SHIFT)
+    }
+
+    /// Whether the destination lies within `fe80::/10`
+    pub fn is_link_local(&self) -> bool {
+        self.covered_by(0xfe80 << 112, 10)
+    }
+
+    /// Whether the destination lies within `ff00::/8`
+    pub fn is_multicast_route(&self) -> bool {
+        self.covered_by(0xff00 << 112, 8)
+    }
+
+    /// Whether the destination lies within the unique local range `fc00::/7`
+    pub fn is_ula(&self) -> bool {
+        self.covered_by(0xfc00 << 112, 7)
+    }
+
+    /// Whether the destination prefix falls entirely inside `prefix/len`
+    fn covered_by(&self, prefix: u128, len: u8) -> bool {
+        let mask = u128::MAX << (128 - len);
+        self.dest_prefix >= len && u128::from(self.dest) & mask == prefix
+    }
+}
+
+/// Reach of an IPv6 route's destination, from narrowest to widest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ipv6RouteScope {
+    /// Addresses of this host: `LOCAL`/`ANYCAST` routes, `::1/128` and interface-local multicast
+    Host,
+    /// `fe80::/10` and link-local multicast
+    Link,
+    /// Unique local `fc00::/7`, deprecated site-local `fec0::/10` and site-local multicast
+    Site,
+    /// Everything else, including the default route
+    Global,
+}
+
+/// Router preference of an IPv6 route (RFC 4191), ordered from least to most preferred
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ipv6RoutePref {
+    /// The reserved encoding, which the kernel treats as an invalid preference
+    Invalid,
+    Low,
+    Medium,
+    High,
+}
+
+impl Ipv6RoutePref {
+    /// Decode the kernel's two-bit encoding (`ICMPV6_ROUTER_PREF_*`)
+    fn from_bits(bits: u32) -> Self {
+        match bits & 0b11 {
+            0b00 => Self::Medium,
+            0b01 => Self::High,
+            0b11 => Self::Low,
+            _ => Self::Invalid,
+        }
+    }
+
+    fn to_bits(self) -> u32 {
+        match self {
+            Self::Medium => 0b00,
+            Self::High => 0b01,
+            Self::Invalid => 0b10,
+            Self::Low => 0b11,
+        }
+    }
+}
+
+/// Builder for synthetic [`Ipv6RouteEntry`] values.
+///
+/// Every field except the interface name defaults to zero, and the flags default to `UP`.
+#[derive(Debug, Clone)]
+pub struct Ipv6RouteEntryBuilder {
+    dest: Ipv6Addr,
+    dest_prefix: u8,
+    src: Ipv6Addr,
+    src_prefix: u8,
+    next_hop: Ipv6Addr,
+    metric: u32,
+    ref_count: u32,
+    use_count: u32,
+    flags: Ipv6RouteFlags,
+    preference: Option<Ipv6RoutePref>,
+    name: Arc<str>,
+}
+
+impl Ipv6RouteEntryBuilder {
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Self {
+            dest: Ipv6Addr::UNSPECIFIED,
+            dest_prefix: 0,
+            src: Ipv6Addr::UNSPECIFIED,
+            src_prefix: 0,
+            next_hop: Ipv6Addr::UNSPECIFIED,
+            metric: 0,
+            ref_count: 0,
+            use_count: 0,
+            flags: Ipv6RouteFlags::UP,
+            preference: None,
+            name: name.into(),
+        }
+    }
+
+    pub fn dest(mut self, dest: Ipv6Addr) -> Self {
+        self.dest = dest;
+        self
+    }
+
+    pub fn dest_prefix(mut self, dest_prefix: u8) -> Self {
+        self.dest_prefix = dest_prefix;
+        self
+    }
+
+    pub fn src(mut self, src: Ipv6Addr) -> Self {
+        self.src = src;
+        self
+    }
+
+    pub fn src_prefix(mut self, src_prefix: u8) -> Self {
+        self.src_prefix = src_prefix;
+        self
+    }
+
+    /// Next hop address, requires [`Ipv6RouteFlags::GATEWAY`] to be set as well
+    pub fn next_hop(mut self, next_hop: Ipv6Addr) -> Self {
+        self.next_hop = next_hop;
+        self
+    }
+
+    pub fn metric(mut self, metric: u32) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn ref_count(mut self, ref_count: u32) -> Self {
+        self.ref_count = ref_count;
+        self
+    }
+
+    pub fn use_count(mut self, use_count: u32) -> Self {
+        self.use_count = use_count;
+        self
+    }
+
+    pub fn flags(mut self, flags: Ipv6RouteFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Router preference, overriding any preference bits passed to [`Ipv6RouteEntryBuilder::flags`]
+    pub fn preference(mut self, preference: Ipv6RoutePref) -> Self {
+        self.preference = Some(preference);
+        self
+    }
+
+    pub fn build(self) -> Result<Ipv6RouteEntry, ValidationError> {
+        const MAX_PREFIX_LEN: u8 = 128;
+        let name = IfName::new(self.name)?;
+        for len in [self.dest_prefix, self.src_prefix] {
+            if len > MAX_PREFIX_LEN {
+                return Err(ValidationError::PrefixTooLong {
+                    len,
+                    max: MAX_PREFIX_LEN,
+                });
+            }
+        }
+        if !self.next_hop.is_unspecified() && !self.flags.contains(Ipv6RouteFlags::GATEWAY) {
+            return Err(ValidationError::GatewayWithoutFlag(self.next_hop.into()));
+        }
+        let flags = match self.preference {
+            Some(preference) => Ipv6RouteFlags::from_bits_retain(
+                self.flags.bits() & !PREF_MASK | preference.to_bits() << PREF_SHIFT,
+            ),
+            None => self.flags,
+        };
+        Ok(Ipv6RouteEntry {
+            dest: self.dest,
+            dest_prefix: self.dest_prefix,
+            src: self.src,
+            src_prefix: self.src_prefix,
+            next_hop: self.next_hop,
+            metric: self.metric,
+            ref_count: self.ref_count,
+            use_count: self.use_count,
+            flags,
+            name: Some(name),
         })
     }
 }
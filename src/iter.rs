@@ -0,0 +1,137 @@
+use crate::{Ipv4RouteEntry, Ipv6RouteEntry, LineError, Route, RouteFilter};
+use alloc::{sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Items of the route table iterators: either an entry or a per-line result
+pub trait RouteItem {
+    type Route: Route;
+
+    /// The entry, or `None` for a line that failed to parse
+    fn route(&self) -> Option<&Self::Route>;
+}
+
+impl RouteItem for Ipv4RouteEntry {
+    type Route = Self;
+
+    fn route(&self) -> Option<&Self> {
+        Some(self)
+    }
+}
+
+impl RouteItem for Ipv6RouteEntry {
+    type Route = Self;
+
+    fn route(&self) -> Option<&Self> {
+        Some(self)
+    }
+}
+
+impl RouteItem for Result<Ipv4RouteEntry, LineError> {
+    type Route = Ipv4RouteEntry;
+
+    fn route(&self) -> Option<&Ipv4RouteEntry> {
+        self.as_ref().ok()
+    }
+}
+
+impl RouteItem for Result<Ipv6RouteEntry, LineError> {
+    type Route = Ipv6RouteEntry;
+
+    fn route(&self) -> Option<&Ipv6RouteEntry> {
+        self.as_ref().ok()
+    }
+}
+
+/// Streaming filters over route iterators.
+///
+/// Errors are passed through untouched so the filters never hide a malformed line.
+pub trait RouteIteratorExt: Iterator<Item: RouteItem> + Sized {
+    /// Keep routes flagged `UP`
+    fn up(self) -> impl Iterator<Item = Self::Item> {
+        self.filter(|item| item.route().is_none_or(Route::is_up))
+    }
+
+    /// Keep routes whose output interface is `iface`
+    fn on_iface(self, iface: &str) -> impl Iterator<Item = Self::Item> {
+        self.filter(move |item| item.route().is_none_or(|route| route.interface() == iface))
+    }
+
+    /// Keep default routes, the ones with a zero-length prefix
+    fn defaults(self) -> impl Iterator<Item = Self::Item> {
+        self.filter(|item| item.route().is_none_or(|route| route.prefix_len() == 0))
+    }
+
+    /// Keep routes forwarding through a gateway
+    fn with_gateway(self) -> impl Iterator<Item = Self::Item> {
+        self.filter(|item| item.route().is_none_or(|route| route.gateway().is_some()))
+    }
+
+    /// Keep routes passing `filter`, see [`parse_filter`](crate::parse_filter)
+    fn matching(self, filter: &RouteFilter) -> impl Iterator<Item = Self::Item> {
+        self.filter(move |item| item.route().is_none_or(|route| filter.matches(route)))
+    }
+
+    /// Read in chunks of up to `batch_size` items, stopping early once the [`Batches::token`] is
+    /// cancelled
+    fn read_in_batches(self, batch_size: usize) -> Batches<Self> {
+        Batches {
+            inner: self,
+            batch_size: batch_size.max(1),
+            token: CancellationToken::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item: RouteItem>> RouteIteratorExt for I {}
+
+/// A flag shared between threads to stop a [`Batches`] read
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the read before its next batch; the batch in progress is still delivered
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Iterator over chunks of a route table, see [`RouteIteratorExt::read_in_batches`]
+#[derive(Debug)]
+pub struct Batches<I> {
+    inner: I,
+    batch_size: usize,
+    token: CancellationToken,
+}
+
+impl<I> Batches<I> {
+    /// A handle that cancels this read, e.g. from a UI thread
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Use an existing token instead of the one created for this read
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+}
+
+impl<I: Iterator> Iterator for Batches<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.token.is_cancelled() {
+            return None;
+        }
+        let batch: Vec<_> = self.inner.by_ref().take(self.batch_size).collect();
+        (!batch.is_empty()).then_some(batch)
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(feature = "std")]
+use crate::ProcRoot;
+use alloc::string::String;
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result as IoResult},
+};
+use thiserror::Error;
+
+/// A kernel release that does not start with a `major.minor` version
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid kernel release {0:?}")]
+pub struct KernelVersionError(pub String);
+
+/// Version of a Linux kernel, from a release string such as `6.8.0-45-generic` or `3.10.14+`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KernelVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl KernelVersion {
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+/// Takes the leading version numbers and ignores the rest of the release, so the vendor suffix
+/// of `2.6.36.4brcmarm+` leaves `2.6.36`
+impl FromStr for KernelVersion {
+    type Err = KernelVersionError;
+
+    fn from_str(release: &str) -> Result<Self, Self::Err> {
+        let error = || KernelVersionError(release.into());
+        let mut numbers = release.split('.').map(|part| {
+            let digits = part.len() - part.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            part[..digits].parse::<u16>().ok()
+        });
+        let major = numbers.next().flatten().ok_or_else(error)?;
+        let minor = numbers.next().flatten().ok_or_else(error)?;
+        let patch = numbers.next().flatten().unwrap_or_default();
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for KernelVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How the route files of one kernel differ from those of a current one.
+///
+/// The default profile is that of a current kernel. Apply a profile to a table with
+/// [`RouteTableOptions::format_profile`](crate::RouteTableOptions::format_profile).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatProfile {
+    /// The kernel the profile was picked for
+    pub kernel: Option<KernelVersion>,
+    /// Whether `/proc/net/ipv6_route` lists cloned `CACHE` routes, which moved out of the FIB
+    /// into per-route exception tables in 4.15. Tables read with the profile drop them.
+    pub ipv6_cached_routes: bool,
+    /// Whether `/proc/net/ipv6_route` may lack the device column or have extra ones, as on the
+    /// vendor 3.x and older kernels of embedded routers, see
+    /// [`RouteTableOptions::tolerant`](crate::RouteTableOptions::tolerant)
+    pub tolerant: bool,
+    /// Whether interface names may be longer than the kernel's limit, see
+    /// [`RouteTableOptions::long_ifnames`](crate::RouteTableOptions::long_ifnames)
+    pub long_ifnames: bool,
+}
+
+impl FormatProfile {
+    /// The quirks of `kernel`'s route files
+    pub fn for_kernel(kernel: KernelVersion) -> Self {
+        Self {
+            kernel: Some(kernel),
+            ipv6_cached_routes: kernel < KernelVersion::new(4, 15, 0),
+            tolerant: kernel.major < 4,
+            long_ifnames: false,
+        }
+    }
+
+    /// The quirks of an OpenWrt or other busybox and musl based router running `kernel`.
+    ///
+    /// Router firmware names its interfaces after the logical networks, such as `pppoe-wan`,
+    /// `br-lan` or `6in4-henet`, and captures from vendor builds may hold names beyond the
+    /// kernel's limit or drop columns, so the profile reads tolerantly and accepts long names.
+    pub fn openwrt(kernel: KernelVersion) -> Self {
+        Self {
+            tolerant: true,
+            long_ifnames: true,
+            ..Self::for_kernel(kernel)
+        }
+    }
+
+    /// The profile of the running kernel, from `/proc/sys/kernel/osrelease`
+    #[cfg(feature = "std")]
+    pub fn detect() -> IoResult<Self> {
+        ProcRoot::default().format_profile()
+    }
+}
+
+#[cfg(feature = "std")]
+impl ProcRoot {
+    /// `sys/kernel/osrelease`, the release of the kernel as printed by `uname -r`
+    pub fn kernel_release(&self) -> IoResult<String> {
+        let release = fs::read_to_string(self.path().join("sys/kernel/osrelease"))?;
+        Ok(release.trim_end().into())
+    }
+
+    /// Version of the kernel, see [`Self::kernel_release`]
+    pub fn kernel_version(&self) -> IoResult<KernelVersion> {
+        self.kernel_release()?
+            .parse()
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Format quirks of the kernel's route files, see [`FormatProfile::for_kernel`]
+    pub fn format_profile(&self) -> IoResult<FormatProfile> {
+        Ok(FormatProfile::for_kernel(self.kernel_version()?))
+    }
+
+    /// Whether the kernel is one of the Windows Subsystem for Linux, whose release names
+    /// Microsoft, e.g. `5.15.153.1-microsoft-standard-WSL2`
+    pub fn is_wsl(&self) -> bool {
+        self.kernel_release()
+            .is_ok_and(|release| release.to_ascii_lowercase().contains("microsoft"))
+    }
+}
+
+/// Whether this process runs under the Windows Subsystem for Linux, see [`ProcRoot::is_wsl`]
+#[cfg(feature = "std")]
+pub fn is_wsl() -> bool {
+    ProcRoot::default().is_wsl()
+}
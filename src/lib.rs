@@ -1,30 +1,294 @@
-#![feature(ip_from)]
-#![feature(slice_pattern)]
-#![feature(file_buffered)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "std", feature(file_buffered))]
+
+extern crate alloc;
+
+mod aggregate;
+mod analyze;
+mod arp;
+#[cfg(feature = "std")]
+mod backend;
+#[cfg(all(feature = "std", any(target_os = "macos", target_os = "freebsd")))]
+mod bsd;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "dbus")]
+mod dbus;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+mod expect;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "exporter")]
+mod exporter;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filter;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod health;
+#[cfg(feature = "std")]
+pub mod history;
+mod ifname;
+#[cfg(feature = "std")]
+mod index;
+#[cfg(feature = "std")]
+mod interface;
+#[cfg(feature = "interop")]
+mod interop;
+#[cfg(feature = "serde")]
+pub mod iproute2;
 mod ipv4;
 mod ipv6;
+mod iter;
+mod kernel;
+#[cfg(feature = "std")]
+mod live;
+mod mac;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod multicast;
+mod net;
+#[cfg(feature = "netlink-write")]
+mod netlink;
+mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "probe")]
+mod probe;
+#[cfg(feature = "std")]
+mod proc_root;
+#[cfg(feature = "std")]
+mod reconcile;
+mod render;
+#[cfg(feature = "std")]
+mod report;
+mod route;
+mod schema;
+mod select;
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "std")]
+mod snmp;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod source;
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+mod systemd;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "tracing")]
+mod trace;
 mod utils;
+#[cfg(feature = "verify")]
+mod verify;
+pub mod vpn;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+mod watch;
+#[cfg(all(feature = "std", windows))]
+mod windows;
 
 use crate::utils::ConvertError;
-pub use ipv4::{Ipv4RouteEntry, Ipv4RouteFlags, Ipv4RouteTable};
-pub use ipv6::{Ipv6RouteEntry, Ipv6RouteFlags, Ipv6RouteTable};
-use std::io::Result as IoResult;
+pub use aggregate::summarize;
+use alloc::{boxed::Box, string::String};
+pub use analyze::{
+    GatewayInfo, RouteConflict, TableSummary, find_conflicts, gateways, summarize_table,
+    top_routes_by_refcnt, top_routes_by_use,
+};
+#[cfg(feature = "std")]
+pub use arp::ArpTable;
+pub use arp::{ArpEntry, ArpFlags};
+#[cfg(feature = "std")]
+pub use backend::{RouteIter, RouteSource};
+#[cfg(all(feature = "std", any(target_os = "macos", target_os = "freebsd")))]
+pub use bsd::{get_ipv4_route_table, get_ipv6_route_table};
+#[cfg(feature = "std")]
+pub use cache::{CacheStats, CachedRouteTable, RouteTables};
+use core::fmt;
+#[cfg(feature = "dbus")]
+pub use dbus::{Bus, RouteMonitorService};
+#[cfg(feature = "std")]
+pub use diff::{RouteChange, RouteEvent, SnapshotDiff, diff_routes};
+#[cfg(feature = "std")]
+pub use expect::{CheckOutcome, ExpectationReport, RouteCheck, RouteExpectation};
+#[cfg(feature = "exporter")]
+pub use exporter::RouteMetricsCollector;
+pub use filter::{FilterError, RouteFilter, parse_filter};
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use health::{FamilyHealth, Ipv6RouteStats, RouteTableHealth};
+pub use ifname::{IFNAME_MAX_LEN, IfName, IfNameError};
+#[cfg(feature = "std")]
+pub use index::RouteIndex;
+#[cfg(feature = "std")]
+pub use interface::{EnrichedRouteEntry, InterfaceInfo, InterfaceResolver, OperState};
+pub use ipv4::{
+    Ipv4RouteColumn, Ipv4RouteEntry, Ipv4RouteEntryBuilder, Ipv4RouteEntryRef, Ipv4RouteFlags,
+    Ipv4RouteLayout, Ipv4RouteScope, RawIpv4RouteEntry,
+};
+#[cfg(feature = "std")]
+pub use ipv4::{Ipv4RouteTable, LenientIpv4RouteTable};
+pub use ipv6::{
+    Ipv6RouteColumn, Ipv6RouteEntry, Ipv6RouteEntryBuilder, Ipv6RouteEntryRef, Ipv6RouteFlags,
+    Ipv6RouteLayout, Ipv6RoutePref, Ipv6RouteScope, RawIpv6RouteEntry,
+};
+#[cfg(feature = "std")]
+pub use ipv6::{Ipv6RouteTable, LenientIpv6RouteTable};
+pub use iter::{Batches, CancellationToken, RouteItem, RouteIteratorExt};
+#[cfg(feature = "std")]
+pub use kernel::is_wsl;
+pub use kernel::{FormatProfile, KernelVersion, KernelVersionError};
+#[cfg(feature = "std")]
+pub use live::LiveRouteTable;
+pub use mac::{MacAddr, MacAddrError};
+#[cfg(feature = "mmap")]
+pub use mmap::MappedCapture;
+pub use multicast::{MulticastCacheEntry, MulticastOif, MulticastVif};
+#[cfg(feature = "std")]
+pub use multicast::{MulticastCacheTable, MulticastVifTable};
+pub use net::IpNet;
+#[cfg(feature = "netlink-write")]
+pub use netlink::{KernelRoute, NetlinkRoute, NetlinkRouteSource, NextHop, RouteManager};
+pub use options::{RouteTableOptions, UnknownFlagBits};
+#[cfg(feature = "icmp-probe")]
+pub use probe::probe_gateway_with_echo;
+#[cfg(feature = "probe")]
+pub use probe::{GatewayProbe, NeighborState, probe_gateway};
+#[cfg(feature = "std")]
+pub use proc_root::{AccessDenied, ProcRoot};
+#[cfg(feature = "netlink-write")]
+pub use reconcile::{ApplyError, reconcile_kernel};
+#[cfg(feature = "std")]
+pub use reconcile::{Plan, PlanAction, RouteSpec, reconcile};
+pub use render::{Column, TableRenderer, TableRow};
+#[cfg(feature = "std")]
+pub use report::{ParseReport, TableStats};
+pub use route::{Metric, Route, RouteEntry};
+pub use schema::{Schema, TableColumn};
+pub use select::{
+    DefaultRouteLoser, DefaultRouteLoss, DefaultRouteSelection, effective_default_route_v4,
+    effective_default_route_v6, select_best, select_best_from,
+};
+#[cfg(feature = "std")]
+pub use snapshot::{Snapshot, SnapshotError, SnapshotV1};
+#[cfg(feature = "std")]
+pub use snmp::{Ipv4RouteCounters, Ipv6RouteCounters, MissingCounter};
+#[cfg(feature = "std")]
+use std::{
+    io::{ErrorKind, Result as IoResult},
+    path::Path,
+    sync::Arc,
+};
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+pub use systemd::SystemdNotifier;
+#[cfg(feature = "testing")]
+pub use testing::MockProcNet;
 use thiserror::Error;
+#[cfg(feature = "verify")]
+pub use verify::{
+    LookupMismatch, VerifyReport, sample_destinations, verify_lookups, verify_lookups_with,
+};
+#[cfg(all(feature = "wasm", target_family = "wasm"))]
+pub use wasm::{
+    ipv4_route_table as get_ipv4_route_table, ipv6_route_table as get_ipv6_route_table,
+};
+#[cfg(feature = "std")]
+pub use watch::{PollingRouteWatcher, WatcherHandle};
+#[cfg(all(feature = "std", windows))]
+pub use windows::{get_ipv4_route_table, get_ipv6_route_table};
+
+/// Internal hex helpers, exposed only to the `cargo fuzz` targets in `fuzz/`
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub mod fuzz_api {
+    use crate::utils::{self, ConvertError};
+    use core::net::{Ipv4Addr, Ipv6Addr};
+
+    pub fn hex_str_to_ipv4(text: &str) -> Result<Ipv4Addr, ConvertError> {
+        utils::hex_str_to_ipv4(text)
+    }
 
-#[cfg(target_os = "linux")]
+    pub fn hex_str_to_ipv6(text: &str) -> Result<Ipv6Addr, ConvertError> {
+        utils::hex_str_to_ipv6(text)
+    }
+
+    pub fn decode_hex_into(text: &str, out: &mut [u8]) -> Result<(), ConvertError> {
+        utils::decode_hex_into(text, out)
+    }
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
 /// Get IPv4 route table via `/proc/net/route`
 pub fn get_ipv4_route_table() -> IoResult<ipv4::Ipv4RouteTable> {
-    ipv4::Ipv4RouteTable::open("/proc/net/route")
+    ProcRoot::default().ipv4_route_table()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get IPv6 route table via `/proc/net/ipv6_route`, see [`ProcRoot::ipv6_route_table`]
+pub fn get_ipv6_route_table() -> Result<ipv6::Ipv6RouteTable, RouteParseError> {
+    ProcRoot::default().ipv6_route_table()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Read both route tables at once, see [`ProcRoot::route_tables`]
+pub fn get_route_tables() -> Result<(Vec<Ipv4RouteEntry>, Vec<Ipv6RouteEntry>), SnapshotError> {
+    ProcRoot::default().route_tables()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Predict the source address for packets to `dest`, see [`ProcRoot::preferred_source`].
+///
+/// `None` when there is no route to `dest`, no suitable address or the tables cannot be read.
+pub fn preferred_source(dest: core::net::IpAddr) -> Option<core::net::IpAddr> {
+    ProcRoot::default().preferred_source(dest).ok().flatten()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Compare the route tables against the kernel's cache limits, see
+/// [`ProcRoot::route_table_health`]
+pub fn get_route_table_health() -> IoResult<RouteTableHealth> {
+    ProcRoot::default().route_table_health()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv4 routing counters via `/proc/net/snmp` and `/proc/net/netstat`
+pub fn get_ipv4_route_counters() -> IoResult<Ipv4RouteCounters> {
+    ProcRoot::default().ipv4_route_counters()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv6 routing counters via `/proc/net/snmp6`
+pub fn get_ipv6_route_counters() -> IoResult<Ipv6RouteCounters> {
+    ProcRoot::default().ipv6_route_counters()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv4 multicast forwarding cache via `/proc/net/ip_mr_cache`
+pub fn get_ipv4_multicast_cache() -> IoResult<MulticastCacheTable<std::net::Ipv4Addr>> {
+    ProcRoot::default().ipv4_multicast_cache()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv6 multicast forwarding cache via `/proc/net/ip6_mr_cache`
+pub fn get_ipv6_multicast_cache() -> IoResult<MulticastCacheTable<std::net::Ipv6Addr>> {
+    ProcRoot::default().ipv6_multicast_cache()
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv4 multicast vif table via `/proc/net/ip_mr_vif`
+pub fn get_ipv4_multicast_vifs() -> IoResult<MulticastVifTable> {
+    ProcRoot::default().ipv4_multicast_vifs()
 }
 
-#[cfg(target_os = "linux")]
-/// Get IPv6 route table via `/proc/net/ipv6_route`
-pub fn get_ipv6_route_table() -> IoResult<ipv6::Ipv6RouteTable> {
-    ipv6::Ipv6RouteTable::open("/proc/net/ipv6_route")
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Get the IPv6 multicast vif table via `/proc/net/ip6_mr_vif`
+pub fn get_ipv6_multicast_vifs() -> IoResult<MulticastVifTable> {
+    ProcRoot::default().ipv6_multicast_vifs()
 }
 
 #[derive(Debug, Error)]
 pub enum RouteParseError {
+    #[cfg(feature = "std")]
     #[error("I/O error reading route file")]
     Io(#[from] std::io::Error),
 
@@ -38,8 +302,235 @@ pub enum RouteParseError {
     MissingField(usize),
 
     #[error("Failed to convert the slice into u8 array")]
-    SliceToBytes(#[from] std::array::TryFromSliceError),
+    SliceToBytes(#[from] core::array::TryFromSliceError),
+
+    #[error("Failed to parse an integer field")]
+    ParseInt(#[from] core::num::ParseIntError),
+
+    #[error("Failed to parse an IP address field")]
+    AddrParse(#[from] core::net::AddrParseError),
+
+    #[error("IPv6 is disabled on this host")]
+    Ipv6Unavailable,
+
+    #[error("Invalid route entry: {0}")]
+    InvalidEntry(#[from] ValidationError),
+
+    #[error("Invalid header line: {0}")]
+    InvalidHeader(#[from] HeaderError),
+
+    #[error("Invalid interface name: {0}")]
+    InvalidIfName(#[from] IfNameError),
+
+    #[error("Unknown flag bits {0:#x}")]
+    UnknownFlagBits(u32),
+
+    #[error("Invalid {column} field (column {index}): {source}")]
+    InvalidField {
+        /// Header name of the column
+        column: &'static str,
+        /// Position of the column in the kernel's layout, see [`TableColumn::index`]
+        index: usize,
+        source: Box<RouteParseError>,
+    },
+
+    #[error("{limit} exceeds the limit of {max}")]
+    LimitExceeded { limit: Limit, max: usize },
+}
+
+impl RouteParseError {
+    /// The kind of error as a stable code
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(err) => ErrorCode::of_io(err),
+            Self::Convert(_) | Self::SliceToBytes(_) | Self::ParseInt(_) | Self::AddrParse(_) => {
+                ErrorCode::InvalidValue
+            }
+            Self::InvalidFieldCount { .. } | Self::MissingField(_) => ErrorCode::FieldCount,
+            Self::Ipv6Unavailable => ErrorCode::Ipv6Unavailable,
+            Self::InvalidEntry(_) => ErrorCode::InvalidEntry,
+            Self::InvalidHeader(_) => ErrorCode::InvalidHeader,
+            Self::InvalidIfName(_) => ErrorCode::InvalidIfName,
+            Self::UnknownFlagBits(_) => ErrorCode::UnknownFlagBits,
+            Self::InvalidField { source, .. } => source.error_code(),
+            Self::LimitExceeded { .. } => ErrorCode::LimitExceeded,
+        }
+    }
+}
+
+/// Stable identifiers of the errors of this crate, to branch on errors that crossed an FFI or
+/// serialization boundary. The numeric values never change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[repr(u16)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// An I/O error other than the ones below
+    Io = 1,
+    /// The file exists but may not be read, see [`AccessDenied`]
+    PermissionDenied = 2,
+    NotFound = 3,
+    /// IPv6 is disabled on the host
+    Ipv6Unavailable = 4,
+    /// A line has too few or too many fields
+    FieldCount = 10,
+    /// A field does not hold a value of its column's type
+    InvalidValue = 11,
+    /// See [`ValidationError`]
+    InvalidEntry = 12,
+    /// See [`HeaderError`]
+    InvalidHeader = 13,
+    InvalidIfName = 14,
+    UnknownFlagBits = 15,
+    /// See [`Limit`]
+    LimitExceeded = 16,
+    /// A saved snapshot is not valid JSON of a [`Snapshot`]
+    MalformedSnapshot = 20,
+    /// A saved snapshot is of a version this release cannot read, see [`SnapshotV1`]
+    UnsupportedSnapshotVersion = 21,
+}
+
+impl ErrorCode {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn of_io(err: &std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::NotFound => Self::NotFound,
+            _ => Self::Io,
+        }
+    }
+
+    /// The code in `snake_case`, as serialized
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Io => "io",
+            Self::PermissionDenied => "permission_denied",
+            Self::NotFound => "not_found",
+            Self::Ipv6Unavailable => "ipv6_unavailable",
+            Self::FieldCount => "field_count",
+            Self::InvalidValue => "invalid_value",
+            Self::InvalidEntry => "invalid_entry",
+            Self::InvalidHeader => "invalid_header",
+            Self::InvalidIfName => "invalid_if_name",
+            Self::UnknownFlagBits => "unknown_flag_bits",
+            Self::LimitExceeded => "limit_exceeded",
+            Self::MalformedSnapshot => "malformed_snapshot",
+            Self::UnsupportedSnapshotVersion => "unsupported_snapshot_version",
+        }
+    }
+}
+
+/// A bound on the input of a table, see [`RouteTableOptions::max_line_len`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// Bytes in a line, without its terminator
+    LineLength,
+    /// Whitespace separated fields in a line
+    FieldCount,
+    /// Lines after the header
+    Entries,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::LineLength => "Line length",
+            Self::FieldCount => "Field count",
+            Self::Entries => "Entry count",
+        })
+    }
+}
+
+/// The address family of the entries of a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
 }
 
-#[cfg(not(target_os = "linux"))]
-compile_error!("This crate can only be compiled on Linux systems.");
+/// A [`RouteParseError`] located at a line of a route file
+#[derive(Debug, Error)]
+#[error("Failed to parse line {line_number}: {source}")]
+pub struct LineError {
+    /// 1-based line number within the route file
+    pub line_number: usize,
+    /// The offending line, empty if reading the line itself failed
+    pub raw: String,
+    pub source: RouteParseError,
+    /// Family of the table, `None` for tables that hold both
+    pub family: Option<AddressFamily>,
+    /// The file the line was read from, `None` for tables read from memory
+    #[cfg(feature = "std")]
+    pub path: Option<Arc<Path>>,
+}
+
+#[cfg(feature = "std")]
+impl LineError {
+    pub(crate) fn new(line_number: usize, raw: String, source: RouteParseError) -> Self {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(line_number, error = %source, "malformed line");
+        Self {
+            line_number,
+            raw,
+            source,
+            family: None,
+            path: None,
+        }
+    }
+
+    pub(crate) fn in_table(
+        mut self,
+        family: Option<AddressFamily>,
+        path: Option<&Arc<Path>>,
+    ) -> Self {
+        self.family = family;
+        self.path = path.cloned();
+        self
+    }
+
+    /// The kind of error as a stable code
+    pub fn error_code(&self) -> ErrorCode {
+        self.source.error_code()
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("Prefix length {len} exceeds the maximum of {max}")]
+    PrefixTooLong { len: u8, max: u8 },
+
+    #[error("Gateway {0} is set but the GATEWAY flag is not")]
+    GatewayWithoutFlag(core::net::IpAddr),
+
+    #[error("Netmask {0} has non-contiguous one bits")]
+    NonContiguousMask(core::net::Ipv4Addr),
+
+    #[error("{0}")]
+    InvalidIfName(#[from] IfNameError),
+
+    #[error("Gateway {gateway} is not in the address family of {destination}")]
+    GatewayFamily {
+        destination: IpNet,
+        gateway: core::net::IpAddr,
+    },
+}
+
+/// A header line that does not describe the columns of its table
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HeaderError {
+    #[error("the {0} column is missing")]
+    MissingColumn(&'static str),
+
+    #[error("the {0} column appears more than once")]
+    DuplicateColumn(&'static str),
+}
@@ -0,0 +1,103 @@
+use crate::{ProcRoot, RouteEntry, RouteSource, RouteTables, SnapshotError};
+use std::{
+    net::IpAddr,
+    sync::{
+        Arc, PoisonError, RwLock, Weak,
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+/// The current route tables as a shared service object, refreshed by a background thread.
+///
+/// Clones share the same tables. The thread stops once the last clone is dropped; a read that fails
+/// keeps the previous tables until the next refresh.
+#[derive(Debug, Clone)]
+pub struct LiveRouteTable {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    tables: RwLock<Arc<RouteTables>>,
+    /// Wakes the refresher early, and stops it once dropped with the last handle
+    wake: Sender<()>,
+}
+
+impl LiveRouteTable {
+    /// Read the tables of `/proc` now and again every `refresh`
+    pub fn spawn(refresh: Duration) -> Result<Self, SnapshotError> {
+        Self::spawn_with(ProcRoot::default(), refresh)
+    }
+
+    /// Read the tables of `source` now and again every `refresh`
+    pub fn spawn_with(
+        source: impl RouteSource + Send + 'static,
+        refresh: Duration,
+    ) -> Result<Self, SnapshotError> {
+        let tables = RouteTables::read(&source)?;
+        let (wake, woken) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            tables: RwLock::new(Arc::new(tables)),
+            wake,
+        });
+        let weak = Arc::downgrade(&shared);
+        thread::Builder::new()
+            .name("route-refresher".into())
+            .spawn(move || {
+                while let Ok(()) | Err(RecvTimeoutError::Timeout) = woken.recv_timeout(refresh) {
+                    if !refresh_from(&source, &weak) {
+                        return;
+                    }
+                }
+            })?;
+        Ok(Self { shared })
+    }
+
+    /// The most recently read tables
+    pub fn tables(&self) -> Arc<RouteTables> {
+        self.shared
+            .tables
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The route the kernel would use to reach `dest`, see [`select_best`](crate::select_best)
+    pub fn lookup(&self, dest: IpAddr) -> Option<RouteEntry> {
+        self.tables().lookup(dest)
+    }
+
+    /// Gateway of the default route in use, see [`RouteTables::default_gateway`]
+    pub fn default_gateway(&self) -> Option<IpAddr> {
+        self.tables().default_gateway()
+    }
+
+    /// Refresh without waiting for the timer, e.g. on a change reported by a
+    /// [`PollingRouteWatcher`](crate::PollingRouteWatcher)
+    pub fn refresh_now(&self) {
+        let _ = self.shared.wake.send(());
+    }
+}
+
+/// Replace the tables, returning false once every handle is gone
+fn refresh_from(source: &impl RouteSource, shared: &Weak<Shared>) -> bool {
+    let tables = RouteTables::read(source);
+    let Some(shared) = shared.upgrade() else {
+        return false;
+    };
+    match tables {
+        Ok(tables) => {
+            *shared
+                .tables
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = Arc::new(tables);
+        }
+        Err(_err) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_err, "failed to refresh the route tables, keeping the last ones");
+        }
+    }
+    true
+}
@@ -0,0 +1,88 @@
+use crate::utils::parse_mac;
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// A string that is not six colon-separated hex octets
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid hardware address {0:?}")]
+pub struct MacAddrError(pub String);
+
+/// A 48-bit hardware address, as printed in `/proc/net/arp` and `/sys/class/net/*/address`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    /// `00:00:00:00:00:00`, which the kernel prints for unresolved neighbors
+    pub const ZERO: Self = Self([0; 6]);
+    pub const BROADCAST: Self = Self([0xff; 6]);
+
+    pub const fn new(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+
+    pub const fn octets(self) -> [u8; 6] {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    /// Whether the group bit is set, which includes [`Self::BROADCAST`]
+    pub fn is_multicast(self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Whether the address was assigned locally rather than by the vendor, as for most virtual
+    /// interfaces
+    pub fn is_locally_administered(self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = MacAddrError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        parse_mac(address).ok_or_else(|| MacAddrError(address.into()))
+    }
+}
+
+impl TryFrom<String> for MacAddr {
+    type Error = MacAddrError;
+
+    fn try_from(address: String) -> Result<Self, Self::Error> {
+        address.parse()
+    }
+}
+
+impl From<MacAddr> for String {
+    fn from(mac: MacAddr) -> Self {
+        mac.to_string()
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+impl From<MacAddr> for [u8; 6] {
+    fn from(mac: MacAddr) -> Self {
+        mac.0
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{AddressFamily, Ipv4RouteEntryRef, Ipv6RouteEntryRef, LineError, RouteParseError};
+use memmap2::Mmap;
+use std::{fs::File, io, path::Path, str, sync::Arc};
+
+/// A captured `route` or `ipv6_route` file mapped into memory, for bulk offline analysis.
+///
+/// The lines are found by scanning the mapped bytes and parsed into borrowed entries, so nothing is
+/// copied or allocated for well-formed lines. Only map captures: files under `/proc` report a
+/// size of zero and would map as empty.
+#[derive(Debug)]
+pub struct MappedCapture {
+    map: Mmap,
+    path: Arc<Path>,
+}
+
+impl MappedCapture {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        // SAFETY: captures are not expected to change while mapped; a truncation by another
+        // process would fault on access, as with any mapped file
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            map,
+            path: path.as_ref().into(),
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Entries of a `/proc/net/route` capture, skipping its header line
+    pub fn ipv4_entries(&self) -> impl Iterator<Item = Result<Ipv4RouteEntryRef<'_>, LineError>> {
+        self.lines(1).map(|(line_number, line)| {
+            self.parse_line(
+                line_number,
+                line,
+                AddressFamily::Ipv4,
+                Ipv4RouteEntryRef::parse,
+            )
+        })
+    }
+
+    /// Entries of a `/proc/net/ipv6_route` capture
+    pub fn ipv6_entries(&self) -> impl Iterator<Item = Result<Ipv6RouteEntryRef<'_>, LineError>> {
+        self.lines(0).map(|(line_number, line)| {
+            self.parse_line(
+                line_number,
+                line,
+                AddressFamily::Ipv6,
+                Ipv6RouteEntryRef::parse,
+            )
+        })
+    }
+
+    /// Numbered lines without their terminators, after the first `skip` ones
+    fn lines(&self, skip: usize) -> impl Iterator<Item = (usize, &[u8])> {
+        let bytes = self.map.strip_suffix(b"\n").unwrap_or(&self.map);
+        // an empty file has no lines rather than one empty line
+        (!bytes.is_empty())
+            .then(|| bytes.split(|&byte| byte == b'\n'))
+            .into_iter()
+            .flatten()
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .enumerate()
+            .skip(skip)
+            .map(|(index, line)| (index + 1, line))
+    }
+
+    fn parse_line<'a, E>(
+        &self,
+        line_number: usize,
+        line: &'a [u8],
+        family: AddressFamily,
+        parse: impl FnOnce(&'a str) -> Result<E, RouteParseError>,
+    ) -> Result<E, LineError> {
+        let error = |source| {
+            LineError::new(
+                line_number,
+                String::from_utf8_lossy(line).into_owned(),
+                source,
+            )
+            .in_table(Some(family), Some(&self.path))
+        };
+        let text = str::from_utf8(line)
+            .map_err(|err| error(io::Error::new(io::ErrorKind::InvalidData, err).into()))?;
+        parse(text).map_err(error)
+    }
+}
@@ -0,0 +1,291 @@
+#[cfg(feature = "tracing")]
+use crate::trace;
+use crate::{AddressFamily, RouteParseError, utils::hex_str_to_ipv4};
+#[cfg(feature = "std")]
+use crate::{LineError, TableStats, report::TableLines};
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    marker::PhantomData,
+    path::Path,
+};
+
+/// A virtual interface of the multicast router, from `/proc/net/ip_mr_vif` or `/proc/net/ip6_mr_vif`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MulticastVif {
+    pub index: u16,
+    /// Name of the underlying device, `none` for a vif without one
+    pub name: Arc<str>,
+    pub bytes_in: u64,
+    pub packets_in: u64,
+    pub bytes_out: u64,
+    pub packets_out: u64,
+    /// `VIFF_*` flags for IPv4, `MIFF_*` flags for IPv6
+    pub flags: u32,
+    /// Local tunnel endpoint, IPv4 only. Holds the interface index when `VIFF_USE_IFINDEX` is set.
+    pub local: Option<Ipv4Addr>,
+    /// Remote tunnel endpoint, IPv4 only
+    pub remote: Option<Ipv4Addr>,
+}
+
+impl FromStr for MulticastVif {
+    type Err = RouteParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        const IPV4_VIF_FIELD_COUNT: usize = 9;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let field_count_error = RouteParseError::InvalidFieldCount {
+            expected: IPV4_VIF_FIELD_COUNT,
+            found: fields.len(),
+        };
+        let [
+            index,
+            name,
+            bytes_in,
+            packets_in,
+            bytes_out,
+            packets_out,
+            flags,
+            ref endpoints @ ..,
+        ] = fields[..]
+        else {
+            return Err(field_count_error);
+        };
+        // only the IPv4 table has the tunnel endpoint columns
+        let (local, remote) = match *endpoints {
+            [] => (None, None),
+            [local, remote] => (
+                Some(hex_str_to_ipv4(local)?),
+                Some(hex_str_to_ipv4(remote)?),
+            ),
+            _ => return Err(field_count_error),
+        };
+        Ok(Self {
+            index: index.parse()?,
+            name: Arc::from(name),
+            bytes_in: bytes_in.parse()?,
+            packets_in: packets_in.parse()?,
+            bytes_out: bytes_out.parse()?,
+            packets_out: packets_out.parse()?,
+            flags: u32::from_str_radix(flags, 16)?,
+            local,
+            remote,
+        })
+    }
+}
+
+/// An outgoing interface of a multicast forwarding cache entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MulticastOif {
+    /// Index into the vif table
+    pub vif: u16,
+    /// Packets with a lower TTL are not forwarded on this vif
+    pub ttl: u8,
+}
+
+/// An (origin, group) entry of `/proc/net/ip_mr_cache` or `/proc/net/ip6_mr_cache`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MulticastCacheEntry<A = Ipv4Addr> {
+    pub group: A,
+    pub origin: A,
+    /// Vif the packets are expected to arrive on, `None` while the entry is unresolved
+    pub iif: Option<u16>,
+    pub packets: u64,
+    pub bytes: u64,
+    /// Packets that arrived on another vif than `iif`
+    pub wrong_if: u64,
+    pub oifs: Vec<MulticastOif>,
+}
+
+impl<A> MulticastCacheEntry<A> {
+    /// Whether the routing daemon has installed the entry, as opposed to one waiting for it
+    pub fn is_resolved(&self) -> bool {
+        self.iif.is_some()
+    }
+}
+
+/// Address formats of the two multicast cache files
+pub trait MulticastAddr: Sized {
+    const FAMILY: AddressFamily;
+
+    fn parse_field(field: &str) -> Result<Self, RouteParseError>;
+}
+
+impl MulticastAddr for Ipv4Addr {
+    const FAMILY: AddressFamily = AddressFamily::Ipv4;
+
+    /// The raw network-order word in hex, like `/proc/net/route`
+    fn parse_field(field: &str) -> Result<Self, RouteParseError> {
+        Ok(hex_str_to_ipv4(field)?)
+    }
+}
+
+impl MulticastAddr for Ipv6Addr {
+    const FAMILY: AddressFamily = AddressFamily::Ipv6;
+
+    /// Uncompressed colon notation
+    fn parse_field(field: &str) -> Result<Self, RouteParseError> {
+        Ok(field.parse()?)
+    }
+}
+
+impl<A: MulticastAddr> FromStr for MulticastCacheEntry<A> {
+    type Err = RouteParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        const MFC_FIELD_COUNT: usize = 6;
+        let mut fields = line.split_whitespace();
+        let mut columns = [""; MFC_FIELD_COUNT];
+        for (found, column) in columns.iter_mut().enumerate() {
+            *column = fields.next().ok_or(RouteParseError::InvalidFieldCount {
+                expected: MFC_FIELD_COUNT,
+                found,
+            })?;
+        }
+        let [group, origin, iif, packets, bytes, wrong_if] = columns;
+        let oifs = fields
+            .map(|oif| {
+                let (vif, ttl) = oif
+                    .split_once(':')
+                    .ok_or(RouteParseError::MissingField(MFC_FIELD_COUNT))?;
+                Ok(MulticastOif {
+                    vif: vif.parse()?,
+                    ttl: ttl.parse()?,
+                })
+            })
+            .collect::<Result<_, RouteParseError>>()?;
+        // unresolved entries have a parent of -1
+        let iif = iif.parse::<i32>()?;
+        Ok(Self {
+            group: A::parse_field(group)?,
+            origin: A::parse_field(origin)?,
+            iif: u16::try_from(iif).ok(),
+            packets: packets.parse()?,
+            bytes: bytes.parse()?,
+            wrong_if: wrong_if.parse()?,
+            oifs,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+/// Iterator over the entries of a multicast forwarding cache
+pub struct MulticastCacheTable<A = Ipv4Addr, R = BufReader<File>> {
+    lines: TableLines<R>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    family: PhantomData<A>,
+}
+
+#[cfg(feature = "std")]
+impl<A: MulticastAddr> MulticastCacheTable<A> {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut table = Self::from_reader(File::open_buffered(&path)?);
+        table.lines.set_path(path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, path.as_ref());
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: MulticastAddr, R: BufRead> MulticastCacheTable<A, R> {
+    /// Read the table from any buffered source, starting with the header line
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            lines: TableLines::new(reader, true, Some(A::FAMILY)),
+            family: PhantomData,
+            #[cfg(feature = "tracing")]
+            span: trace::table_span("mr_cache"),
+        }
+    }
+
+    /// Counters of the lines read so far
+    pub fn stats(&self) -> TableStats {
+        self.lines.stats()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: MulticastAddr, R: BufRead> Iterator for MulticastCacheTable<A, R> {
+    type Item = Result<MulticastCacheEntry<A>, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+        let (line_number, line) = self.lines.next_line()?;
+        let result = parse_line(&self.lines, line_number, line);
+        self.lines.record(result.is_ok());
+        Some(result)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Iterator over the entries of a multicast vif table
+pub struct MulticastVifTable<R = BufReader<File>> {
+    lines: TableLines<R>,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "std")]
+impl MulticastVifTable {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut table = Self::from_reader(File::open_buffered(&path)?);
+        table.lines.set_path(path.as_ref());
+        #[cfg(feature = "tracing")]
+        trace::record_path(&table.span, path.as_ref());
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> MulticastVifTable<R> {
+    /// Read the table from any buffered source, starting with the header line
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            lines: TableLines::new(reader, true, None),
+            #[cfg(feature = "tracing")]
+            span: trace::table_span("mr_vif"),
+        }
+    }
+
+    /// Counters of the lines read so far
+    pub fn stats(&self) -> TableStats {
+        self.lines.stats()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for MulticastVifTable<R> {
+    type Item = Result<MulticastVif, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "tracing")]
+        let _entered = self.span.clone().entered();
+        let (line_number, line) = self.lines.next_line()?;
+        let result = parse_line(&self.lines, line_number, line);
+        self.lines.record(result.is_ok());
+        Some(result)
+    }
+}
+
+#[cfg(feature = "std")]
+fn parse_line<T: FromStr<Err = RouteParseError>, R: BufRead>(
+    lines: &TableLines<R>,
+    line_number: usize,
+    line: Result<String, RouteParseError>,
+) -> Result<T, LineError> {
+    let line = line.map_err(|err| lines.error(line_number, String::new(), err))?;
+    line.parse()
+        .map_err(|err| lines.error(line_number, line, err))
+}
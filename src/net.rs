@@ -0,0 +1,95 @@
+use crate::ValidationError;
+use core::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// An IPv4 or IPv6 network in CIDR notation, with the host bits cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    /// Network of `addr` with the given prefix length, clearing any host bits of `addr`
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ValidationError> {
+        let max = max_prefix_len(addr);
+        if prefix_len > max {
+            return Err(ValidationError::PrefixTooLong {
+                len: prefix_len,
+                max,
+            });
+        }
+        let addr = from_bits(addr, to_bits(addr) & mask(addr, prefix_len));
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        self.addr.is_ipv4() == addr.is_ipv4()
+            && to_bits(addr) & mask(addr, self.prefix_len) == to_bits(self.addr)
+    }
+
+    /// Whether every address of `other` lies within this network
+    pub fn contains_net(&self, other: &IpNet) -> bool {
+        self.prefix_len <= other.prefix_len && self.contains(other.addr)
+    }
+
+    /// The enclosing network one bit shorter, `None` for `0.0.0.0/0` and `::/0`
+    pub fn supernet(&self) -> Option<IpNet> {
+        let prefix_len = self.prefix_len.checked_sub(1)?;
+        Some(Self::new(self.addr, prefix_len).expect("shorter prefix is valid"))
+    }
+
+    /// Last address of the network
+    pub fn broadcast(&self) -> IpAddr {
+        from_bits(
+            self.addr,
+            to_bits(self.addr) | !mask(self.addr, self.prefix_len) & full_mask(self.addr),
+        )
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+fn max_prefix_len(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn to_bits(addr: IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(addr) => u32::from(addr) as u128,
+        IpAddr::V6(addr) => addr.into(),
+    }
+}
+
+fn from_bits(family: IpAddr, bits: u128) -> IpAddr {
+    match family {
+        IpAddr::V4(_) => Ipv4Addr::from(bits as u32).into(),
+        IpAddr::V6(_) => Ipv6Addr::from(bits).into(),
+    }
+}
+
+fn full_mask(family: IpAddr) -> u128 {
+    u128::MAX >> (128 - max_prefix_len(family) as u32)
+}
+
+fn mask(family: IpAddr, prefix_len: u8) -> u128 {
+    let host_bits = (max_prefix_len(family) - prefix_len) as u32;
+    u128::MAX.checked_shl(host_bits).unwrap_or(0) & full_mask(family)
+}
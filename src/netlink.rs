@@ -0,0 +1,747 @@
+use crate::{
+    IfName, IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, RouteIter,
+    RouteSource,
+};
+use std::{
+    ffi::{CStr, CString},
+    io::{self, Result as IoResult},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+const NLMSG_HDR_LEN: usize = 16;
+const NLMSG_ALIGN: usize = 4;
+const RTMSG_LEN: usize = 12;
+const RTNEXTHOP_LEN: usize = 8;
+/// Report the table the route was found in, missing from libc on musl and Android
+const RTM_F_LOOKUP_TABLE: u32 = 0x1000;
+/// Return the matching route rather than a host route to the destination
+const RTM_F_FIB_MATCH: u32 = 0x2000;
+
+/// One path of a route read back from the kernel
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NextHop {
+    /// Next hop, `None` for an on-link path
+    pub gateway: Option<IpAddr>,
+    /// Output interface, or its index if the interface disappeared while reading
+    pub iface: String,
+    /// Share of the flows sent down this path, 1 unless the route is weighted multipath
+    pub weight: u16,
+}
+
+/// A route read with [`RouteManager::routes`].
+///
+/// An ECMP route is a single entry with one [`NextHop`] per path, which `/proc/net/route` cannot
+/// show at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelRoute {
+    pub destination: IpNet,
+    /// Routing table id, 254 for the main table
+    pub table: u32,
+    /// Kind of route (`RTN_*`), such as `RTN_UNICAST` or `RTN_LOCAL`
+    pub route_type: u8,
+    pub metric: u32,
+    /// Source address hint (`src` in `ip route`)
+    pub preferred_source: Option<IpAddr>,
+    /// Empty for routes without a path, such as unreachable routes
+    pub next_hops: Vec<NextHop>,
+}
+
+impl KernelRoute {
+    /// Decode the payload of an `RTM_NEWROUTE` message, the `rtmsg` header and its attributes,
+    /// such as those a socket subscribed to route changes receives. `None` for families other
+    /// than IPv4 and IPv6 and for a malformed payload.
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        parse_route(payload)
+    }
+}
+
+/// Installs and removes kernel routes through a `NETLINK_ROUTE` socket.
+///
+/// Only the destination, source prefix (IPv6), gateway, output interface and metric of an entry
+/// are transmitted, and entries always go to the main table. A [`KernelRoute`] goes to its own
+/// table, so the routes of [`RouteManager::routes`] can be deleted wherever they are.
+/// Modifying routes requires `CAP_NET_ADMIN`.
+pub struct RouteManager {
+    socket: OwnedFd,
+    seq: u32,
+}
+
+impl RouteManager {
+    pub fn new() -> IoResult<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Self { socket, seq: 0 })
+    }
+
+    /// Install a new route, failing if an identical one already exists (`RTM_NEWROUTE`)
+    pub fn add(&mut self, entry: &impl NetlinkRoute) -> IoResult<()> {
+        let seq = self.next_seq();
+        self.request(Self::add_request(entry, seq)?)
+    }
+
+    /// Remove a matching route (`RTM_DELROUTE`)
+    pub fn delete(&mut self, entry: &impl NetlinkRoute) -> IoResult<()> {
+        let seq = self.next_seq();
+        self.request(Self::delete_request(entry, seq)?)
+    }
+
+    /// Install a route, replacing the existing one for the same destination (`RTM_NEWROUTE`)
+    pub fn replace(&mut self, entry: &impl NetlinkRoute) -> IoResult<()> {
+        let seq = self.next_seq();
+        self.request(Self::replace_request(entry, seq)?)
+    }
+
+    /// The message [`Self::add`] sends for `entry`, for a socket of one's own. Fails when the
+    /// interface of the entry does not exist.
+    pub fn add_request(entry: &impl NetlinkRoute, seq: u32) -> IoResult<Vec<u8>> {
+        let flags = libc::NLM_F_CREATE | libc::NLM_F_EXCL;
+        change_request(libc::RTM_NEWROUTE, flags, seq, entry)
+    }
+
+    /// The message [`Self::delete`] sends for `entry`, see [`Self::add_request`]
+    pub fn delete_request(entry: &impl NetlinkRoute, seq: u32) -> IoResult<Vec<u8>> {
+        change_request(libc::RTM_DELROUTE, 0, seq, entry)
+    }
+
+    /// The message [`Self::replace`] sends for `entry`, see [`Self::add_request`]
+    pub fn replace_request(entry: &impl NetlinkRoute, seq: u32) -> IoResult<Vec<u8>> {
+        let flags = libc::NLM_F_CREATE | libc::NLM_F_REPLACE;
+        change_request(libc::RTM_NEWROUTE, flags, seq, entry)
+    }
+
+    /// Read the routes of both families in every table (`RTM_GETROUTE` dump)
+    pub fn routes(&mut self) -> IoResult<Vec<KernelRoute>> {
+        let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+        let mut msg = RouteMessage::new(libc::RTM_GETROUTE, flags, self.next_seq());
+        msg.buf
+            .extend_from_slice(&[libc::AF_UNSPEC as u8; RTMSG_LEN]);
+        self.send(msg.finish())?;
+        let mut routes = Vec::new();
+        self.receive(|msg_type, payload| match msg_type {
+            libc::RTM_NEWROUTE => {
+                routes.extend(parse_route(payload));
+                None
+            }
+            _ if msg_type == libc::NLMSG_DONE as u16 => Some(Ok(())),
+            _ if msg_type == libc::NLMSG_ERROR as u16 => Some(ack_result(payload)),
+            _ => None,
+        })?;
+        Ok(routes)
+    }
+
+    /// Ask the kernel which source address it would use for packets to `dest` (`RTM_GETROUTE`).
+    ///
+    /// Unlike [`ProcRoot::preferred_source`](crate::ProcRoot::preferred_source) this honours
+    /// policy rules and `src` hints on routes. Fails with the kernel's error, such as
+    /// `ENETUNREACH`, when there is no route to `dest`.
+    pub fn preferred_source(&mut self, dest: IpAddr) -> IoResult<Option<IpAddr>> {
+        self.get_route(dest, 0, |payload| {
+            attrs(payload.get(RTMSG_LEN..).unwrap_or_default())
+                .find(|(attr_type, _)| *attr_type == libc::RTA_PREFSRC)
+                .and_then(|(_, data)| parse_addr(data))
+        })
+    }
+
+    /// The route the kernel would use to reach `dest`, following policy rules, or `None` when
+    /// `dest` is unreachable (`RTM_GETROUTE` with `RTM_F_FIB_MATCH`)
+    pub fn lookup(&mut self, dest: IpAddr) -> IoResult<Option<KernelRoute>> {
+        match self.get_route(dest, RTM_F_LOOKUP_TABLE | RTM_F_FIB_MATCH, parse_route) {
+            Err(err)
+                if matches!(
+                    err.raw_os_error(),
+                    Some(libc::ENETUNREACH | libc::EHOSTUNREACH)
+                ) =>
+            {
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+
+    /// Ask for the route to `dest` and decode the kernel's answer with `decode`
+    fn get_route<T>(
+        &mut self,
+        dest: IpAddr,
+        rtm_flags: u32,
+        decode: impl Fn(&[u8]) -> Option<T>,
+    ) -> IoResult<Option<T>> {
+        let (family, octets, dst_len) = match dest {
+            IpAddr::V4(dest) => (libc::AF_INET, dest.octets().to_vec(), 32),
+            IpAddr::V6(dest) => (libc::AF_INET6, dest.octets().to_vec(), 128),
+        };
+        let mut msg = RouteMessage::new(
+            libc::RTM_GETROUTE,
+            libc::NLM_F_REQUEST as u16,
+            self.next_seq(),
+        );
+        msg.rtmsg(family as u8, dst_len, 0, 0, 0);
+        msg.rtm_flags(rtm_flags);
+        msg.attr(libc::RTA_DST, &octets);
+        self.send(msg.finish())?;
+        self.receive(|msg_type, payload| match msg_type {
+            libc::RTM_NEWROUTE => Some(Ok(decode(payload))),
+            _ if msg_type == libc::NLMSG_ERROR as u16 => Some(ack_result(payload).and(Ok(None))),
+            _ => None,
+        })
+    }
+
+    /// Send a change built by [`change_request`] and wait for its acknowledgement
+    fn request(&mut self, buf: Vec<u8>) -> IoResult<()> {
+        self.send(buf)?;
+        self.receive(|msg_type, payload| {
+            (msg_type == libc::NLMSG_ERROR as u16).then(|| ack_result(payload))
+        })
+    }
+
+    fn next_seq(&mut self) -> u32 {
+        self.seq = self.seq.wrapping_add(1);
+        self.seq
+    }
+
+    fn send(&self, buf: Vec<u8>) -> IoResult<()> {
+        let sent =
+            unsafe { libc::send(self.socket.as_raw_fd(), buf.as_ptr().cast(), buf.len(), 0) };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Feed the replies to the last request to `handle` until it returns a result
+    fn receive<T>(&self, mut handle: impl FnMut(u16, &[u8]) -> Option<IoResult<T>>) -> IoResult<T> {
+        // dump replies are batched into messages of up to 32 KiB
+        let mut buf = vec![0u8; 32 * 1024];
+        loop {
+            let len = unsafe {
+                libc::recv(
+                    self.socket.as_raw_fd(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len(),
+                    0,
+                )
+            };
+            if len < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut rest = &buf[..len as usize];
+            while rest.len() >= NLMSG_HDR_LEN {
+                let msg_len = u32::from_ne_bytes(rest[0..4].try_into().unwrap()) as usize;
+                let msg_type = u16::from_ne_bytes(rest[4..6].try_into().unwrap());
+                let seq = u32::from_ne_bytes(rest[8..12].try_into().unwrap());
+                if msg_len < NLMSG_HDR_LEN || msg_len > rest.len() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "truncated netlink message",
+                    ));
+                }
+                if seq == self.seq
+                    && let Some(result) = handle(msg_type, &rest[NLMSG_HDR_LEN..msg_len])
+                {
+                    return result;
+                }
+                rest = &rest[align(msg_len).min(rest.len())..];
+            }
+        }
+    }
+}
+
+/// A request to change the route of `entry` that asks for an acknowledgement
+fn change_request(
+    msg_type: u16,
+    flags: libc::c_int,
+    seq: u32,
+    entry: &impl NetlinkRoute,
+) -> IoResult<Vec<u8>> {
+    let flags = (flags | libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16;
+    let mut msg = RouteMessage::new(msg_type, flags, seq);
+    let delete = msg_type == libc::RTM_DELROUTE;
+    entry.encode(&mut msg, delete)?;
+    if delete {
+        msg.match_any_origin();
+    }
+    Ok(msg.finish())
+}
+
+/// A [`RouteSource`] reading the kernel's routes over netlink, in the shape `/proc` lists them.
+///
+/// IPv4 routes come from the main table only, as in `/proc/net/route`, IPv6 routes from every
+/// table. Each path of a multipath route becomes an entry of its own, and routes without a path,
+/// such as unreachable ones, are flagged `REJECT`. The entries do not say their table, so delete
+/// routes of other tables through the [`KernelRoute`]s of [`RouteManager::routes`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetlinkRouteSource;
+
+impl RouteSource for NetlinkRouteSource {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>> {
+        let routes = RouteManager::new()?.routes()?;
+        Ok(Box::new(
+            routes
+                .into_iter()
+                .filter(|route| route.table == libc::RT_TABLE_MAIN as u32)
+                .flat_map(ipv4_entries)
+                .map(Ok),
+        ))
+    }
+
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>> {
+        let routes = RouteManager::new()?.routes()?;
+        Ok(Box::new(routes.into_iter().flat_map(ipv6_entries).map(Ok)))
+    }
+}
+
+fn ipv4_entries(route: KernelRoute) -> Vec<Ipv4RouteEntry> {
+    let IpAddr::V4(dest) = route.destination.addr() else {
+        return Vec::new();
+    };
+    let prefix_len = route.destination.prefix_len();
+    let mask = Ipv4Addr::from(
+        u32::MAX
+            .checked_shl(32 - u32::from(prefix_len))
+            .unwrap_or(0),
+    );
+    let entry = |name, gateway: Option<Ipv4Addr>, mut flags: Ipv4RouteFlags| {
+        flags.set(Ipv4RouteFlags::GATEWAY, gateway.is_some());
+        flags.set(Ipv4RouteFlags::HOST, prefix_len == 32);
+        Ipv4RouteEntry {
+            name,
+            dest,
+            gateway: gateway.unwrap_or(Ipv4Addr::UNSPECIFIED),
+            flags,
+            ref_count: 0,
+            use_count: 0,
+            metric: route.metric,
+            mask,
+            mtu: None,
+            window: None,
+            irtt: None,
+        }
+    };
+    if route.next_hops.is_empty() {
+        // the kernel prints `*` for routes without a device
+        let name = IfName::new("*").expect("valid interface name");
+        return vec![entry(
+            name,
+            None,
+            Ipv4RouteFlags::UP | Ipv4RouteFlags::REJECT,
+        )];
+    }
+    route
+        .next_hops
+        .iter()
+        .filter_map(|hop| {
+            let gateway = match hop.gateway {
+                Some(IpAddr::V4(gateway)) => Some(gateway),
+                _ => None,
+            };
+            let name = IfName::new(hop.iface.as_str()).ok()?;
+            Some(entry(name, gateway, Ipv4RouteFlags::UP))
+        })
+        .collect()
+}
+
+fn ipv6_entries(route: KernelRoute) -> Vec<Ipv6RouteEntry> {
+    let IpAddr::V6(dest) = route.destination.addr() else {
+        return Vec::new();
+    };
+    let entry = |name, next_hop: Option<Ipv6Addr>, mut flags: Ipv6RouteFlags| {
+        flags.set(Ipv6RouteFlags::GATEWAY, next_hop.is_some());
+        Ipv6RouteEntry {
+            dest,
+            dest_prefix: route.destination.prefix_len(),
+            src: Ipv6Addr::UNSPECIFIED,
+            src_prefix: 0,
+            next_hop: next_hop.unwrap_or(Ipv6Addr::UNSPECIFIED),
+            metric: route.metric,
+            ref_count: 0,
+            use_count: 0,
+            flags,
+            name,
+        }
+    };
+    if route.next_hops.is_empty() {
+        return vec![entry(
+            None,
+            None,
+            Ipv6RouteFlags::UP | Ipv6RouteFlags::REJECT,
+        )];
+    }
+    route
+        .next_hops
+        .iter()
+        .map(|hop| {
+            let next_hop = match hop.gateway {
+                Some(IpAddr::V6(next_hop)) => Some(next_hop),
+                _ => None,
+            };
+            let name = IfName::new(hop.iface.as_str()).ok();
+            entry(name, next_hop, Ipv6RouteFlags::UP)
+        })
+        .collect()
+}
+
+/// Route entry types that can be sent to the kernel by [`RouteManager`]
+pub trait NetlinkRoute: sealed::Sealed {}
+
+impl NetlinkRoute for Ipv4RouteEntry {}
+
+impl NetlinkRoute for Ipv6RouteEntry {}
+
+impl NetlinkRoute for KernelRoute {}
+
+mod sealed {
+    use super::RouteMessage;
+    use std::io::Result as IoResult;
+
+    pub trait Sealed {
+        fn encode(&self, msg: &mut RouteMessage, delete: bool) -> IoResult<()>;
+    }
+}
+
+impl sealed::Sealed for Ipv4RouteEntry {
+    fn encode(&self, msg: &mut RouteMessage, delete: bool) -> IoResult<()> {
+        let reject = self.is_reject();
+        let gateway = self.uses_gateway();
+        let scope = match (delete, gateway || reject) {
+            (true, _) => libc::RT_SCOPE_NOWHERE,
+            (false, true) => libc::RT_SCOPE_UNIVERSE,
+            (false, false) => libc::RT_SCOPE_LINK,
+        };
+        let dst_len = self.prefix_len();
+        msg.rtmsg(libc::AF_INET as u8, dst_len, 0, scope, route_type(reject));
+        if dst_len > 0 {
+            msg.attr(libc::RTA_DST, &self.dest.octets());
+        }
+        if gateway {
+            msg.attr(libc::RTA_GATEWAY, &self.gateway.octets());
+        }
+        if !reject {
+            msg.attr(libc::RTA_OIF, &if_index(&self.name)?.to_ne_bytes());
+        }
+        msg.attr(libc::RTA_PRIORITY, &self.metric.to_ne_bytes());
+        Ok(())
+    }
+}
+
+impl sealed::Sealed for Ipv6RouteEntry {
+    fn encode(&self, msg: &mut RouteMessage, delete: bool) -> IoResult<()> {
+        let reject = self.flags.contains(Ipv6RouteFlags::REJECT);
+        let scope = if delete {
+            libc::RT_SCOPE_NOWHERE
+        } else {
+            libc::RT_SCOPE_UNIVERSE
+        };
+        msg.rtmsg(
+            libc::AF_INET6 as u8,
+            self.dest_prefix,
+            self.src_prefix,
+            scope,
+            route_type(reject),
+        );
+        if self.dest_prefix > 0 {
+            msg.attr(libc::RTA_DST, &self.dest.octets());
+        }
+        if self.src_prefix > 0 {
+            msg.attr(libc::RTA_SRC, &self.src.octets());
+        }
+        if self.flags.contains(Ipv6RouteFlags::GATEWAY) {
+            msg.attr(libc::RTA_GATEWAY, &self.next_hop.octets());
+        }
+        if !reject && let Some(name) = &self.name {
+            msg.attr(libc::RTA_OIF, &if_index(name)?.to_ne_bytes());
+        }
+        msg.attr(libc::RTA_PRIORITY, &self.metric.to_ne_bytes());
+        Ok(())
+    }
+}
+
+/// The route in its own table, with every next hop. A delete matches any next hop.
+impl sealed::Sealed for KernelRoute {
+    fn encode(&self, msg: &mut RouteMessage, delete: bool) -> IoResult<()> {
+        let (family, dest) = match self.destination.addr() {
+            IpAddr::V4(dest) => (libc::AF_INET, dest.octets().to_vec()),
+            IpAddr::V6(dest) => (libc::AF_INET6, dest.octets().to_vec()),
+        };
+        let on_link = self.next_hops.iter().all(|hop| hop.gateway.is_none());
+        let scope = match self.route_type {
+            _ if delete => libc::RT_SCOPE_NOWHERE,
+            libc::RTN_LOCAL => libc::RT_SCOPE_HOST,
+            libc::RTN_UNICAST if on_link && family == libc::AF_INET => libc::RT_SCOPE_LINK,
+            _ => libc::RT_SCOPE_UNIVERSE,
+        };
+        let dst_len = self.destination.prefix_len();
+        msg.rtmsg(family as u8, dst_len, 0, scope, self.route_type);
+        if self.table != libc::RT_TABLE_MAIN as u32 {
+            msg.table(self.table);
+        }
+        if dst_len > 0 {
+            msg.attr(libc::RTA_DST, &dest);
+        }
+        if let Some(source) = self.preferred_source {
+            msg.attr(libc::RTA_PREFSRC, &addr_octets(source));
+        }
+        match self.next_hops.as_slice() {
+            [] => {}
+            [hop] => {
+                if let Some(gateway) = hop.gateway {
+                    msg.attr(libc::RTA_GATEWAY, &addr_octets(gateway));
+                }
+                msg.attr(libc::RTA_OIF, &hop_index(&hop.iface)?.to_ne_bytes());
+            }
+            hops => {
+                let mut paths = Vec::new();
+                for hop in hops {
+                    let gateway = hop.gateway.map(addr_octets).unwrap_or_default();
+                    let attr_len = if gateway.is_empty() {
+                        0
+                    } else {
+                        4 + gateway.len()
+                    };
+                    paths.extend_from_slice(&((RTNEXTHOP_LEN + attr_len) as u16).to_ne_bytes());
+                    paths.push(0); // rtnh_flags
+                    paths.push(hop.weight.saturating_sub(1).min(255) as u8);
+                    paths.extend_from_slice(&hop_index(&hop.iface)?.to_ne_bytes());
+                    if !gateway.is_empty() {
+                        paths.extend_from_slice(&(attr_len as u16).to_ne_bytes());
+                        paths.extend_from_slice(&libc::RTA_GATEWAY.to_ne_bytes());
+                        paths.extend_from_slice(&gateway);
+                    }
+                }
+                msg.attr(libc::RTA_MULTIPATH, &paths);
+            }
+        }
+        msg.attr(libc::RTA_PRIORITY, &self.metric.to_ne_bytes());
+        Ok(())
+    }
+}
+
+/// A `nlmsghdr` + `rtmsg` request followed by its route attributes
+pub struct RouteMessage {
+    buf: Vec<u8>,
+}
+
+impl RouteMessage {
+    fn new(msg_type: u16, flags: u16, seq: u32) -> Self {
+        let mut buf = Vec::with_capacity(128);
+        // the length is patched in `finish`
+        buf.extend_from_slice(&0u32.to_ne_bytes());
+        buf.extend_from_slice(&msg_type.to_ne_bytes());
+        buf.extend_from_slice(&flags.to_ne_bytes());
+        buf.extend_from_slice(&seq.to_ne_bytes());
+        buf.extend_from_slice(&0u32.to_ne_bytes());
+        Self { buf }
+    }
+
+    fn rtmsg(&mut self, family: u8, dst_len: u8, src_len: u8, scope: u8, route_type: u8) {
+        self.buf.extend_from_slice(&[
+            family,
+            dst_len,
+            src_len,
+            0, // tos
+            libc::RT_TABLE_MAIN,
+            libc::RTPROT_BOOT,
+            scope,
+            route_type,
+        ]);
+        self.buf.extend_from_slice(&0u32.to_ne_bytes());
+    }
+
+    /// Set the `rtm_flags` of the `rtmsg` written by [`Self::rtmsg`]
+    fn rtm_flags(&mut self, flags: u32) {
+        let offset = NLMSG_HDR_LEN + RTMSG_LEN - 4;
+        self.buf[offset..offset + 4].copy_from_slice(&flags.to_ne_bytes());
+    }
+
+    /// Put the route in `table` rather than the main one. Ids above 255 do not fit `rtm_table`
+    /// and go into `RTA_TABLE`, as `ip route` sends them.
+    fn table(&mut self, table: u32) {
+        match u8::try_from(table) {
+            Ok(table) => self.buf[NLMSG_HDR_LEN + 4] = table,
+            Err(_) => {
+                self.buf[NLMSG_HDR_LEN + 4] = libc::RT_TABLE_UNSPEC;
+                self.attr(libc::RTA_TABLE, &table.to_ne_bytes());
+            }
+        }
+    }
+
+    /// Clear the `rtm_protocol` and `rtm_type` of the `rtmsg`. The kernel only deletes a route
+    /// whose protocol and type match the nonzero ones of the request, so a delete leaves both
+    /// unspecified, as `ip route del` does, to match routes installed by DHCP, router
+    /// advertisements or a routing daemon too.
+    fn match_any_origin(&mut self) {
+        self.buf[NLMSG_HDR_LEN + 5] = libc::RTPROT_UNSPEC;
+        self.buf[NLMSG_HDR_LEN + 7] = libc::RTN_UNSPEC;
+    }
+
+    fn attr(&mut self, attr_type: u16, data: &[u8]) {
+        let len = 4 + data.len();
+        self.buf.extend_from_slice(&(len as u16).to_ne_bytes());
+        self.buf.extend_from_slice(&attr_type.to_ne_bytes());
+        self.buf.extend_from_slice(data);
+        self.buf.resize(align(self.buf.len()), 0);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let len = self.buf.len() as u32;
+        self.buf[0..4].copy_from_slice(&len.to_ne_bytes());
+        self.buf
+    }
+}
+
+#[inline(always)]
+fn align(len: usize) -> usize {
+    (len + NLMSG_ALIGN - 1) & !(NLMSG_ALIGN - 1)
+}
+
+#[inline(always)]
+fn route_type(reject: bool) -> u8 {
+    if reject {
+        libc::RTN_UNREACHABLE
+    } else {
+        libc::RTN_UNICAST
+    }
+}
+
+fn if_index(name: &str) -> IoResult<u32> {
+    let c_name = CString::new(name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "interface name contains NUL"))?;
+    match unsafe { libc::if_nametoindex(c_name.as_ptr()) } {
+        0 => Err(io::Error::last_os_error()),
+        index => Ok(index),
+    }
+}
+
+/// The index of the interface of a [`NextHop`], which is the index itself for an interface
+/// that disappeared while reading
+fn hop_index(iface: &str) -> IoResult<u32> {
+    if_index(iface).or_else(|err| iface.parse().map_err(|_| err))
+}
+
+fn addr_octets(addr: IpAddr) -> Vec<u8> {
+    match addr {
+        IpAddr::V4(addr) => addr.octets().to_vec(),
+        IpAddr::V6(addr) => addr.octets().to_vec(),
+    }
+}
+
+/// The outcome carried by an `NLMSG_ERROR` message, which is an acknowledgement for errno 0
+fn ack_result(payload: &[u8]) -> IoResult<()> {
+    let code = payload
+        .get(..4)
+        .map_or(0, |code| i32::from_ne_bytes(code.try_into().unwrap()));
+    match code {
+        0 => Ok(()),
+        errno => Err(io::Error::from_raw_os_error(-errno)),
+    }
+}
+
+/// Decode an `RTM_NEWROUTE` payload, skipping families other than IPv4 and IPv6
+fn parse_route(payload: &[u8]) -> Option<KernelRoute> {
+    let header = payload.get(..RTMSG_LEN)?;
+    let (family, dst_len, rtm_table, route_type) = (header[0], header[1], header[4], header[7]);
+    let unspecified = match i32::from(family) {
+        libc::AF_INET => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        libc::AF_INET6 => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        _ => return None,
+    };
+    let mut destination = unspecified;
+    let mut table = u32::from(rtm_table);
+    let mut metric = 0;
+    let mut gateway = None;
+    let mut preferred_source = None;
+    let mut oif = None;
+    let mut multipath = None;
+    for (attr_type, data) in attrs(&payload[RTMSG_LEN..]) {
+        match attr_type {
+            libc::RTA_DST => destination = parse_addr(data)?,
+            libc::RTA_GATEWAY => gateway = Some(parse_addr(data)?),
+            libc::RTA_PREFSRC => preferred_source = Some(parse_addr(data)?),
+            libc::RTA_OIF => oif = Some(parse_u32(data)?),
+            libc::RTA_PRIORITY => metric = parse_u32(data)?,
+            libc::RTA_TABLE => table = parse_u32(data)?,
+            libc::RTA_MULTIPATH => multipath = Some(data),
+            _ => {}
+        }
+    }
+
+    let mut next_hops = Vec::new();
+    if let Some(mut data) = multipath {
+        // a list of `rtnexthop` headers, each followed by the attributes of that path
+        while data.len() >= RTNEXTHOP_LEN {
+            let len = usize::from(u16::from_ne_bytes(data[0..2].try_into().unwrap()));
+            if len < RTNEXTHOP_LEN || len > data.len() {
+                break;
+            }
+            let hops = data[3];
+            let index = u32::from_ne_bytes(data[4..8].try_into().unwrap());
+            let gateway = attrs(&data[RTNEXTHOP_LEN..len])
+                .find(|(attr_type, _)| *attr_type == libc::RTA_GATEWAY)
+                .and_then(|(_, data)| parse_addr(data));
+            next_hops.push(NextHop {
+                gateway,
+                iface: if_name(index),
+                weight: u16::from(hops) + 1,
+            });
+            data = &data[align(len).min(data.len())..];
+        }
+    } else if let Some(index) = oif {
+        next_hops.push(NextHop {
+            gateway,
+            iface: if_name(index),
+            weight: 1,
+        });
+    }
+    Some(KernelRoute {
+        destination: IpNet::new(destination, dst_len).ok()?,
+        table,
+        route_type,
+        metric,
+        preferred_source,
+        next_hops,
+    })
+}
+
+/// Iterate over the `rtattr`s of a buffer, stopping at the first malformed one
+fn attrs(mut data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        let len = usize::from(u16::from_ne_bytes(data.get(0..2)?.try_into().unwrap()));
+        let attr_type = u16::from_ne_bytes(data.get(2..4)?.try_into().unwrap());
+        let value = data.get(4..len)?;
+        data = &data[align(len).min(data.len())..];
+        Some((attr_type, value))
+    })
+}
+
+fn parse_addr(data: &[u8]) -> Option<IpAddr> {
+    match data.len() {
+        4 => Some(IpAddr::from(<[u8; 4]>::try_from(data).unwrap())),
+        16 => Some(IpAddr::from(<[u8; 16]>::try_from(data).unwrap())),
+        _ => None,
+    }
+}
+
+fn parse_u32(data: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(data.try_into().ok()?))
+}
+
+fn if_name(index: u32) -> String {
+    let mut buf = [0 as libc::c_char; libc::IF_NAMESIZE];
+    // SAFETY: the buffer is IF_NAMESIZE long, as if_indextoname requires
+    let name = unsafe { libc::if_indextoname(index, buf.as_mut_ptr()) };
+    if name.is_null() {
+        return index.to_string();
+    }
+    // SAFETY: on success the buffer holds a NUL-terminated name
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    name.to_string_lossy().into_owned()
+}
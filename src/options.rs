@@ -0,0 +1,132 @@
+use crate::{FormatProfile, Ipv6RouteLayout};
+
+/// What to do with flag bits that have no named flag in [`Ipv4RouteFlags`](crate::Ipv4RouteFlags)
+/// or [`Ipv6RouteFlags`](crate::Ipv6RouteFlags)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UnknownFlagBits {
+    /// Keep them in the flags, where `unknown_bits()` reports them
+    #[default]
+    Retain,
+    /// Clear them
+    Drop,
+    /// Fail the line with [`RouteParseError::UnknownFlagBits`](crate::RouteParseError::UnknownFlagBits)
+    Reject,
+}
+
+/// Bounds on the input a table reads before failing with
+/// [`RouteParseError::LimitExceeded`](crate::RouteParseError::LimitExceeded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Limits {
+    pub(crate) max_line_len: usize,
+    pub(crate) max_fields: usize,
+    pub(crate) max_entries: usize,
+}
+
+/// Far above any line the kernel prints, and below what a crafted file could make us buffer
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_line_len: 4096,
+            max_fields: 64,
+            max_entries: usize::MAX,
+        }
+    }
+}
+
+/// Settings applied by the `open_with` route table constructors
+#[derive(Debug, Clone, Default)]
+pub struct RouteTableOptions {
+    pub(crate) intern_names: bool,
+    pub(crate) tolerant: bool,
+    pub(crate) skip_validation: bool,
+    pub(crate) ipv6_layout: Option<Ipv6RouteLayout>,
+    pub(crate) unknown_flag_bits: UnknownFlagBits,
+    pub(crate) limits: Limits,
+    pub(crate) long_ifnames: bool,
+    pub(crate) skip_ipv6_cache: bool,
+}
+
+impl RouteTableOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share a single `Arc<str>` among all entries on the same interface instead of allocating one per entry
+    pub fn intern_names(mut self, intern_names: bool) -> Self {
+        self.intern_names = intern_names;
+        self
+    }
+
+    /// Accept `/proc/net/ipv6_route` lines with a missing device name or extra trailing columns,
+    /// as rendered by some kernels and container runtimes
+    pub fn tolerant(mut self, tolerant: bool) -> Self {
+        self.tolerant = tolerant;
+        self
+    }
+
+    /// Read the files the way the kernel of `profile` renders them, see [`FormatProfile`]. The
+    /// IPv6 tables of kernels that list cloned `CACHE` routes leave them out, as those of current
+    /// kernels do.
+    pub fn format_profile(mut self, profile: &FormatProfile) -> Self {
+        self.tolerant |= profile.tolerant;
+        self.long_ifnames |= profile.long_ifnames;
+        self.skip_ipv6_cache |= profile.ipv6_cached_routes;
+        self
+    }
+
+    /// Accept interface names longer than [`IFNAME_MAX_LEN`](crate::IFNAME_MAX_LEN), as found in captures from vendor
+    /// firmware and tools that do not hold to the kernel's limit. The entries then carry names
+    /// that [`IfName::new`](crate::IfName::new) would refuse.
+    pub fn long_ifnames(mut self, long_ifnames: bool) -> Self {
+        self.long_ifnames = long_ifnames;
+        self
+    }
+
+    /// Reject entries with impossible values as [`RouteParseError::InvalidEntry`](crate::RouteParseError::InvalidEntry),
+    /// on by default. Turn it off to read captures as they are.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.skip_validation = !validate;
+        self
+    }
+
+    /// Parse `/proc/net/ipv6_route` lines with this layout instead of detecting it, for captures
+    /// from a kernel that prints its columns in another order or adds some
+    pub fn ipv6_layout(mut self, layout: Ipv6RouteLayout) -> Self {
+        self.ipv6_layout = Some(layout);
+        self
+    }
+
+    /// How to decode flag bits the kernel sets but this crate does not model, kept by default
+    pub fn unknown_flag_bits(mut self, handling: UnknownFlagBits) -> Self {
+        self.unknown_flag_bits = handling;
+        self
+    }
+
+    /// Fail lines longer than `max` bytes without buffering the rest of them, 4096 by default
+    pub fn max_line_len(mut self, max: usize) -> Self {
+        self.limits.max_line_len = max;
+        self
+    }
+
+    /// Fail lines with more than `max` fields, 64 by default
+    pub fn max_fields(mut self, max: usize) -> Self {
+        self.limits.max_fields = max;
+        self
+    }
+
+    /// Stop with an error after `max` entries, unbounded by default since a full routing table
+    /// holds about a million routes
+    pub fn max_entries(mut self, max: usize) -> Self {
+        self.limits.max_entries = max;
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn max_ifname_len(&self) -> usize {
+        if self.long_ifnames {
+            usize::MAX
+        } else {
+            crate::IFNAME_MAX_LEN
+        }
+    }
+}
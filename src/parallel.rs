@@ -0,0 +1,55 @@
+use crate::{
+    AddressFamily, Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteTable, LineError,
+    RouteParseError, SnapshotError,
+};
+use rayon::prelude::*;
+use std::{fs, path::Path, str::FromStr, sync::Arc};
+
+/// Lines handed to each rayon task, small enough to balance and large enough to amortize the split
+const MIN_CHUNK_LINES: usize = 4096;
+
+impl Ipv4RouteTable {
+    /// Parse a captured `/proc/net/route` file on the rayon thread pool, keeping the file order.
+    ///
+    /// Fails with the first malformed line, like collecting the table into a `Result`.
+    pub fn parse_file_parallel(
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<Ipv4RouteEntry>, SnapshotError> {
+        parse_file_parallel(path.as_ref(), 1, AddressFamily::Ipv4)
+    }
+}
+
+impl Ipv6RouteTable {
+    /// Parse a captured `/proc/net/ipv6_route` file on the rayon thread pool, see
+    /// [`Ipv4RouteTable::parse_file_parallel`]
+    pub fn parse_file_parallel(
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<Ipv6RouteEntry>, SnapshotError> {
+        parse_file_parallel(path.as_ref(), 0, AddressFamily::Ipv6)
+    }
+}
+
+fn parse_file_parallel<E>(
+    path: &Path,
+    header_lines: usize,
+    family: AddressFamily,
+) -> Result<Vec<E>, SnapshotError>
+where
+    E: FromStr<Err = RouteParseError> + Send,
+{
+    let text = fs::read_to_string(path)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let path = Arc::from(path);
+    let parsed: Vec<Result<E, LineError>> = lines
+        .par_iter()
+        .enumerate()
+        .skip(header_lines)
+        .with_min_len(MIN_CHUNK_LINES)
+        .map(|(index, line)| {
+            line.parse().map_err(|err| {
+                LineError::new(index + 1, line.to_string(), err).in_table(Some(family), Some(&path))
+            })
+        })
+        .collect();
+    Ok(parsed.into_iter().collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,374 @@
+use crate::{MacAddr, Route};
+use std::{
+    ffi::CString,
+    io::{self, Result as IoResult},
+    net::IpAddr,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+#[cfg(feature = "icmp-probe")]
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    time::{Duration, Instant},
+};
+
+const NLMSG_HDR_LEN: usize = 16;
+const NDMSG_LEN: usize = 12;
+const NDA_DST: u16 = 1;
+const NDA_LLADDR: u16 = 2;
+
+/// Neighbor cache state of a gateway (the kernel's `NUD_*` states)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NeighborState {
+    Incomplete,
+    Reachable,
+    Stale,
+    Delay,
+    Probe,
+    Failed,
+    NoArp,
+    Permanent,
+    /// The gateway has no entry in the neighbor cache
+    Missing,
+}
+
+impl NeighborState {
+    fn from_nud(state: u16) -> Self {
+        match state {
+            libc::NUD_REACHABLE => Self::Reachable,
+            libc::NUD_STALE => Self::Stale,
+            libc::NUD_DELAY => Self::Delay,
+            libc::NUD_PROBE => Self::Probe,
+            libc::NUD_FAILED => Self::Failed,
+            libc::NUD_NOARP => Self::NoArp,
+            libc::NUD_PERMANENT => Self::Permanent,
+            _ => Self::Incomplete,
+        }
+    }
+
+    /// Whether the link-layer address is known, mirroring the kernel's `NUD_VALID`
+    pub fn is_valid(&self) -> bool {
+        matches!(
+            self,
+            Self::Reachable
+                | Self::Stale
+                | Self::Delay
+                | Self::Probe
+                | Self::NoArp
+                | Self::Permanent
+        )
+    }
+}
+
+/// Result of probing the gateway of a route
+#[derive(Debug)]
+pub struct GatewayProbe {
+    pub gateway: IpAddr,
+    pub neighbor: NeighborState,
+    /// Link-layer address from the neighbor cache
+    pub mac: Option<MacAddr>,
+    /// Round trip of an ICMP echo, if one was requested
+    #[cfg(feature = "icmp-probe")]
+    pub echo: Option<IoResult<Duration>>,
+}
+
+impl GatewayProbe {
+    /// Whether the gateway looks alive: a successful echo if one was sent, a valid neighbor entry otherwise
+    pub fn is_alive(&self) -> bool {
+        #[cfg(feature = "icmp-probe")]
+        if let Some(echo) = &self.echo {
+            return echo.is_ok();
+        }
+        self.neighbor.is_valid()
+    }
+}
+
+/// Look up the gateway of `entry` in the ARP (IPv4) or NDP (IPv6) neighbor cache.
+///
+/// Returns `None` for routes without a gateway.
+pub fn probe_gateway(entry: &impl Route) -> IoResult<Option<GatewayProbe>> {
+    let Some(gateway) = entry.gateway() else {
+        return Ok(None);
+    };
+    let index = if_index(entry.interface())?;
+    let (neighbor, mac) = neighbor_lookup(gateway, index)?
+        .map_or((NeighborState::Missing, None), |(state, mac)| (state, mac));
+    Ok(Some(GatewayProbe {
+        gateway,
+        neighbor,
+        mac,
+        #[cfg(feature = "icmp-probe")]
+        echo: None,
+    }))
+}
+
+/// Like [`probe_gateway`], additionally sending an ICMP echo to the gateway.
+///
+/// Uses an unprivileged ICMP socket when `net.ipv4.ping_group_range` allows it and falls back to a
+/// raw socket, which requires `CAP_NET_RAW`.
+#[cfg(feature = "icmp-probe")]
+pub fn probe_gateway_with_echo(
+    entry: &impl Route,
+    timeout: Duration,
+) -> IoResult<Option<GatewayProbe>> {
+    let Some(mut probe) = probe_gateway(entry)? else {
+        return Ok(None);
+    };
+    probe.echo = Some(echo(probe.gateway, if_index(entry.interface())?, timeout));
+    Ok(Some(probe))
+}
+
+fn neighbor_lookup(addr: IpAddr, index: u32) -> IoResult<Option<(NeighborState, Option<MacAddr>)>> {
+    let family = match addr {
+        IpAddr::V4(_) => libc::AF_INET,
+        IpAddr::V6(_) => libc::AF_INET6,
+    };
+    let socket = socket(libc::AF_NETLINK, libc::SOCK_RAW, libc::NETLINK_ROUTE)?;
+    let mut request = Vec::with_capacity(NLMSG_HDR_LEN + NDMSG_LEN);
+    request.extend_from_slice(&((NLMSG_HDR_LEN + NDMSG_LEN) as u32).to_ne_bytes());
+    request.extend_from_slice(&libc::RTM_GETNEIGH.to_ne_bytes());
+    request.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+    request.extend_from_slice(&1u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    // ndmsg: family, padding, ifindex, state, flags, type
+    request.extend_from_slice(&[family as u8, 0, 0, 0]);
+    request.extend_from_slice(&0i32.to_ne_bytes());
+    request.extend_from_slice(&[0; 4]);
+    send(&socket, &request)?;
+
+    let target = match addr {
+        IpAddr::V4(addr) => addr.octets().to_vec(),
+        IpAddr::V6(addr) => addr.octets().to_vec(),
+    };
+    let mut found = None;
+    let mut buf = vec![0u8; 32 * 1024];
+    loop {
+        let len = recv(&socket, &mut buf)?;
+        let mut rest = &buf[..len];
+        while rest.len() >= NLMSG_HDR_LEN {
+            let msg_len = u32::from_ne_bytes(rest[0..4].try_into().unwrap()) as usize;
+            let msg_type = u16::from_ne_bytes(rest[4..6].try_into().unwrap());
+            if msg_len < NLMSG_HDR_LEN || msg_len > rest.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "truncated netlink message",
+                ));
+            }
+            match msg_type as i32 {
+                libc::NLMSG_DONE => return Ok(found),
+                libc::NLMSG_ERROR => {
+                    let code = i32::from_ne_bytes(
+                        rest[NLMSG_HDR_LEN..NLMSG_HDR_LEN + 4].try_into().unwrap(),
+                    );
+                    return Err(io::Error::from_raw_os_error(-code));
+                }
+                _ if msg_type == libc::RTM_NEWNEIGH && msg_len >= NLMSG_HDR_LEN + NDMSG_LEN => {
+                    let ndmsg = &rest[NLMSG_HDR_LEN..NLMSG_HDR_LEN + NDMSG_LEN];
+                    let ifindex = i32::from_ne_bytes(ndmsg[4..8].try_into().unwrap());
+                    let state = u16::from_ne_bytes(ndmsg[8..10].try_into().unwrap());
+                    let attrs = &rest[NLMSG_HDR_LEN + NDMSG_LEN..msg_len];
+                    if ifindex as u32 == index
+                        && attr(attrs, NDA_DST) == Some(target.as_slice())
+                        && found.is_none()
+                    {
+                        let mac = attr(attrs, NDA_LLADDR)
+                            .and_then(|mac| <[u8; 6]>::try_from(mac).ok())
+                            .map(MacAddr);
+                        found = Some((NeighborState::from_nud(state), mac));
+                    }
+                }
+                _ => {}
+            }
+            rest = &rest[align(msg_len).min(rest.len())..];
+        }
+    }
+}
+
+/// Payload of the first route attribute of type `attr_type`
+fn attr(mut attrs: &[u8], attr_type: u16) -> Option<&[u8]> {
+    while attrs.len() >= 4 {
+        let len = u16::from_ne_bytes([attrs[0], attrs[1]]) as usize;
+        let kind = u16::from_ne_bytes([attrs[2], attrs[3]]);
+        if len < 4 || len > attrs.len() {
+            return None;
+        }
+        if kind == attr_type {
+            return Some(&attrs[4..len]);
+        }
+        attrs = &attrs[align(len).min(attrs.len())..];
+    }
+    None
+}
+
+#[cfg(feature = "icmp-probe")]
+fn echo(addr: IpAddr, index: u32, timeout: Duration) -> IoResult<Duration> {
+    const ECHO_ID: u16 = 0x7072;
+    const ECHO_SEQ: u16 = 1;
+    let (domain, protocol, request_type, reply_type) = match addr {
+        IpAddr::V4(_) => (libc::AF_INET, libc::IPPROTO_ICMP, 8, 0),
+        IpAddr::V6(_) => (libc::AF_INET6, libc::IPPROTO_ICMPV6, 128, 129),
+    };
+    let (socket, raw) = match socket(domain, libc::SOCK_DGRAM, protocol) {
+        Ok(socket) => (socket, false),
+        Err(err) if matches!(err.raw_os_error(), Some(libc::EACCES | libc::EPERM)) => {
+            (socket(domain, libc::SOCK_RAW, protocol)?, true)
+        }
+        Err(err) => return Err(err),
+    };
+    let mut packet = vec![request_type, 0, 0, 0];
+    packet.extend_from_slice(&ECHO_ID.to_be_bytes());
+    packet.extend_from_slice(&ECHO_SEQ.to_be_bytes());
+    packet.extend_from_slice(b"proc_route_parser");
+    if addr.is_ipv4() {
+        // ICMPv6 checksums are filled in by the kernel
+        let checksum = checksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    let start = Instant::now();
+    let sent = match addr {
+        IpAddr::V4(addr) => send_to_v4(&socket, &packet, addr),
+        IpAddr::V6(addr) => send_to_v6(&socket, &packet, addr, index),
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut buf = [0u8; 1024];
+    loop {
+        let remaining = timeout
+            .checked_sub(start.elapsed())
+            .filter(|remaining| !remaining.is_zero())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut))?;
+        set_recv_timeout(&socket, remaining)?;
+        let len = match recv(&socket, &mut buf) {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            len => len?,
+        };
+        let mut reply = &buf[..len];
+        if raw && addr.is_ipv4() {
+            // raw IPv4 sockets hand over the IP header as well
+            let header_len = reply.first().map_or(0, |byte| (byte & 0x0f) as usize * 4);
+            reply = reply.get(header_len..).unwrap_or_default();
+        }
+        // unprivileged sockets rewrite the identifier, so only raw replies can be matched on it
+        let matches = reply.len() >= 8
+            && reply[0] == reply_type
+            && u16::from_be_bytes([reply[6], reply[7]]) == ECHO_SEQ
+            && (!raw || u16::from_be_bytes([reply[4], reply[5]]) == ECHO_ID);
+        if matches {
+            return Ok(start.elapsed());
+        }
+    }
+}
+
+#[cfg(feature = "icmp-probe")]
+fn checksum(data: &[u8]) -> u16 {
+    let (words, tail) = data.as_chunks::<2>();
+    let mut sum = words
+        .iter()
+        .map(|word| u16::from_be_bytes(*word) as u32)
+        .sum::<u32>();
+    if let [last] = tail {
+        sum += (*last as u32) << 8;
+    }
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(feature = "icmp-probe")]
+fn send_to_v4(socket: &OwnedFd, packet: &[u8], addr: Ipv4Addr) -> isize {
+    let mut sockaddr: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    sockaddr.sin_family = libc::AF_INET as libc::sa_family_t;
+    sockaddr.sin_addr.s_addr = u32::from_ne_bytes(addr.octets());
+    unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            packet.as_ptr().cast(),
+            packet.len(),
+            0,
+            (&raw const sockaddr).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    }
+}
+
+#[cfg(feature = "icmp-probe")]
+fn send_to_v6(socket: &OwnedFd, packet: &[u8], addr: Ipv6Addr, index: u32) -> isize {
+    let mut sockaddr: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+    sockaddr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+    sockaddr.sin6_addr.s6_addr = addr.octets();
+    // link-local gateways are only meaningful together with the interface
+    sockaddr.sin6_scope_id = index;
+    unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            packet.as_ptr().cast(),
+            packet.len(),
+            0,
+            (&raw const sockaddr).cast(),
+            size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+        )
+    }
+}
+
+#[cfg(feature = "icmp-probe")]
+fn set_recv_timeout(socket: &OwnedFd, timeout: Duration) -> IoResult<()> {
+    let timeval = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros().max(1) as libc::suseconds_t,
+    };
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            (&raw const timeval).cast(),
+            size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn socket(domain: libc::c_int, kind: libc::c_int, protocol: libc::c_int) -> IoResult<OwnedFd> {
+    let fd = unsafe { libc::socket(domain, kind | libc::SOCK_CLOEXEC, protocol) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn send(socket: &OwnedFd, buf: &[u8]) -> IoResult<()> {
+    let sent = unsafe { libc::send(socket.as_raw_fd(), buf.as_ptr().cast(), buf.len(), 0) };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn recv(socket: &OwnedFd, buf: &mut [u8]) -> IoResult<usize> {
+    let len = unsafe { libc::recv(socket.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+    if len < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(len as usize)
+}
+
+#[inline(always)]
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn if_index(name: &str) -> IoResult<u32> {
+    let c_name = CString::new(name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "interface name contains NUL"))?;
+    match unsafe { libc::if_nametoindex(c_name.as_ptr()) } {
+        0 => Err(io::Error::last_os_error()),
+        index => Ok(index),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    ArpTable, Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteTable, MacAddr,
+    MulticastCacheTable, MulticastVifTable, Route, RouteParseError, Snapshot, SnapshotError,
+    SnapshotV1,
+};
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result as IoResult},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// A `/proc` file that could not be read for lack of permission, carried inside an
+/// [`ErrorKind::PermissionDenied`] I/O error.
+///
+/// The files under `/proc/net` are world-readable, so this is a security policy at work: Android
+/// 10 and later deny apps access to them through SELinux. Read the routes over rtnetlink with
+/// [`RouteManager::routes`](crate::RouteManager) (feature `netlink-write`) instead, where the
+/// platform allows it.
+#[derive(Debug, Error)]
+#[error("Access to {} denied, likely by a security policy such as Android's SELinux rules", path.display())]
+pub struct AccessDenied {
+    pub path: PathBuf,
+}
+
+impl AccessDenied {
+    /// The [`AccessDenied`] behind an I/O error returned by a [`ProcRoot`] method, if any
+    pub fn find(err: &Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+/// Where the proc filesystem is mounted, `/proc` by default.
+///
+/// Agents in a container often see the host's `/proc` bind-mounted elsewhere, e.g.
+/// `ProcRoot::new("/host/proc")`; every table is then read from below that path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProcRoot {
+    path: PathBuf,
+}
+
+impl Default for ProcRoot {
+    fn default() -> Self {
+        Self::new("/proc")
+    }
+}
+
+impl ProcRoot {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `net` directory, as taken by [`Snapshot::capture_from`] and
+    /// [`PollingRouteWatcher::proc_net`](crate::PollingRouteWatcher::proc_net)
+    pub fn net(&self) -> PathBuf {
+        self.path.join("net")
+    }
+
+    /// Open `net/route`
+    pub fn ipv4_route_table(&self) -> IoResult<Ipv4RouteTable> {
+        let path = self.net().join("route");
+        Ipv4RouteTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Whether the kernel has IPv6 enabled, which is when `sys/net/ipv6` exists
+    pub fn ipv6_enabled(&self) -> bool {
+        self.path.join("sys/net/ipv6").exists()
+    }
+
+    /// Open `net/ipv6_route`.
+    ///
+    /// Fails with [`RouteParseError::Ipv6Unavailable`] rather than a not found I/O error on a host
+    /// booted with `ipv6.disable=1`, or under WSL when the file is missing.
+    pub fn ipv6_route_table(&self) -> Result<Ipv6RouteTable, RouteParseError> {
+        self.open_ipv6_route_table()?
+            .ok_or(RouteParseError::Ipv6Unavailable)
+    }
+
+    /// Read both route tables, with an empty IPv6 table when IPv6 is disabled or, under WSL, the
+    /// table is missing
+    pub fn route_tables(
+        &self,
+    ) -> Result<(Vec<Ipv4RouteEntry>, Vec<Ipv6RouteEntry>), SnapshotError> {
+        let ipv4 = self.ipv4_route_table()?.collect::<Result<_, _>>()?;
+        let ipv6 = match self.open_ipv6_route_table()? {
+            Some(table) => table.collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        Ok((ipv4, ipv6))
+    }
+
+    /// `None` when the table is missing because IPv6 is disabled, or under WSL, where it may be
+    /// missing with IPv6 enabled
+    pub(crate) fn open_ipv6_route_table(&self) -> IoResult<Option<Ipv6RouteTable>> {
+        let path = self.net().join("ipv6_route");
+        match Ipv6RouteTable::open(&path).map_err(access_denied(path)) {
+            Err(err)
+                if err.kind() == ErrorKind::NotFound && (!self.ipv6_enabled() || self.is_wsl()) =>
+            {
+                Ok(None)
+            }
+            table => table.map(Some),
+        }
+    }
+
+    /// Open `net/arp`
+    pub fn arp_table(&self) -> IoResult<ArpTable> {
+        let path = self.net().join("arp");
+        ArpTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Open `net/ip_mr_cache`
+    pub fn ipv4_multicast_cache(&self) -> IoResult<MulticastCacheTable<Ipv4Addr>> {
+        let path = self.net().join("ip_mr_cache");
+        MulticastCacheTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Open `net/ip6_mr_cache`
+    pub fn ipv6_multicast_cache(&self) -> IoResult<MulticastCacheTable<Ipv6Addr>> {
+        let path = self.net().join("ip6_mr_cache");
+        MulticastCacheTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Open `net/ip_mr_vif`
+    pub fn ipv4_multicast_vifs(&self) -> IoResult<MulticastVifTable> {
+        let path = self.net().join("ip_mr_vif");
+        MulticastVifTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Open `net/ip6_mr_vif`
+    pub fn ipv6_multicast_vifs(&self) -> IoResult<MulticastVifTable> {
+        let path = self.net().join("ip6_mr_vif");
+        MulticastVifTable::open(&path).map_err(access_denied(path))
+    }
+
+    /// Hardware address of the gateway of `entry`, from the resolved entries of `net/arp` on the
+    /// route's interface.
+    ///
+    /// No `/proc` file lists the NDP cache, so IPv6 gateways are looked up over netlink with the
+    /// `probe` feature and resolve to `None` without it. Routes without a gateway and gateways
+    /// that are not resolved yet give `None` as well.
+    pub fn resolve_gateway_mac(&self, entry: &impl Route) -> IoResult<Option<MacAddr>> {
+        match entry.gateway() {
+            None => Ok(None),
+            Some(IpAddr::V4(gateway)) => Ok(self
+                .arp_table()?
+                .filter_map(Result::ok)
+                .find(|arp| {
+                    arp.ip == gateway && &*arp.device == entry.interface() && arp.is_complete()
+                })
+                .and_then(|arp| arp.hw_addr)),
+            #[cfg(feature = "probe")]
+            Some(IpAddr::V6(_)) => Ok(crate::probe_gateway(entry)?
+                .filter(|probe| probe.neighbor.is_valid())
+                .and_then(|probe| probe.mac)),
+            #[cfg(not(feature = "probe"))]
+            Some(IpAddr::V6(_)) => Ok(None),
+        }
+    }
+
+    /// Capture the route and ARP tables below this root, see [`Snapshot::capture_from`]
+    pub fn snapshot(&self) -> Result<Snapshot, SnapshotError> {
+        Snapshot::capture_from(self.net())
+    }
+
+    /// Capture the tables along with the host name and kernel release from `sys/kernel`, which
+    /// are left out when unreadable
+    pub fn snapshot_v1(&self) -> Result<SnapshotV1, SnapshotError> {
+        let hostname = fs::read_to_string(self.path.join("sys/kernel/hostname"));
+        Ok(SnapshotV1 {
+            hostname: hostname.ok().map(|name| name.trim_end().into()),
+            kernel_version: self.kernel_release().ok(),
+            snapshot: self.snapshot()?,
+        })
+    }
+}
+
+/// Turn a permission error on opening `path` into one carrying [`AccessDenied`]
+fn access_denied(path: PathBuf) -> impl FnOnce(Error) -> Error {
+    |err| match err.kind() {
+        ErrorKind::PermissionDenied => Error::new(err.kind(), AccessDenied { path }),
+        _ => err,
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, Route, RouteEntry,
+    Snapshot, ValidationError,
+};
+#[cfg(feature = "netlink-write")]
+use crate::{KernelRoute, RouteManager};
+use std::{fmt, net::IpAddr};
+#[cfg(feature = "netlink-write")]
+use thiserror::Error;
+
+/// A route as it should be in the main table, see [`reconcile`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RouteSpec {
+    pub destination: IpNet,
+    /// Next hop, `None` for an on-link route
+    pub gateway: Option<IpAddr>,
+    pub iface: String,
+    pub metric: u32,
+}
+
+impl RouteSpec {
+    /// An on-link route to `destination` out of `iface` with metric 0
+    pub fn new(destination: IpNet, iface: impl Into<String>) -> Self {
+        Self {
+            destination,
+            gateway: None,
+            iface: iface.into(),
+            metric: 0,
+        }
+    }
+
+    pub fn via(mut self, gateway: IpAddr) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn metric(mut self, metric: u32) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// The spec of an existing route
+    pub fn of(route: &impl Route) -> Self {
+        Self {
+            destination: route.network(),
+            gateway: route.gateway(),
+            iface: route.interface().to_string(),
+            metric: route.metric().get(),
+        }
+    }
+
+    /// The route as an entry, for [`RouteManager`](crate::RouteManager)
+    pub fn to_entry(&self) -> Result<RouteEntry, ValidationError> {
+        let mismatch = |gateway| ValidationError::GatewayFamily {
+            destination: self.destination,
+            gateway,
+        };
+        match self.destination.addr() {
+            IpAddr::V4(dest) => {
+                let mut builder = Ipv4RouteEntry::builder(self.iface.as_str())
+                    .dest(dest)
+                    .prefix_len(self.destination.prefix_len())
+                    .metric(self.metric);
+                match self.gateway {
+                    Some(IpAddr::V4(gateway)) => {
+                        builder = builder
+                            .gateway(gateway)
+                            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY);
+                    }
+                    Some(gateway @ IpAddr::V6(_)) => return Err(mismatch(gateway)),
+                    None => {}
+                }
+                builder.build().map(RouteEntry::V4)
+            }
+            IpAddr::V6(dest) => {
+                let mut builder = Ipv6RouteEntry::builder(self.iface.as_str())
+                    .dest(dest)
+                    .dest_prefix(self.destination.prefix_len())
+                    .metric(self.metric);
+                match self.gateway {
+                    Some(IpAddr::V6(gateway)) => {
+                        builder = builder
+                            .next_hop(gateway)
+                            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY);
+                    }
+                    Some(gateway @ IpAddr::V4(_)) => return Err(mismatch(gateway)),
+                    None => {}
+                }
+                builder.build().map(RouteEntry::V6)
+            }
+        }
+    }
+
+    /// Whether `route` is on the interface of this spec and in its address family
+    fn manages(&self, route: &Self) -> bool {
+        self.iface == route.iface
+            && self.destination.addr().is_ipv4() == route.destination.addr().is_ipv4()
+    }
+
+    /// The kernel tells routes apart by destination and metric, so a spec and a route with the
+    /// same of both are the same route
+    fn same_key(&self, other: &Self) -> bool {
+        self.destination == other.destination && self.metric == other.metric
+    }
+}
+
+impl fmt::Display for RouteSpec {
+    /// In the style of `ip route`, such as `10.0.0.0/8 via 192.0.2.1 dev eth0 metric 100`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.destination)?;
+        if let Some(gateway) = self.gateway {
+            write!(f, " via {gateway}")?;
+        }
+        write!(f, " dev {} metric {}", self.iface, self.metric)
+    }
+}
+
+/// The changes that turn the routes of a [`Snapshot`] into a desired set, see [`reconcile`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Desired routes the tables lack
+    pub add: Vec<RouteSpec>,
+    /// Desired routes whose destination and metric are taken by a route with another gateway or
+    /// interface
+    pub replace: Vec<RouteSpec>,
+    /// Routes via a gateway that are not desired, on an interface and in an address family with
+    /// desired routes
+    pub delete: Vec<RouteSpec>,
+}
+
+impl Plan {
+    /// Whether the tables already match the desired routes
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.replace.is_empty() && self.delete.is_empty()
+    }
+
+    /// Make the changes through a new [`RouteManager`], see [`Plan::apply_with`]
+    #[cfg(feature = "netlink-write")]
+    pub fn apply(&self) -> Result<(), ApplyError> {
+        self.apply_with(&mut RouteManager::new().map_err(ApplyError::Socket)?)
+    }
+
+    /// Make the changes: the replacements and additions first, so that traffic keeps a route
+    /// while it moves, then the deletions.
+    ///
+    /// Stops at the first change that fails, leaving the ones before it in place. Reading the
+    /// tables again and reconciling once more picks up where it stopped.
+    #[cfg(feature = "netlink-write")]
+    pub fn apply_with(&self, manager: &mut RouteManager) -> Result<(), ApplyError> {
+        let steps = (self.replace.iter().map(|spec| (PlanAction::Replace, spec)))
+            .chain(self.add.iter().map(|spec| (PlanAction::Add, spec)))
+            .chain(self.delete.iter().map(|spec| (PlanAction::Delete, spec)));
+        for (action, spec) in steps {
+            let entry = spec.to_entry().map_err(|source| ApplyError::Invalid {
+                route: spec.clone(),
+                source,
+            })?;
+            let result = match (action, &entry) {
+                (PlanAction::Add, RouteEntry::V4(entry)) => manager.add(entry),
+                (PlanAction::Add, RouteEntry::V6(entry)) => manager.add(entry),
+                (PlanAction::Replace, RouteEntry::V4(entry)) => manager.replace(entry),
+                (PlanAction::Replace, RouteEntry::V6(entry)) => manager.replace(entry),
+                (PlanAction::Delete, RouteEntry::V4(entry)) => manager.delete(entry),
+                (PlanAction::Delete, RouteEntry::V6(entry)) => manager.delete(entry),
+            };
+            result.map_err(|source| ApplyError::Change {
+                action,
+                route: spec.clone(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// The kind of a change of a [`Plan`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlanAction {
+    Add,
+    Replace,
+    Delete,
+}
+
+impl fmt::Display for PlanAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "add",
+            Self::Replace => "replace",
+            Self::Delete => "delete",
+        })
+    }
+}
+
+/// Why [`Plan::apply`] stopped
+#[cfg(feature = "netlink-write")]
+#[derive(Debug, Error)]
+pub enum ApplyError {
+    #[error("Failed to open a netlink socket: {0}")]
+    Socket(#[source] std::io::Error),
+
+    #[error("Cannot send {route} to the kernel: {source}")]
+    Invalid {
+        route: RouteSpec,
+        #[source]
+        source: ValidationError,
+    },
+
+    #[error("Failed to {action} {route}: {source}")]
+    Change {
+        action: PlanAction,
+        route: RouteSpec,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Work out the changes that make the routes of `actual` match `desired`.
+///
+/// Only routes on the interfaces `desired` names, in the address families it names for them, are
+/// looked at. Of those, routes via a gateway that are not desired get deleted, while on-link
+/// routes, which the kernel adds for the addresses of an interface, are never deleted. Reject
+/// routes are left alone.
+///
+/// `/proc/net/ipv6_route` lists the routes of every table, so IPv6 routes are never deleted, as
+/// they may belong to a policy table. [`reconcile_kernel`] knows the table of each route.
+pub fn reconcile(desired: &[RouteSpec], actual: &Snapshot) -> Plan {
+    let ipv4: Vec<RouteSpec> = actual
+        .ipv4
+        .iter()
+        .filter(|route| !route.is_reject())
+        .map(RouteSpec::of)
+        .collect();
+    let ipv6 = actual
+        .ipv6
+        .iter()
+        .filter(|route| !route.is_reject())
+        .map(RouteSpec::of);
+    let existing: Vec<RouteSpec> = ipv4.iter().cloned().chain(ipv6).collect();
+    plan(desired, &existing, ipv4)
+}
+
+/// [`reconcile`] against the routes read with [`RouteManager::routes`], looking at the unicast
+/// routes of the main table only. Unlike a [`Snapshot`], these tell the IPv6 routes of policy
+/// tables apart, so undesired IPv6 routes are deleted as well.
+#[cfg(feature = "netlink-write")]
+pub fn reconcile_kernel(desired: &[RouteSpec], actual: &[KernelRoute]) -> Plan {
+    let existing: Vec<RouteSpec> = actual
+        .iter()
+        .filter(|route| {
+            route.table == libc::RT_TABLE_MAIN as u32 && route.route_type == libc::RTN_UNICAST
+        })
+        .flat_map(|route| {
+            route.next_hops.iter().map(|hop| RouteSpec {
+                destination: route.destination,
+                gateway: hop.gateway,
+                iface: hop.iface.clone(),
+                metric: route.metric,
+            })
+        })
+        .collect();
+    plan(desired, &existing, existing.clone())
+}
+
+/// The changes that turn `existing` into `desired`, deleting only from `deletable`
+fn plan(desired: &[RouteSpec], existing: &[RouteSpec], deletable: Vec<RouteSpec>) -> Plan {
+    let mut plan = Plan::default();
+    for spec in desired {
+        if existing.contains(spec) || plan.add.contains(spec) || plan.replace.contains(spec) {
+            continue;
+        }
+        if existing.iter().any(|route| route.same_key(spec)) {
+            plan.replace.push(spec.clone());
+        } else {
+            plan.add.push(spec.clone());
+        }
+    }
+    plan.delete = deletable
+        .into_iter()
+        .filter(|route| route.gateway.is_some())
+        .filter(|route| desired.iter().any(|spec| spec.manages(route)))
+        .filter(|route| !desired.iter().any(|spec| spec.same_key(route)))
+        .collect();
+    plan
+}
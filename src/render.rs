@@ -0,0 +1,315 @@
+use crate::{Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, Route, RouteEntry};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+/// A column of a rendered route table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// Destination address, with the prefix length appended for IPv6
+    Destination,
+    Gateway,
+    /// Destination netmask, IPv4 only
+    Genmask,
+    /// Source prefix, IPv6 only
+    Source,
+    /// Flag letters as printed by `route -n`
+    Flags,
+    Metric,
+    Ref,
+    Use,
+    Iface,
+    Mtu,
+    Window,
+    Irtt,
+}
+
+impl Column {
+    /// Heading of the column, as printed by `route -n`
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Destination => "Destination",
+            Self::Gateway => "Gateway",
+            Self::Genmask => "Genmask",
+            Self::Source => "Source",
+            Self::Flags => "Flags",
+            Self::Metric => "Metric",
+            Self::Ref => "Ref",
+            Self::Use => "Use",
+            Self::Iface => "Iface",
+            Self::Mtu => "MTU",
+            Self::Window => "Window",
+            Self::Irtt => "irtt",
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Metric | Self::Ref | Self::Use | Self::Mtu | Self::Window | Self::Irtt
+        )
+    }
+}
+
+/// Entries that can be rendered as a row of a [`TableRenderer`] table
+pub trait TableRow {
+    /// Columns shown when none are configured, matching `route -n`
+    const DEFAULT_COLUMNS: &'static [Column];
+
+    /// Text of one cell, `-` for a column the address family does not have
+    fn cell(&self, column: Column) -> String;
+
+    /// Whether the row is a default route, which colored tables show in bold
+    fn is_default_route(&self) -> bool {
+        false
+    }
+}
+
+impl TableRow for Ipv4RouteEntry {
+    const DEFAULT_COLUMNS: &'static [Column] = &[
+        Column::Destination,
+        Column::Gateway,
+        Column::Genmask,
+        Column::Flags,
+        Column::Metric,
+        Column::Ref,
+        Column::Use,
+        Column::Iface,
+    ];
+
+    fn cell(&self, column: Column) -> String {
+        match column {
+            Column::Destination => self.dest.to_string(),
+            Column::Gateway => self.gateway.to_string(),
+            Column::Genmask => self.mask.to_string(),
+            Column::Source => "-".to_string(),
+            Column::Flags => flag_letters(&IPV4_FLAG_LETTERS, self.flags),
+            Column::Metric => self.metric.to_string(),
+            Column::Ref => self.ref_count.to_string(),
+            Column::Use => self.use_count.to_string(),
+            Column::Iface => self.name.to_string(),
+            Column::Mtu => self.mtu_raw().to_string(),
+            Column::Window => self.window_raw().to_string(),
+            Column::Irtt => self.irtt_raw().to_string(),
+        }
+    }
+
+    fn is_default_route(&self) -> bool {
+        Route::prefix_len(self) == 0
+    }
+}
+
+impl TableRow for Ipv6RouteEntry {
+    const DEFAULT_COLUMNS: &'static [Column] = &[
+        Column::Destination,
+        Column::Gateway,
+        Column::Flags,
+        Column::Metric,
+        Column::Ref,
+        Column::Use,
+        Column::Iface,
+    ];
+
+    fn cell(&self, column: Column) -> String {
+        match column {
+            Column::Destination => format!("{}/{}", self.dest, self.dest_prefix),
+            Column::Gateway => self.next_hop.to_string(),
+            Column::Source => format!("{}/{}", self.src, self.src_prefix),
+            Column::Flags => flag_letters(&IPV6_FLAG_LETTERS, self.flags),
+            Column::Metric => self.metric.to_string(),
+            Column::Ref => self.ref_count.to_string(),
+            Column::Use => self.use_count.to_string(),
+            Column::Iface => self.name.as_deref().unwrap_or_default().to_string(),
+            Column::Genmask | Column::Mtu | Column::Window | Column::Irtt => "-".to_string(),
+        }
+    }
+
+    fn is_default_route(&self) -> bool {
+        self.dest_prefix == 0
+    }
+}
+
+/// Rows of a table mixing both families, where the destination carries the prefix length of
+/// either
+impl TableRow for RouteEntry {
+    const DEFAULT_COLUMNS: &'static [Column] = Ipv6RouteEntry::DEFAULT_COLUMNS;
+
+    fn cell(&self, column: Column) -> String {
+        match (self, column) {
+            (_, Column::Destination) => self.network().to_string(),
+            (Self::V4(entry), column) => entry.cell(column),
+            (Self::V6(entry), column) => entry.cell(column),
+        }
+    }
+
+    fn is_default_route(&self) -> bool {
+        self.prefix_len() == 0
+    }
+}
+
+/// net-tools' `inet_gr.c` letters
+const IPV4_FLAG_LETTERS: [(Ipv4RouteFlags, char); 7] = [
+    (Ipv4RouteFlags::UP, 'U'),
+    (Ipv4RouteFlags::GATEWAY, 'G'),
+    (Ipv4RouteFlags::HOST, 'H'),
+    (Ipv4RouteFlags::REINSTATE, 'R'),
+    (Ipv4RouteFlags::DYNAMIC, 'D'),
+    (Ipv4RouteFlags::MODIFIED, 'M'),
+    (Ipv4RouteFlags::REJECT, '!'),
+];
+
+/// net-tools' `inet6_gr.c` letters
+const IPV6_FLAG_LETTERS: [(Ipv6RouteFlags, char); 12] = [
+    (Ipv6RouteFlags::UP, 'U'),
+    (Ipv6RouteFlags::REJECT, '!'),
+    (Ipv6RouteFlags::GATEWAY, 'G'),
+    (Ipv6RouteFlags::HOST, 'H'),
+    (Ipv6RouteFlags::DEFAULT, 'D'),
+    (Ipv6RouteFlags::ADDR_CONF, 'A'),
+    (Ipv6RouteFlags::CACHE, 'C'),
+    (Ipv6RouteFlags::ALL_ON_LINK, 'a'),
+    (Ipv6RouteFlags::EXPIRES, 'e'),
+    (Ipv6RouteFlags::MODIFIED, 'm'),
+    (Ipv6RouteFlags::NO_NEXT_HOP, 'n'),
+    (Ipv6RouteFlags::FLOW, 'f'),
+];
+
+fn flag_letters<F: bitflags::Flags + Copy>(letters: &[(F, char)], flags: F) -> String {
+    letters
+        .iter()
+        .filter(|(flag, _)| flags.contains(*flag))
+        .map(|(_, letter)| *letter)
+        .collect()
+}
+
+/// ANSI color of a flag letter: up in green, gateway in cyan, reject in red, the rest in yellow
+fn letter_color(letter: char) -> &'static str {
+    match letter {
+        'U' => "\x1b[32m",
+        'G' => "\x1b[36m",
+        '!' => "\x1b[31m",
+        _ => "\x1b[33m",
+    }
+}
+
+/// Formats entries into a column-aligned text table in the style of `route -n`
+#[derive(Debug, Clone, Default)]
+pub struct TableRenderer {
+    columns: Option<Vec<Column>>,
+    color: bool,
+    group_by_interface: bool,
+}
+
+impl TableRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Columns to show, in order, instead of [`TableRow::DEFAULT_COLUMNS`]
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Column>) -> Self {
+        self.columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// Color the flag letters with ANSI escape codes and set default routes in bold
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Put the routes of each interface together under a line naming it, the interfaces in the
+    /// order they first appear. The columns stay aligned across the groups.
+    pub fn group_by_interface(mut self, group: bool) -> Self {
+        self.group_by_interface = group;
+        self
+    }
+
+    /// Render a header line followed by one line per entry
+    pub fn render<E: TableRow>(&self, entries: &[E]) -> String {
+        let columns = self.columns.as_deref().unwrap_or(E::DEFAULT_COLUMNS);
+        let rows: Vec<Vec<String>> = entries
+            .iter()
+            .map(|entry| columns.iter().map(|column| entry.cell(*column)).collect())
+            .collect();
+        let widths: Vec<usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .fold(column.header().len(), usize::max)
+            })
+            .collect();
+
+        let mut out = String::new();
+        let headers = columns.iter().map(|column| column.header().to_string());
+        self.write_line(&mut out, columns, &widths, headers, false, false);
+        if !self.group_by_interface {
+            for (entry, row) in entries.iter().zip(rows) {
+                let bold = self.color && entry.is_default_route();
+                self.write_line(&mut out, columns, &widths, row, self.color, bold);
+            }
+            return out;
+        }
+        let mut interfaces: Vec<String> = Vec::new();
+        for entry in entries {
+            let iface = entry.cell(Column::Iface);
+            if !interfaces.contains(&iface) {
+                interfaces.push(iface);
+            }
+        }
+        for iface in interfaces {
+            if self.color {
+                let _ = writeln!(out, "\x1b[1m{iface}:\x1b[0m");
+            } else {
+                let _ = writeln!(out, "{iface}:");
+            }
+            for (entry, row) in entries.iter().zip(&rows) {
+                if entry.cell(Column::Iface) == iface {
+                    let bold = self.color && entry.is_default_route();
+                    self.write_line(&mut out, columns, &widths, row.clone(), self.color, bold);
+                }
+            }
+        }
+        out
+    }
+
+    fn write_line(
+        &self,
+        out: &mut String,
+        columns: &[Column],
+        widths: &[usize],
+        cells: impl IntoIterator<Item = String>,
+        color: bool,
+        bold: bool,
+    ) {
+        let mut line = String::new();
+        for ((column, width), cell) in columns.iter().zip(widths).zip(cells) {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            // pad by hand so that escape codes do not count towards the width
+            let padding = " ".repeat(width - cell.chars().count());
+            if column.is_numeric() {
+                line.push_str(&padding);
+            }
+            if color && *column == Column::Flags {
+                for letter in cell.chars() {
+                    let _ = write!(line, "{}{letter}\x1b[0m", letter_color(letter));
+                }
+            } else if bold {
+                let _ = write!(line, "\x1b[1m{cell}\x1b[0m");
+            } else {
+                line.push_str(&cell);
+            }
+            if !column.is_numeric() {
+                line.push_str(&padding);
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+}
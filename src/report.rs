@@ -0,0 +1,187 @@
+use crate::{AddressFamily, Limit, LineError, RouteParseError, options::Limits};
+use std::{
+    io::{self, BufRead, Read},
+    mem,
+    path::Path,
+    sync::Arc,
+};
+
+/// Diagnostics collected while iterating a lenient route table
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    malformed: Vec<LineError>,
+}
+
+impl ParseReport {
+    pub(crate) fn record(&mut self, error: LineError) {
+        self.malformed.push(error);
+    }
+
+    /// Whether every line read so far parsed successfully
+    pub fn is_clean(&self) -> bool {
+        self.malformed.is_empty()
+    }
+
+    /// Lines skipped so far, in file order
+    pub fn malformed(&self) -> &[LineError] {
+        &self.malformed
+    }
+}
+
+/// Counters of a table read, see for example [`Ipv4RouteTable::stats`](crate::Ipv4RouteTable::stats)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TableStats {
+    /// Lines read so far, including the header line
+    pub lines_read: u64,
+    /// Lines that parsed into an entry, including entries a filter dropped afterwards
+    pub entries_parsed: u64,
+    /// Lines that failed to read or parse
+    pub parse_errors: u64,
+    /// Bytes read so far, including line terminators
+    pub bytes_read: u64,
+}
+
+/// The lines of a table source, numbered from 1 and counted into [`TableStats`]
+pub(crate) struct TableLines<R> {
+    reader: R,
+    line_number: usize,
+    skip_header: bool,
+    stats: TableStats,
+    family: Option<AddressFamily>,
+    path: Option<Arc<Path>>,
+    limits: Limits,
+    /// Whether the entry limit was hit, ending the lines
+    exhausted: bool,
+}
+
+impl<R: BufRead> TableLines<R> {
+    /// Lines of `reader`, leaving out the first one if it is a header
+    pub(crate) fn new(reader: R, header: bool, family: Option<AddressFamily>) -> Self {
+        Self {
+            reader,
+            line_number: 0,
+            skip_header: header,
+            stats: TableStats::default(),
+            family,
+            path: None,
+            limits: Limits::default(),
+            exhausted: false,
+        }
+    }
+
+    pub(crate) fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The next line without its terminator, along with its line number
+    pub(crate) fn next_line(&mut self) -> Option<(usize, Result<String, RouteParseError>)> {
+        if self.exhausted {
+            return None;
+        }
+        if mem::take(&mut self.skip_header)
+            && let header @ (_, Err(_)) = self.read_line()?
+        {
+            return Some(header);
+        }
+        let max_entries = self.limits.max_entries;
+        if self.stats.entries_parsed + self.stats.parse_errors >= max_entries as u64 {
+            // only fail if there is a line past the limit
+            if self.reader.fill_buf().is_ok_and(|buf| buf.is_empty()) {
+                return None;
+            }
+            self.exhausted = true;
+            let limit = RouteParseError::LimitExceeded {
+                limit: Limit::Entries,
+                max: max_entries,
+            };
+            return Some((self.line_number + 1, Err(limit)));
+        }
+        self.read_line()
+    }
+
+    fn read_line(&mut self) -> Option<(usize, Result<String, RouteParseError>)> {
+        let max_len = self.limits.max_line_len;
+        let mut line = Vec::new();
+        // room for the terminator, so that a line of exactly `max_len` bytes passes
+        let mut bounded = (&mut self.reader).take(max_len as u64 + 2);
+        let result = match bounded.read_until(b'\n', &mut line) {
+            Ok(0) => return None,
+            Ok(len) => {
+                self.stats.bytes_read += len as u64;
+                let terminated = line.ends_with(b"\n");
+                if terminated {
+                    line.pop();
+                    if line.ends_with(b"\r") {
+                        line.pop();
+                    }
+                }
+                if line.len() > max_len {
+                    if !terminated {
+                        self.skip_rest_of_line();
+                    }
+                    Err(RouteParseError::LimitExceeded {
+                        limit: Limit::LineLength,
+                        max: max_len,
+                    })
+                } else {
+                    String::from_utf8(line)
+                        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+                        .and_then(|line| self.check_fields(line))
+                }
+            }
+            Err(err) => Err(err.into()),
+        };
+        self.line_number += 1;
+        self.stats.lines_read += 1;
+        Some((self.line_number, result))
+    }
+
+    fn check_fields(&self, line: String) -> Result<String, RouteParseError> {
+        let max = self.limits.max_fields;
+        if line.split_whitespace().nth(max).is_some() {
+            return Err(RouteParseError::LimitExceeded {
+                limit: Limit::FieldCount,
+                max,
+            });
+        }
+        Ok(line)
+    }
+
+    /// Discard the remainder of an overlong line without buffering it
+    fn skip_rest_of_line(&mut self) {
+        if let Ok(len) = self.reader.skip_until(b'\n') {
+            self.stats.bytes_read += len as u64;
+        }
+    }
+}
+
+impl<R> TableLines<R> {
+    /// Count a line as parsed into an entry or as failed
+    pub(crate) fn record(&mut self, parsed: bool) {
+        if parsed {
+            self.stats.entries_parsed += 1;
+        } else {
+            self.stats.parse_errors += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> TableStats {
+        self.stats
+    }
+
+    /// Name the file the lines come from in the errors
+    pub(crate) fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.into());
+    }
+
+    /// A [`LineError`] carrying the family and path of the table
+    pub(crate) fn error(
+        &self,
+        line_number: usize,
+        raw: String,
+        source: RouteParseError,
+    ) -> LineError {
+        LineError::new(line_number, raw, source).in_table(self.family, self.path.as_ref())
+    }
+}
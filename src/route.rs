@@ -0,0 +1,352 @@
+use crate::{IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags};
+use core::{fmt, net::IpAddr};
+
+/// A route metric, the kernel's priority of a route.
+///
+/// Lower metrics are preferred, so sorting metrics puts the route the kernel picks first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Metric(pub u32);
+
+impl Metric {
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Whether a route with this metric wins over one with `other`, all else being equal
+    pub fn is_preferred_over(self, other: Self) -> bool {
+        self < other
+    }
+}
+
+impl From<u32> for Metric {
+    fn from(metric: u32) -> Self {
+        Self(metric)
+    }
+}
+
+impl From<Metric> for u32 {
+    fn from(metric: Metric) -> Self {
+        metric.0
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Accessors shared by the IPv4 and IPv6 route entry types
+pub trait Route {
+    /// Name of the output interface
+    fn interface(&self) -> &str;
+
+    /// Next hop, when packets are forwarded rather than delivered on-link
+    fn gateway(&self) -> Option<IpAddr>;
+
+    /// Network address of the destination prefix
+    fn destination(&self) -> IpAddr;
+
+    fn prefix_len(&self) -> u8;
+
+    /// Destination prefix as a network, clamping prefix lengths wider than the address
+    fn network(&self) -> IpNet {
+        let destination = self.destination();
+        let max = if destination.is_ipv4() { 32 } else { 128 };
+        IpNet::new(destination, self.prefix_len().min(max)).expect("prefix length is clamped")
+    }
+
+    /// Source prefix of a source-specific route, `None` for routes that match every source
+    fn source_prefix(&self) -> Option<IpNet> {
+        None
+    }
+
+    fn metric(&self) -> Metric;
+
+    /// Lookups that hit the route, as counted by the kernel (`Use`)
+    fn use_count(&self) -> u32;
+
+    /// References held on the route (`RefCnt`)
+    fn ref_count(&self) -> u32;
+
+    /// Whether the route is flagged `UP`
+    fn is_up(&self) -> bool;
+
+    /// Whether the route is flagged `REJECT`, as unreachable, prohibit and blackhole routes are
+    fn is_reject(&self) -> bool;
+
+    /// Whether the route leads to a single address
+    fn is_host(&self) -> bool {
+        self.network().prefix_len()
+            == if self.destination().is_ipv4() {
+                32
+            } else {
+                128
+            }
+    }
+
+    /// Whether the route carries the flag spelled `name` by its flags type, such as `GATEWAY` or
+    /// `ADDR_CONF`.
+    ///
+    /// Types without named flags answer for `UP`, `GATEWAY`, `HOST` and `REJECT` only.
+    fn has_flag(&self, name: &str) -> bool {
+        match name {
+            "UP" => self.is_up(),
+            "GATEWAY" => self.gateway().is_some(),
+            "HOST" => self.is_host(),
+            "REJECT" => self.is_reject(),
+            _ => false,
+        }
+    }
+
+    /// Router preference (RFC 4191) used to break ties between routes of equal metric, higher wins.
+    ///
+    /// Routes without a preference rank as medium.
+    fn preference_rank(&self) -> u8 {
+        2
+    }
+
+    /// Whether `other` describes the same route, ignoring the reference and use counters
+    fn same_route(&self, other: &Self) -> bool
+    where
+        Self: Sized;
+}
+
+impl Route for Ipv4RouteEntry {
+    fn interface(&self) -> &str {
+        &self.name
+    }
+
+    fn gateway(&self) -> Option<IpAddr> {
+        self.flags
+            .contains(Ipv4RouteFlags::GATEWAY)
+            .then_some(self.gateway.into())
+    }
+
+    fn destination(&self) -> IpAddr {
+        self.dest.into()
+    }
+
+    fn prefix_len(&self) -> u8 {
+        Ipv4RouteEntry::prefix_len(self)
+    }
+
+    fn metric(&self) -> Metric {
+        Metric(self.metric)
+    }
+
+    fn use_count(&self) -> u32 {
+        self.use_count
+    }
+
+    fn ref_count(&self) -> u32 {
+        self.ref_count
+    }
+
+    fn is_up(&self) -> bool {
+        self.flags.contains(Ipv4RouteFlags::UP)
+    }
+
+    fn is_reject(&self) -> bool {
+        self.flags.contains(Ipv4RouteFlags::REJECT)
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        Ipv4RouteFlags::from_name(name).is_some_and(|flag| self.flags.contains(flag))
+    }
+
+    fn same_route(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.dest == other.dest
+            && self.gateway == other.gateway
+            && self.flags == other.flags
+            && self.metric == other.metric
+            && self.mask == other.mask
+            && self.mtu == other.mtu
+            && self.window == other.window
+            && self.irtt == other.irtt
+    }
+}
+
+impl Route for Ipv6RouteEntry {
+    fn interface(&self) -> &str {
+        self.name.as_deref().unwrap_or_default()
+    }
+
+    fn gateway(&self) -> Option<IpAddr> {
+        self.flags
+            .contains(Ipv6RouteFlags::GATEWAY)
+            .then_some(self.next_hop.into())
+    }
+
+    fn destination(&self) -> IpAddr {
+        self.dest.into()
+    }
+
+    fn prefix_len(&self) -> u8 {
+        self.dest_prefix
+    }
+
+    fn source_prefix(&self) -> Option<IpNet> {
+        (self.src_prefix > 0).then(|| self.source_network())
+    }
+
+    fn metric(&self) -> Metric {
+        Metric(self.metric)
+    }
+
+    fn use_count(&self) -> u32 {
+        self.use_count
+    }
+
+    fn ref_count(&self) -> u32 {
+        self.ref_count
+    }
+
+    fn is_up(&self) -> bool {
+        self.flags.contains(Ipv6RouteFlags::UP)
+    }
+
+    fn is_reject(&self) -> bool {
+        self.flags.contains(Ipv6RouteFlags::REJECT)
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        Ipv6RouteFlags::from_name(name).is_some_and(|flag| self.flags.contains(flag))
+    }
+
+    fn same_route(&self, other: &Self) -> bool {
+        self.dest == other.dest
+            && self.dest_prefix == other.dest_prefix
+            && self.src == other.src
+            && self.src_prefix == other.src_prefix
+            && self.next_hop == other.next_hop
+            && self.metric == other.metric
+            && self.flags == other.flags
+            && self.name == other.name
+    }
+
+    fn preference_rank(&self) -> u8 {
+        self.preference() as u8
+    }
+}
+
+/// An entry of either family, for collections that hold both tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RouteEntry {
+    V4(Ipv4RouteEntry),
+    V6(Ipv6RouteEntry),
+}
+
+impl From<Ipv4RouteEntry> for RouteEntry {
+    fn from(entry: Ipv4RouteEntry) -> Self {
+        Self::V4(entry)
+    }
+}
+
+impl From<Ipv6RouteEntry> for RouteEntry {
+    fn from(entry: Ipv6RouteEntry) -> Self {
+        Self::V6(entry)
+    }
+}
+
+impl Route for RouteEntry {
+    fn interface(&self) -> &str {
+        match self {
+            Self::V4(entry) => entry.interface(),
+            Self::V6(entry) => entry.interface(),
+        }
+    }
+
+    fn gateway(&self) -> Option<IpAddr> {
+        match self {
+            Self::V4(entry) => entry.gateway(),
+            Self::V6(entry) => entry.gateway(),
+        }
+    }
+
+    fn destination(&self) -> IpAddr {
+        match self {
+            Self::V4(entry) => entry.destination(),
+            Self::V6(entry) => entry.destination(),
+        }
+    }
+
+    fn prefix_len(&self) -> u8 {
+        match self {
+            Self::V4(entry) => Route::prefix_len(entry),
+            Self::V6(entry) => Route::prefix_len(entry),
+        }
+    }
+
+    fn source_prefix(&self) -> Option<IpNet> {
+        match self {
+            Self::V4(entry) => entry.source_prefix(),
+            Self::V6(entry) => entry.source_prefix(),
+        }
+    }
+
+    fn metric(&self) -> Metric {
+        match self {
+            Self::V4(entry) => entry.metric(),
+            Self::V6(entry) => entry.metric(),
+        }
+    }
+
+    fn use_count(&self) -> u32 {
+        match self {
+            Self::V4(entry) => entry.use_count(),
+            Self::V6(entry) => entry.use_count(),
+        }
+    }
+
+    fn ref_count(&self) -> u32 {
+        match self {
+            Self::V4(entry) => entry.ref_count(),
+            Self::V6(entry) => entry.ref_count(),
+        }
+    }
+
+    fn is_up(&self) -> bool {
+        match self {
+            Self::V4(entry) => entry.is_up(),
+            Self::V6(entry) => entry.is_up(),
+        }
+    }
+
+    fn is_reject(&self) -> bool {
+        match self {
+            Self::V4(entry) => entry.is_reject(),
+            Self::V6(entry) => entry.is_reject(),
+        }
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        match self {
+            Self::V4(entry) => entry.has_flag(name),
+            Self::V6(entry) => entry.has_flag(name),
+        }
+    }
+
+    fn preference_rank(&self) -> u8 {
+        match self {
+            Self::V4(entry) => entry.preference_rank(),
+            Self::V6(entry) => entry.preference_rank(),
+        }
+    }
+
+    /// Entries of different families are never the same route
+    fn same_route(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::V4(entry), Self::V4(other)) => entry.same_route(other),
+            (Self::V6(entry), Self::V6(other)) => entry.same_route(other),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Column layouts that map the fields of a table line to the columns they hold
+
+use crate::{HeaderError, RouteParseError};
+use alloc::{boxed::Box, vec::Vec};
+
+/// A known column of a whitespace separated `/proc` table
+pub trait TableColumn: Copy + Eq + 'static {
+    /// Every known column, in the order the kernel prints them
+    const ALL: &'static [Self];
+
+    /// The column's name in the header line
+    fn name(self) -> &'static str;
+
+    /// Position of the column within [`Self::ALL`]
+    fn index(self) -> usize;
+}
+
+/// Where each column of a table sits within its lines.
+///
+/// Columns this crate does not know are kept as `None` and skipped when parsing, so a kernel that
+/// reorders or appends columns can still be read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Schema<C> {
+    columns: Vec<Option<C>>,
+}
+
+/// The layout of the kernel's own tables, [`TableColumn::ALL`] in order
+impl<C: TableColumn> Default for Schema<C> {
+    fn default() -> Self {
+        Self {
+            columns: C::ALL.iter().copied().map(Some).collect(),
+        }
+    }
+}
+
+impl<C: TableColumn> Schema<C> {
+    /// Lay out the columns in the given order, which must hold every known column exactly once
+    pub fn from_columns(columns: impl IntoIterator<Item = Option<C>>) -> Result<Self, HeaderError> {
+        let columns: Vec<_> = columns.into_iter().collect();
+        for &column in C::ALL {
+            match columns
+                .iter()
+                .filter(|&&found| found == Some(column))
+                .count()
+            {
+                0 => return Err(HeaderError::MissingColumn(column.name())),
+                1 => {}
+                _ => return Err(HeaderError::DuplicateColumn(column.name())),
+            }
+        }
+        Ok(Self { columns })
+    }
+
+    /// Detect the layout of a header line, keeping columns with unknown names as `None`
+    pub fn from_header(header: &str) -> Result<Self, HeaderError> {
+        Self::from_columns(
+            header
+                .split_whitespace()
+                .map(|name| C::ALL.iter().copied().find(|column| column.name() == name)),
+        )
+    }
+
+    /// The columns in line order, `None` for the ones this crate does not know
+    pub fn columns(&self) -> &[Option<C>] {
+        &self.columns
+    }
+
+    /// Index of `column` within a line
+    pub fn position(&self, column: C) -> usize {
+        self.columns
+            .iter()
+            .position(|&found| found == Some(column))
+            .expect("a schema holds every column")
+    }
+
+    /// Whether the columns are exactly [`TableColumn::ALL`], in order
+    pub fn is_standard(&self) -> bool {
+        self.columns
+            .iter()
+            .copied()
+            .eq(C::ALL.iter().copied().map(Some))
+    }
+
+    /// Split `line` along the schema, or along [`TableColumn::ALL`] without one
+    pub(crate) fn split<'a, const N: usize>(
+        schema: Option<&Self>,
+        line: &'a str,
+    ) -> SplitLine<'a, N> {
+        debug_assert_eq!(N, C::ALL.len());
+        let width = schema.map_or(N, |schema| schema.columns.len());
+        let mut fields = [""; N];
+        let mut found = 0;
+        for field in line.split_whitespace() {
+            let column = match schema {
+                Some(schema) => schema.columns.get(found).copied().flatten().map(C::index),
+                None => (found < N).then_some(found),
+            };
+            if let Some(index) = column {
+                fields[index] = field;
+            }
+            found += 1;
+        }
+        SplitLine {
+            fields,
+            found,
+            width,
+        }
+    }
+}
+
+/// A line split along a [`Schema`]
+pub(crate) struct SplitLine<'a, const N: usize> {
+    /// The known columns in [`TableColumn::ALL`] order, empty where the line ends early
+    pub(crate) fields: [&'a str; N],
+    /// Number of fields in the line
+    pub(crate) found: usize,
+    /// Number of columns in the schema
+    pub(crate) width: usize,
+}
+
+/// Attach the column to the error of converting one of its fields
+pub(crate) fn in_column<C: TableColumn, T, E: Into<RouteParseError>>(
+    column: C,
+    converted: Result<T, E>,
+) -> Result<T, RouteParseError> {
+    converted.map_err(|err| RouteParseError::InvalidField {
+        column: column.name(),
+        index: column.index(),
+        source: Box::new(err.into()),
+    })
+}
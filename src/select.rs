@@ -0,0 +1,153 @@
+use crate::{Ipv4RouteEntry, Ipv6RouteEntry, Route};
+use alloc::vec::Vec;
+use core::{
+    cmp::Reverse,
+    net::{IpAddr, Ipv6Addr},
+};
+
+/// Pick the route the kernel would use to reach `dest`.
+///
+/// Only routes that are `UP` and whose prefix covers `dest` are candidates. Among them the longest
+/// prefix wins, then the lowest metric, then the highest IPv6 router preference. Remaining ties go to
+/// the route listed first, matching the kernel's table order. Reject routes are candidates like any
+/// other: when one wins, the kernel answers with an ICMP unreachable.
+///
+/// Policy rules are not modelled, and `/proc/net/route` only lists the main table, so local
+/// addresses such as `127.0.0.1` resolve through whatever main-table route covers them.
+pub fn select_best<R: Route>(routes: impl IntoIterator<Item = R>, dest: IpAddr) -> Option<R> {
+    select_best_by(routes, dest, |route| {
+        (
+            route.prefix_len(),
+            Reverse(route.metric()),
+            route.preference_rank(),
+        )
+    })
+}
+
+/// Pick the IPv6 route the kernel would use to reach `dest` from `source`, honoring
+/// source-specific routes (`ip -6 route add ... from PREFIX`).
+///
+/// Like [`select_best`], but only routes whose source prefix covers `source` are candidates, and
+/// only source-independent ones when `source` is `None`. The longest destination prefix wins
+/// first, then the longest source prefix, as the kernel looks up the source within the
+/// destination's node.
+pub fn select_best_from(
+    routes: impl IntoIterator<Item = Ipv6RouteEntry>,
+    dest: Ipv6Addr,
+    source: Option<Ipv6Addr>,
+) -> Option<Ipv6RouteEntry> {
+    let routes = routes
+        .into_iter()
+        .filter(|route| route.matches_source(source));
+    select_best_by(routes, dest.into(), |route| {
+        (
+            route.dest_prefix,
+            route.src_prefix,
+            Reverse(route.metric()),
+            route.preference_rank(),
+        )
+    })
+}
+
+/// The `UP` route covering `dest` with the highest `rank`, the first one listed among equals
+fn select_best_by<R: Route, K: Ord>(
+    routes: impl IntoIterator<Item = R>,
+    dest: IpAddr,
+    rank: impl Fn(&R) -> K,
+) -> Option<R> {
+    let mut best: Option<R> = None;
+    for route in routes {
+        if !route.is_up() || !route.network().contains(dest) {
+            continue;
+        }
+        if best.as_ref().is_none_or(|best| rank(&route) > rank(best)) {
+            best = Some(route);
+        }
+    }
+    best
+}
+
+/// Why a default route lost to the one the kernel uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DefaultRouteLoss {
+    /// The route is not flagged `UP`
+    Down,
+    HigherMetric,
+    /// Same metric, but a lower IPv6 router preference
+    LowerPreference,
+    /// Tied with the winner, which comes first in the table
+    ListedLater,
+}
+
+/// A default route that is installed but not used
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefaultRouteLoser<R> {
+    pub route: R,
+    pub reason: DefaultRouteLoss,
+}
+
+/// Outcome of choosing between several default routes, such as Wi-Fi, Ethernet and a VPN
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefaultRouteSelection<R> {
+    /// The route the kernel sends traffic through, `None` without a default route that is up
+    pub winner: Option<R>,
+    /// The other default routes in table order
+    pub losers: Vec<DefaultRouteLoser<R>>,
+}
+
+/// Pick the IPv4 default route the kernel would use, see [`effective_default_route_v6`]
+pub fn effective_default_route_v4(
+    entries: impl IntoIterator<Item = Ipv4RouteEntry>,
+) -> DefaultRouteSelection<Ipv4RouteEntry> {
+    effective_default_route(entries)
+}
+
+/// Pick the IPv6 default route the kernel would use.
+///
+/// Among the `UP` routes to `::/0` the lowest metric wins, then the highest router preference, then
+/// the route listed first. Every other default route is returned with the reason it lost.
+pub fn effective_default_route_v6(
+    entries: impl IntoIterator<Item = Ipv6RouteEntry>,
+) -> DefaultRouteSelection<Ipv6RouteEntry> {
+    effective_default_route(entries)
+}
+
+fn effective_default_route<R: Route>(
+    entries: impl IntoIterator<Item = R>,
+) -> DefaultRouteSelection<R> {
+    let rank = |route: &R| (Reverse(route.metric()), route.preference_rank());
+    let defaults: Vec<R> = entries
+        .into_iter()
+        .filter(|route| route.prefix_len() == 0)
+        .collect();
+    let winner_index = defaults
+        .iter()
+        .enumerate()
+        .filter(|(_, route)| route.is_up())
+        // `max_by_key` keeps the last of equal elements, so rank the table position too
+        .max_by_key(|(i, route)| (rank(route), Reverse(*i)))
+        .map(|(i, _)| i);
+
+    let mut winner = None;
+    let mut losers = Vec::new();
+    let winner_rank = winner_index.map(|i| rank(&defaults[i]));
+    for (i, route) in defaults.into_iter().enumerate() {
+        if Some(i) == winner_index {
+            winner = Some(route);
+            continue;
+        }
+        let reason = match winner_rank {
+            _ if !route.is_up() => DefaultRouteLoss::Down,
+            Some((Reverse(metric), _)) if route.metric() > metric => DefaultRouteLoss::HigherMetric,
+            Some((_, preference)) if route.preference_rank() < preference => {
+                DefaultRouteLoss::LowerPreference
+            }
+            _ => DefaultRouteLoss::ListedLater,
+        };
+        losers.push(DefaultRouteLoser { route, reason });
+    }
+    DefaultRouteSelection { winner, losers }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    ArpEntry, ArpTable, ErrorCode, Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteTable,
+    LineError, ProcRoot, RouteTableOptions,
+};
+#[cfg(feature = "serde")]
+use std::{borrow::Cow, io::Write};
+use std::{
+    fs,
+    io::{self, Cursor},
+    path::Path,
+    time::SystemTime,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("I/O error reading or writing a snapshot")]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Parse(#[from] LineError),
+
+    #[cfg(feature = "serde")]
+    #[error("Malformed snapshot file")]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "serde")]
+    #[error("Not a route snapshot, the magic field is not {:?}", SnapshotV1::MAGIC)]
+    BadMagic,
+
+    #[cfg(feature = "serde")]
+    #[error(
+        "Unsupported snapshot version {0}, this release reads version {supported}",
+        supported = SnapshotV1::VERSION
+    )]
+    UnsupportedVersion(u64),
+}
+
+impl SnapshotError {
+    /// The kind of error as a stable code
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Io(err) => ErrorCode::of_io(err),
+            Self::Parse(err) => err.error_code(),
+            #[cfg(feature = "serde")]
+            Self::Json(_) | Self::BadMagic => ErrorCode::MalformedSnapshot,
+            #[cfg(feature = "serde")]
+            Self::UnsupportedVersion(_) => ErrorCode::UnsupportedSnapshotVersion,
+        }
+    }
+}
+
+/// The IPv4 and IPv6 route tables and the ARP table, read back to back
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// When the files were read
+    pub captured_at: SystemTime,
+    pub ipv4: Vec<Ipv4RouteEntry>,
+    pub ipv6: Vec<Ipv6RouteEntry>,
+    pub arp: Vec<ArpEntry>,
+}
+
+impl Snapshot {
+    /// Capture the tables of `/proc/net`
+    pub fn capture() -> Result<Self, SnapshotError> {
+        ProcRoot::default().snapshot()
+    }
+
+    /// Capture the tables of a directory laid out like `/proc/net`.
+    ///
+    /// All three files are read into memory before any parsing so they are as close in time as possible.
+    /// A missing `ipv6_route`, as on a host booted with `ipv6.disable=1`, reads as an empty table.
+    pub fn capture_from(proc_net: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let proc_net = proc_net.as_ref();
+        #[cfg(feature = "tracing")]
+        let _entered =
+            tracing::debug_span!("capture_snapshot", proc_net = %proc_net.display()).entered();
+        let route = fs::read(proc_net.join("route"))?;
+        let ipv6_route = match fs::read(proc_net.join("ipv6_route")) {
+            Ok(ipv6_route) => ipv6_route,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let arp = fs::read(proc_net.join("arp"))?;
+        let captured_at = SystemTime::now();
+        let options = RouteTableOptions::new().intern_names(true);
+        Ok(Self {
+            captured_at,
+            ipv4: Ipv4RouteTable::from_reader(Cursor::new(route), options.clone())
+                .collect::<Result<_, _>>()?,
+            ipv6: Ipv6RouteTable::from_reader(Cursor::new(ipv6_route), options)
+                .collect::<Result<_, _>>()?,
+            arp: ArpTable::from_reader(Cursor::new(arp)).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Write the snapshot in the [`SnapshotV1`] format, without host details
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        SnapshotFile::new(self, None, None).save(path.as_ref())
+    }
+
+    /// Read a snapshot written by [`Snapshot::save`] or [`SnapshotV1::save`], see
+    /// [`SnapshotV1::load`]
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Ok(SnapshotV1::load(path)?.snapshot)
+    }
+}
+
+/// A [`Snapshot`] with details of the host it was captured on, in the versioned file format
+/// written by [`SnapshotV1::save`] and [`Snapshot::save`].
+///
+/// The file is a JSON object with `magic` set to [`Self::MAGIC`] and `version` to
+/// [`Self::VERSION`], next to the fields of the snapshot. Newer releases of the crate may add
+/// fields without changing the version, and loading ignores fields it does not know; a change
+/// older releases cannot read bumps the version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotV1 {
+    /// `kernel.hostname` of the host
+    pub hostname: Option<String>,
+    /// `kernel.osrelease` of the host, e.g. `6.8.0-45-generic`
+    pub kernel_version: Option<String>,
+    pub snapshot: Snapshot,
+}
+
+impl SnapshotV1 {
+    pub const MAGIC: &str = "proc-route-snapshot";
+    pub const VERSION: u64 = 1;
+
+    /// Capture the tables of `/proc/net` and the details of this host
+    pub fn capture() -> Result<Self, SnapshotError> {
+        ProcRoot::default().snapshot_v1()
+    }
+
+    /// Write the snapshot as JSON
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        SnapshotFile::new(
+            &self.snapshot,
+            self.hostname.as_deref(),
+            self.kernel_version.as_deref(),
+        )
+        .save(path.as_ref())
+    }
+
+    /// Read a snapshot written by [`Self::save`], by a newer release of the crate that kept the
+    /// version, or as plain JSON by a release that predates the versioned format
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let file = fs::File::open_buffered(path)?;
+        let value: serde_json::Value = serde_json::from_reader(file)?;
+        let Some(magic) = value.get("magic") else {
+            return Ok(serde_json::from_value::<Snapshot>(value)?.into());
+        };
+        if magic.as_str() != Some(Self::MAGIC) {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = value.get("version").and_then(serde_json::Value::as_u64);
+        if version != Some(Self::VERSION) {
+            return Err(SnapshotError::UnsupportedVersion(
+                version.unwrap_or_default(),
+            ));
+        }
+        Ok(serde_json::from_value::<SnapshotFile>(value)?.into())
+    }
+}
+
+impl From<Snapshot> for SnapshotV1 {
+    fn from(snapshot: Snapshot) -> Self {
+        Self {
+            hostname: None,
+            kernel_version: None,
+            snapshot,
+        }
+    }
+}
+
+/// Layout of a [`SnapshotV1`] file
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SnapshotFile<'a> {
+    magic: Cow<'a, str>,
+    version: u64,
+    #[serde(default)]
+    hostname: Option<Cow<'a, str>>,
+    #[serde(default)]
+    kernel_version: Option<Cow<'a, str>>,
+    captured_at: SystemTime,
+    ipv4: Cow<'a, [Ipv4RouteEntry]>,
+    ipv6: Cow<'a, [Ipv6RouteEntry]>,
+    #[serde(default)]
+    arp: Cow<'a, [ArpEntry]>,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> SnapshotFile<'a> {
+    fn new(
+        snapshot: &'a Snapshot,
+        hostname: Option<&'a str>,
+        kernel_version: Option<&'a str>,
+    ) -> Self {
+        Self {
+            magic: SnapshotV1::MAGIC.into(),
+            version: SnapshotV1::VERSION,
+            hostname: hostname.map(Cow::Borrowed),
+            kernel_version: kernel_version.map(Cow::Borrowed),
+            captured_at: snapshot.captured_at,
+            ipv4: Cow::Borrowed(&snapshot.ipv4),
+            ipv6: Cow::Borrowed(&snapshot.ipv6),
+            arp: Cow::Borrowed(&snapshot.arp),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<(), SnapshotError> {
+        let mut writer = io::BufWriter::new(fs::File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        // dropping the writer would swallow an error writing out the rest of the buffer
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SnapshotFile<'_>> for SnapshotV1 {
+    fn from(file: SnapshotFile<'_>) -> Self {
+        Self {
+            hostname: file.hostname.map(Cow::into_owned),
+            kernel_version: file.kernel_version.map(Cow::into_owned),
+            snapshot: Snapshot {
+                captured_at: file.captured_at,
+                ipv4: file.ipv4.into_owned(),
+                ipv6: file.ipv6.into_owned(),
+                arp: file.arp.into_owned(),
+            },
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ProcRoot;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Error, ErrorKind, Result as IoResult},
+};
+use thiserror::Error;
+
+/// A counter the kernel is expected to report is absent from the table
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Counter {0} missing from the SNMP table")]
+pub struct MissingCounter(pub &'static str);
+
+/// Routing failures and forwarding counters of `net/snmp` and `net/netstat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ipv4RouteCounters {
+    /// `Ip: Forwarding` is 1, the host routes packets between interfaces
+    pub forwarding: bool,
+    pub in_receives: u64,
+    pub in_addr_errors: u64,
+    pub forw_datagrams: u64,
+    /// Locally generated packets dropped for lack of a route
+    pub out_no_routes: u64,
+    /// `IpExt: InNoRoutes`, received packets that had no route to be forwarded on, `None` without
+    /// `net/netstat`
+    pub in_no_routes: Option<u64>,
+    pub in_dest_unreachs: u64,
+    pub out_dest_unreachs: u64,
+    pub in_redirects: u64,
+    pub out_redirects: u64,
+}
+
+/// Routing failures and forwarding counters of `net/snmp6`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ipv6RouteCounters {
+    pub in_receives: u64,
+    /// Received packets that had no route to be forwarded on
+    pub in_no_routes: u64,
+    pub in_addr_errors: u64,
+    pub out_forw_datagrams: u64,
+    /// Locally generated packets dropped for lack of a route
+    pub out_no_routes: u64,
+    pub in_dest_unreachs: u64,
+    pub out_dest_unreachs: u64,
+    pub in_redirects: u64,
+    pub out_redirects: u64,
+}
+
+impl Ipv4RouteCounters {
+    /// Parse the contents of `net/snmp` and, where available, `net/netstat`
+    pub fn parse(snmp: &str, netstat: Option<&str>) -> Result<Self, MissingCounter> {
+        let counters = Counters::parse(snmp);
+        Ok(Self {
+            forwarding: counters.get("IpForwarding")? == 1,
+            in_receives: counters.get("IpInReceives")?,
+            in_addr_errors: counters.get("IpInAddrErrors")?,
+            forw_datagrams: counters.get("IpForwDatagrams")?,
+            out_no_routes: counters.get("IpOutNoRoutes")?,
+            in_no_routes: netstat
+                .map(|netstat| Counters::parse(netstat).get("IpExtInNoRoutes"))
+                .transpose()?,
+            in_dest_unreachs: counters.get("IcmpInDestUnreachs")?,
+            out_dest_unreachs: counters.get("IcmpOutDestUnreachs")?,
+            in_redirects: counters.get("IcmpInRedirects")?,
+            out_redirects: counters.get("IcmpOutRedirects")?,
+        })
+    }
+
+    /// Packets dropped in either direction because no route matched
+    pub fn no_route_drops(&self) -> u64 {
+        self.out_no_routes + self.in_no_routes.unwrap_or(0)
+    }
+}
+
+impl Ipv6RouteCounters {
+    /// Parse the contents of `net/snmp6`
+    pub fn parse(snmp6: &str) -> Result<Self, MissingCounter> {
+        let counters = Counters::parse(snmp6);
+        Ok(Self {
+            in_receives: counters.get("Ip6InReceives")?,
+            in_no_routes: counters.get("Ip6InNoRoutes")?,
+            in_addr_errors: counters.get("Ip6InAddrErrors")?,
+            out_forw_datagrams: counters.get("Ip6OutForwDatagrams")?,
+            out_no_routes: counters.get("Ip6OutNoRoutes")?,
+            in_dest_unreachs: counters.get("Icmp6InDestUnreachs")?,
+            out_dest_unreachs: counters.get("Icmp6OutDestUnreachs")?,
+            in_redirects: counters.get("Icmp6InRedirects")?,
+            out_redirects: counters.get("Icmp6OutRedirects")?,
+        })
+    }
+
+    /// Packets dropped in either direction because no route matched
+    pub fn no_route_drops(&self) -> u64 {
+        self.out_no_routes + self.in_no_routes
+    }
+}
+
+/// Counters keyed by protocol and name, e.g. `IpOutNoRoutes`
+struct Counters(BTreeMap<String, u64>);
+
+impl Counters {
+    /// Read both layouts: `net/snmp` and `net/netstat` pair a `Proto: Name ...` header line with a
+    /// `Proto: value ...` line, `net/snmp6` has one `ProtoName value` per line. Values that are
+    /// not counters, like `Tcp: MaxConn -1`, are left out.
+    fn parse(text: &str) -> Self {
+        let mut counters = BTreeMap::new();
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+            if let Some(proto) = first.strip_suffix(':') {
+                let Some(values) = lines.next() else {
+                    break;
+                };
+                for (name, value) in fields.zip(values.split_whitespace().skip(1)) {
+                    if let Ok(value) = value.parse() {
+                        counters.insert(format!("{proto}{name}"), value);
+                    }
+                }
+            } else if let Some(Ok(value)) = fields.next().map(str::parse) {
+                counters.insert(first.to_string(), value);
+            }
+        }
+        Self(counters)
+    }
+
+    fn get(&self, name: &'static str) -> Result<u64, MissingCounter> {
+        self.0.get(name).copied().ok_or(MissingCounter(name))
+    }
+}
+
+impl ProcRoot {
+    /// Read the IPv4 routing counters of `net/snmp`, with `InNoRoutes` from `net/netstat`
+    pub fn ipv4_route_counters(&self) -> IoResult<Ipv4RouteCounters> {
+        let snmp = fs::read_to_string(self.net().join("snmp"))?;
+        let netstat = match fs::read_to_string(self.net().join("netstat")) {
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            netstat => Some(netstat?),
+        };
+        Ipv4RouteCounters::parse(&snmp, netstat.as_deref())
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Read the IPv6 routing counters of `net/snmp6`
+    pub fn ipv6_route_counters(&self) -> IoResult<Ipv6RouteCounters> {
+        let snmp6 = fs::read_to_string(self.net().join("snmp6"))?;
+        Ipv6RouteCounters::parse(&snmp6).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{ProcRoot, Route, select_best, utils::hex_str_to_ipv6};
+use std::{
+    fs,
+    io::Result as IoResult,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// `IFA_F_*` bits of `net/if_inet6` that rule an address out or down
+const IFA_F_DADFAILED: u8 = 0x08;
+const IFA_F_DEPRECATED: u8 = 0x20;
+const IFA_F_TENTATIVE: u8 = 0x40;
+
+/// `IPV6_ADDR_*` scopes as printed in `net/if_inet6`
+const SCOPE_GLOBAL: u8 = 0x00;
+const SCOPE_HOST: u8 = 0x10;
+const SCOPE_LINK: u8 = 0x20;
+
+impl ProcRoot {
+    /// Predict the source address the kernel picks for packets to `dest`.
+    ///
+    /// The route to `dest` is looked up as in [`select_best`], then an address of its interface is
+    /// chosen: for IPv4 one from `net/fib_trie` on the same link as the next hop, for IPv6 one from
+    /// `net/if_inet6` of matching scope, preferring non-deprecated addresses and the longest prefix
+    /// shared with `dest`. Explicit `src` hints on routes are not visible in `/proc`, see
+    /// [`RouteManager::preferred_source`](crate::RouteManager) for the kernel's own answer.
+    pub fn preferred_source(&self, dest: IpAddr) -> IoResult<Option<IpAddr>> {
+        match dest {
+            IpAddr::V4(dest) => self.preferred_ipv4_source(dest),
+            IpAddr::V6(dest) => self.preferred_ipv6_source(dest),
+        }
+    }
+
+    fn preferred_ipv4_source(&self, dest: Ipv4Addr) -> IoResult<Option<IpAddr>> {
+        let locals = local_ipv4_addrs(&fs::read_to_string(self.net().join("fib_trie"))?);
+        // the local table, which `net/route` leaves out, routes these over the loopback
+        if dest.is_loopback() {
+            return Ok(Some(Ipv4Addr::LOCALHOST.into()));
+        }
+        if locals.contains(&dest) {
+            return Ok(Some(dest.into()));
+        }
+        let routes: Vec<_> = self.ipv4_route_table()?.filter_map(Result::ok).collect();
+        let Some(route) = select_best(routes.iter().cloned(), dest.into()) else {
+            return Ok(None);
+        };
+        let next_hop = route.gateway().unwrap_or(dest.into());
+        let links: Vec<_> = routes
+            .iter()
+            .filter(|link| link.name == route.name && link.is_up() && link.gateway().is_none())
+            .map(|link| link.network())
+            .collect();
+        let on_link = |addr: &&Ipv4Addr| links.iter().any(|link| link.contains((**addr).into()));
+        let same_link = |addr: &&Ipv4Addr| {
+            links
+                .iter()
+                .any(|link| link.contains(next_hop) && link.contains((**addr).into()))
+        };
+        let source = locals
+            .iter()
+            .find(same_link)
+            .or_else(|| locals.iter().find(on_link));
+        Ok(source.map(|addr| IpAddr::V4(*addr)))
+    }
+
+    fn preferred_ipv6_source(&self, dest: Ipv6Addr) -> IoResult<Option<IpAddr>> {
+        let Some(table) = self.open_ipv6_route_table()? else {
+            return Ok(None);
+        };
+        let routes: Vec<_> = table.filter_map(Result::ok).collect();
+        let Some(route) = select_best(routes.iter().cloned(), dest.into()) else {
+            return Ok(None);
+        };
+        let if_inet6 = fs::read_to_string(self.net().join("if_inet6"))?;
+        let dest_scope = ipv6_scope(dest);
+        let source = if_inet6
+            .lines()
+            .filter_map(parse_if_inet6_line)
+            .filter(|addr| Some(addr.iface) == route.name.as_deref())
+            .filter(|addr| addr.flags & (IFA_F_TENTATIVE | IFA_F_DADFAILED) == 0)
+            .max_by_key(|addr| {
+                (
+                    addr.addr == dest,
+                    addr.scope == dest_scope,
+                    addr.flags & IFA_F_DEPRECATED == 0,
+                    (u128::from(addr.addr) ^ u128::from(dest)).leading_zeros(),
+                )
+            });
+        Ok(source.map(|addr| IpAddr::V6(addr.addr)))
+    }
+}
+
+/// Addresses of the `/32 host LOCAL` leaves of `net/fib_trie`, which are the host's own
+fn local_ipv4_addrs(fib_trie: &str) -> Vec<Ipv4Addr> {
+    let mut locals = Vec::new();
+    let mut leaf = None;
+    for line in fib_trie.lines().map(str::trim) {
+        if let Some(addr) = line.strip_prefix("|-- ") {
+            leaf = addr.parse().ok();
+        } else if line == "/32 host LOCAL"
+            && let Some(addr) = leaf
+            && !locals.contains(&addr)
+        {
+            locals.push(addr);
+        }
+    }
+    locals
+}
+
+struct InterfaceAddr<'a> {
+    addr: Ipv6Addr,
+    scope: u8,
+    flags: u8,
+    iface: &'a str,
+}
+
+/// Parse `address ifindex prefix_len scope flags name`, all but the name in hex
+fn parse_if_inet6_line(line: &str) -> Option<InterfaceAddr<'_>> {
+    let fields: Vec<_> = line.split_whitespace().collect();
+    let [addr, _, _, scope, flags, iface] = fields[..] else {
+        return None;
+    };
+    Some(InterfaceAddr {
+        addr: hex_str_to_ipv6(addr).ok()?,
+        scope: u8::from_str_radix(scope, 16).ok()?,
+        flags: u8::from_str_radix(flags, 16).ok()?,
+        iface,
+    })
+}
+
+fn ipv6_scope(addr: Ipv6Addr) -> u8 {
+    if addr.is_loopback() {
+        SCOPE_HOST
+    } else if addr.is_unicast_link_local() {
+        SCOPE_LINK
+    } else {
+        SCOPE_GLOBAL
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    env,
+    io::{Error, ErrorKind, Result as IoResult},
+    os::{
+        linux::net::SocketAddrExt,
+        unix::net::{SocketAddr, UnixDatagram},
+    },
+    process,
+    time::Duration,
+};
+
+/// Sends service state notifications to systemd (`sd_notify`), for services of `Type=notify`
+#[derive(Debug)]
+pub struct SystemdNotifier {
+    socket: UnixDatagram,
+    watchdog_timeout: Option<Duration>,
+}
+
+impl SystemdNotifier {
+    /// The notifier systemd sets up through `NOTIFY_SOCKET`, `None` when the process was not
+    /// started by systemd with notifications enabled.
+    ///
+    /// The watchdog timeout comes from `WATCHDOG_USEC`, unless `WATCHDOG_PID` names another
+    /// process.
+    pub fn from_env() -> IoResult<Option<Self>> {
+        let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+            return Ok(None);
+        };
+        let path = path
+            .into_string()
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "NOTIFY_SOCKET is not UTF-8"))?;
+        let mut notifier = Self::connect(&path)?;
+        let for_this_process =
+            env::var("WATCHDOG_PID").map_or(true, |pid| pid.parse::<u32>() == Ok(process::id()));
+        if for_this_process
+            && let Some(usec) = env::var("WATCHDOG_USEC")
+                .ok()
+                .and_then(|usec| usec.parse().ok())
+                .filter(|&usec| usec > 0)
+        {
+            notifier.watchdog_timeout = Some(Duration::from_micros(usec));
+        }
+        Ok(Some(notifier))
+    }
+
+    /// Notify over the socket at `path`, where a leading `@` names an abstract socket
+    pub fn connect(path: &str) -> IoResult<Self> {
+        let addr = match path.strip_prefix('@') {
+            Some(name) => SocketAddr::from_abstract_name(name)?,
+            None => SocketAddr::from_pathname(path)?,
+        };
+        let socket = UnixDatagram::unbound()?;
+        socket.connect_addr(&addr)?;
+        Ok(Self {
+            socket,
+            watchdog_timeout: None,
+        })
+    }
+
+    /// Expect watchdog pings within `timeout`, as `WatchdogSec=` does
+    pub fn watchdog_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog_timeout = Some(timeout);
+        self
+    }
+
+    /// How often to ping the watchdog: half its timeout, as systemd recommends. `None` when the
+    /// service has no watchdog.
+    pub fn watchdog_interval(&self) -> Option<Duration> {
+        self.watchdog_timeout.map(|timeout| timeout / 2)
+    }
+
+    /// Send newline-separated `KEY=VALUE` assignments, see `sd_notify(3)`
+    pub fn notify(&self, state: &str) -> IoResult<()> {
+        self.socket.send(state.as_bytes()).map(drop)
+    }
+
+    /// `READY=1`: startup finished
+    pub fn ready(&self) -> IoResult<()> {
+        self.notify("READY=1")
+    }
+
+    /// `WATCHDOG=1`: the service is alive
+    pub fn ping_watchdog(&self) -> IoResult<()> {
+        self.notify("WATCHDOG=1")
+    }
+
+    /// `STOPPING=1`: the service is shutting down
+    pub fn stopping(&self) -> IoResult<()> {
+        self.notify("STOPPING=1")
+    }
+
+    /// `STATUS=`: a line of text shown by `systemctl status`
+    pub fn status(&self, status: &str) -> IoResult<()> {
+        self.notify(&format!("STATUS={status}"))
+    }
+}
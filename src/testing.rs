@@ -0,0 +1,111 @@
+use crate::{
+    Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteTable, RouteIter, RouteSource,
+    RouteTableOptions,
+};
+use std::{
+    fs,
+    io::Result as IoResult,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+/// Header line of `/proc/net/arp`
+const ARP_HEADER: &str =
+    "IP address       HW type     Flags       HW address            Mask     Device";
+
+/// A fake `/proc/net` directory holding fixture `route`, `ipv6_route` and `arp` files.
+///
+/// The directory lives in a tempdir and is removed when the value is dropped.
+/// It starts out with empty tables: only the headers the kernel would print.
+pub struct MockProcNet {
+    dir: TempDir,
+}
+
+impl MockProcNet {
+    pub fn new() -> IoResult<Self> {
+        let mock = Self {
+            dir: TempDir::new()?,
+        };
+        mock.set_ipv4_routes(&[])?;
+        mock.set_ipv6_routes(&[])?;
+        mock.set_arp_raw("")?;
+        Ok(mock)
+    }
+
+    /// Directory standing in for `/proc/net`
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn ipv4_route_path(&self) -> PathBuf {
+        self.path().join("route")
+    }
+
+    pub fn ipv6_route_path(&self) -> PathBuf {
+        self.path().join("ipv6_route")
+    }
+
+    pub fn arp_path(&self) -> PathBuf {
+        self.path().join("arp")
+    }
+
+    /// Replace the `route` file with the given entries, formatted as the kernel does
+    pub fn set_ipv4_routes(&self, entries: &[Ipv4RouteEntry]) -> IoResult<()> {
+        let mut contents = format!("{}\n", Ipv4RouteEntry::PROC_HEADER);
+        for entry in entries {
+            contents.push_str(&entry.to_proc_line());
+            contents.push('\n');
+        }
+        fs::write(self.ipv4_route_path(), contents)
+    }
+
+    /// Replace the `ipv6_route` file with the given entries, formatted as the kernel does
+    pub fn set_ipv6_routes(&self, entries: &[Ipv6RouteEntry]) -> IoResult<()> {
+        let contents: String = entries
+            .iter()
+            .map(|entry| entry.to_proc_line() + "\n")
+            .collect();
+        fs::write(self.ipv6_route_path(), contents)
+    }
+
+    /// Replace the `route` file verbatim, including its header line
+    pub fn set_ipv4_raw(&self, contents: &str) -> IoResult<()> {
+        fs::write(self.ipv4_route_path(), contents)
+    }
+
+    /// Replace the `ipv6_route` file verbatim
+    pub fn set_ipv6_raw(&self, contents: &str) -> IoResult<()> {
+        fs::write(self.ipv6_route_path(), contents)
+    }
+
+    /// Replace the body of the `arp` file, the header line is written for you
+    pub fn set_arp_raw(&self, body: &str) -> IoResult<()> {
+        fs::write(self.arp_path(), format!("{ARP_HEADER}\n{body}"))
+    }
+
+    pub fn ipv4_table(&self) -> IoResult<Ipv4RouteTable> {
+        Ipv4RouteTable::open(self.ipv4_route_path())
+    }
+
+    pub fn ipv4_table_with(&self, options: RouteTableOptions) -> IoResult<Ipv4RouteTable> {
+        Ipv4RouteTable::open_with(self.ipv4_route_path(), options)
+    }
+
+    pub fn ipv6_table(&self) -> IoResult<Ipv6RouteTable> {
+        Ipv6RouteTable::open(self.ipv6_route_path())
+    }
+
+    pub fn ipv6_table_with(&self, options: RouteTableOptions) -> IoResult<Ipv6RouteTable> {
+        Ipv6RouteTable::open_with(self.ipv6_route_path(), options)
+    }
+}
+
+impl RouteSource for MockProcNet {
+    fn ipv4_routes(&self) -> IoResult<RouteIter<'_, Ipv4RouteEntry>> {
+        Ok(Box::new(self.ipv4_table()?))
+    }
+
+    fn ipv6_routes(&self) -> IoResult<RouteIter<'_, Ipv6RouteEntry>> {
+        Ok(Box::new(self.ipv6_table()?))
+    }
+}
@@ -0,0 +1,13 @@
+//! Spans of the `tracing` feature
+use std::path::Path;
+use tracing::{Span, field};
+
+/// Span covering the parse of one table, entered on every `next` call
+pub(crate) fn table_span(table: &'static str) -> Span {
+    tracing::debug_span!("parse_table", table, path = field::Empty)
+}
+
+/// Record the file a table span reads from
+pub(crate) fn record_path(span: &Span, path: &Path) {
+    span.record("path", field::display(path.display()));
+}
@@ -1,5 +1,13 @@
-use core::slice::SlicePattern;
-use std::net::{Ipv4Addr, Ipv6Addr};
+#[cfg(feature = "std")]
+use crate::IfName;
+use crate::MacAddr;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::net::{Ipv4Addr, Ipv6Addr};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,19 +16,40 @@ pub enum ConvertError {
     OddStringLength(String),
 
     #[error("Failed to convert the slice into u8 array")]
-    SliceToBytes(#[from] std::array::TryFromSliceError),
+    SliceToBytes(#[from] core::array::TryFromSliceError),
+
+    #[error("Expected {expected} hex digits, found {found}")]
+    UnexpectedLength { expected: usize, found: usize },
 
     #[error("Invalid u8: {0},just ensure the ascii code is within 0..=(F/f)")]
     OutOfHexRange(u8),
 }
 
+/// Marks bytes that are not hex digits in [`HEX_LUT`]
+const INVALID_HEX: u8 = 0x80;
+
+/// Nibble value of every ASCII hex digit, [`INVALID_HEX`] for anything else
+static HEX_LUT: [u8; 256] = {
+    let mut lut = [INVALID_HEX; 256];
+    let mut i = 0;
+    while i < 10 {
+        lut[b'0' as usize + i] = i as u8;
+        i += 1;
+    }
+    let mut i = 0;
+    while i < 6 {
+        lut[b'a' as usize + i] = 10 + i as u8;
+        lut[b'A' as usize + i] = 10 + i as u8;
+        i += 1;
+    }
+    lut
+};
+
 #[inline(always)]
 pub(crate) fn hex_char_to_u8(hex: u8) -> Result<u8, ConvertError> {
-    match hex {
-        b'0'..=b'9' => Ok(hex - b'0'),
-        b'a'..=b'f' => Ok(hex - b'a' + 10),
-        b'A'..=b'F' => Ok(hex - b'A' + 10),
-        a => Err(ConvertError::OutOfHexRange(a)),
+    match HEX_LUT[hex as usize] {
+        INVALID_HEX => Err(ConvertError::OutOfHexRange(hex)),
+        nibble => Ok(nibble),
     }
 }
 
@@ -30,35 +59,106 @@ pub(crate) fn hex_char_pair_to_byte(double_chars: [u8; 2]) -> Result<u8, Convert
     Ok(hex_char_to_u8(high)? << 4 | hex_char_to_u8(low)?)
 }
 
+/// Decode `text` into `out`, which must be exactly half as long as `text`.
+///
+/// Works on 16 hex digits at a time and only checks for invalid digits once per block.
 #[inline(always)]
-pub(crate) fn hex_str_to_bytes(text: &str) -> Result<Box<[u8]>, ConvertError> {
-    if text.len() % 2 != 0 {
+pub(crate) fn decode_hex_into(text: &str, out: &mut [u8]) -> Result<(), ConvertError> {
+    if !text.len().is_multiple_of(2) {
         return Err(ConvertError::OddStringLength(text.to_string()));
     }
-    let mut buf = Vec::with_capacity(text.len() / 2);
-    for hex_char_pair in text.as_bytes().chunks_exact(2) {
-        let (hex_byte_pair, _) = hex_char_pair.as_chunks::<2>();
-        buf.push(hex_char_pair_to_byte(hex_byte_pair[0])?);
+    if text.len() != out.len() * 2 {
+        return Err(ConvertError::UnexpectedLength {
+            expected: out.len() * 2,
+            found: text.len(),
+        });
+    }
+    let (blocks, tail) = text.as_bytes().as_chunks::<16>();
+    let (out_blocks, out_tail) = out.as_chunks_mut::<8>();
+    for (block, out_block) in blocks.iter().zip(out_blocks) {
+        let mut invalid = 0;
+        let (hex_char_pairs, _) = block.as_chunks::<2>();
+        for (byte, [high, low]) in out_block.iter_mut().zip(hex_char_pairs) {
+            let (high, low) = (HEX_LUT[*high as usize], HEX_LUT[*low as usize]);
+            invalid |= high | low;
+            *byte = high << 4 | low;
+        }
+        if invalid & INVALID_HEX != 0 {
+            let hex = block
+                .iter()
+                .find(|hex| HEX_LUT[**hex as usize] == INVALID_HEX)
+                .copied()
+                .unwrap_or_default();
+            return Err(ConvertError::OutOfHexRange(hex));
+        }
+    }
+    let (hex_char_pairs, _) = tail.as_chunks::<2>();
+    for (byte, hex_char_pair) in out_tail.iter_mut().zip(hex_char_pairs) {
+        *byte = hex_char_pair_to_byte(*hex_char_pair)?;
     }
-    Ok(buf.into_boxed_slice())
+    Ok(())
+}
+
+/// Decode exactly `N` bytes of hex into a stack array
+#[inline(always)]
+pub(crate) fn hex_str_to_array<const N: usize>(text: &str) -> Result<[u8; N], ConvertError> {
+    let mut buf = [0u8; N];
+    decode_hex_into(text, &mut buf)?;
+    Ok(buf)
 }
 
 #[inline(always)]
 pub(crate) fn hex_str_to_ipv6(text: &str) -> Result<Ipv6Addr, ConvertError> {
-    Ok(Ipv6Addr::from_octets(
-        hex_str_to_bytes(text)?.as_slice().try_into()?,
-    ))
+    Ok(Ipv6Addr::from_octets(hex_str_to_array(text)?))
 }
 
 #[inline(always)]
 pub(crate) fn hex_str_to_ipv4(text: &str) -> Result<Ipv4Addr, ConvertError> {
-    if text.len() != 8 {
-        return Err(ConvertError::OddStringLength(text.to_string()));
+    Ok(Ipv4Addr::from(u32::from_le_bytes(hex_str_to_array(text)?)))
+}
+
+#[inline(always)]
+pub(crate) fn ipv6_to_hex_str(addr: Ipv6Addr) -> String {
+    format!("{:032x}", u128::from(addr))
+}
+
+#[inline(always)]
+pub(crate) fn ipv4_to_hex_str(addr: Ipv4Addr) -> String {
+    format!("{:08X}", u32::from_le_bytes(addr.octets()))
+}
+
+/// Parse a colon-separated hardware address of `N` octets, such as `02:fc:00:00:00:01` for
+/// Ethernet or the 20 octets of an InfiniBand address
+pub(crate) fn parse_hw_addr<const N: usize>(address: &str) -> Option<[u8; N]> {
+    let mut hw_addr = [0u8; N];
+    let mut octets = address.split(':');
+    for byte in &mut hw_addr {
+        *byte = hex_str_to_array::<1>(octets.next()?).ok()?[0];
     }
-    let mut bytes = [0u8; 4];
-    for (i, chunk) in text.as_bytes().chunks_exact(2).enumerate() {
-        bytes[i] = hex_char_pair_to_byte([chunk[0], chunk[1]])?;
+    octets.next().is_none().then_some(hw_addr)
+}
+
+/// Parse a colon-separated Ethernet address, see [`parse_hw_addr`]
+pub(crate) fn parse_mac(address: &str) -> Option<MacAddr> {
+    parse_hw_addr(address).map(MacAddr)
+}
+
+#[cfg(feature = "std")]
+/// Deduplicates interface names across the entries of a table
+#[derive(Debug, Default)]
+pub(crate) struct NameInterner {
+    names: HashSet<IfName>,
+}
+
+#[cfg(feature = "std")]
+impl NameInterner {
+    /// Share `name`, which the caller has checked with [`IfName::validate`]
+    pub(crate) fn intern(&mut self, name: &str) -> IfName {
+        if let Some(interned) = self.names.get(name) {
+            return interned.clone();
+        }
+        let interned = IfName::new_unchecked(name.into());
+        self.names.insert(interned.clone());
+        interned
     }
-    let addr_u32 = u32::from_le_bytes(bytes);
-    Ok(Ipv4Addr::from(addr_u32))
 }
@@ -0,0 +1,135 @@
+use crate::{IpNet, KernelRoute, Route, RouteEntry, RouteManager, RouteTables};
+use std::{
+    io::Result as IoResult,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+/// Destinations checked besides the routes' own prefixes, reached through the default routes
+const PUBLIC_DESTINATIONS: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    IpAddr::V6(Ipv6Addr::new(0x2606, 0x4700, 0x4700, 0, 0, 0, 0, 0x1111)),
+];
+
+/// A destination that [`RouteTables::lookup`] and the kernel route differently
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupMismatch {
+    pub dest: IpAddr,
+    /// The route picked from the parsed tables
+    pub ours: Option<RouteEntry>,
+    /// The route the kernel matched, `None` when it has no route to `dest`
+    pub kernel: Option<KernelRoute>,
+}
+
+/// Outcome of [`verify_lookups`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Destinations compared
+    pub checked: usize,
+    /// IPv4 destinations the kernel answered with a route `/proc/net/route` does not list: a
+    /// local or broadcast route, or one from a table other than main
+    pub skipped: usize,
+    pub mismatches: Vec<LookupMismatch>,
+}
+
+impl VerifyReport {
+    /// Whether the kernel agreed on every compared destination
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compare [`RouteTables::lookup`] with the kernel's own lookup (`RTM_GETROUTE`) for each of
+/// `dests`.
+///
+/// The routes agree when they have the same prefix and one of the kernel's next hops has the
+/// gateway and interface of the parsed route. A `REJECT` route matches the kernel finding none.
+pub fn verify_lookups(
+    tables: &RouteTables,
+    dests: impl IntoIterator<Item = IpAddr>,
+) -> IoResult<VerifyReport> {
+    let mut manager = RouteManager::new()?;
+    verify_lookups_with(tables, dests, |dest| manager.lookup(dest))
+}
+
+/// Like [`verify_lookups`], asking `lookup` for the kernel's route instead of `RTM_GETROUTE`,
+/// e.g. to check answers recorded on another host
+pub fn verify_lookups_with(
+    tables: &RouteTables,
+    dests: impl IntoIterator<Item = IpAddr>,
+    mut lookup: impl FnMut(IpAddr) -> IoResult<Option<KernelRoute>>,
+) -> IoResult<VerifyReport> {
+    let mut report = VerifyReport::default();
+    for dest in dests {
+        let kernel = lookup(dest)?;
+        if dest.is_ipv4()
+            && let Some(route) = &kernel
+            && !in_proc_route(route)
+        {
+            report.skipped += 1;
+            continue;
+        }
+        report.checked += 1;
+        let ours = tables.lookup(dest);
+        if !agrees(ours.as_ref(), kernel.as_ref()) {
+            report
+                .mismatches
+                .push(LookupMismatch { dest, ours, kernel });
+        }
+    }
+    Ok(report)
+}
+
+/// A sample of destinations exercising every route of `tables`: the first address after each
+/// prefix's network address, plus a public address of each family
+pub fn sample_destinations(tables: &RouteTables) -> Vec<IpAddr> {
+    let networks = tables
+        .ipv4
+        .iter()
+        .map(Route::network)
+        .chain(tables.ipv6.iter().map(Route::network));
+    let mut dests: Vec<_> = networks
+        .filter(|network| network.prefix_len() > 0)
+        .map(first_address)
+        .chain(PUBLIC_DESTINATIONS)
+        .collect();
+    dests.sort_unstable();
+    dests.dedup();
+    dests
+}
+
+/// The address after the network address, or the only one of a host route
+fn first_address(network: IpNet) -> IpAddr {
+    match network.addr() {
+        IpAddr::V4(addr) if network.prefix_len() < 32 => {
+            Ipv4Addr::from_bits(addr.to_bits() + 1).into()
+        }
+        IpAddr::V6(addr) if network.prefix_len() < 128 => {
+            Ipv6Addr::from_bits(addr.to_bits() + 1).into()
+        }
+        addr => addr,
+    }
+}
+
+fn in_proc_route(route: &KernelRoute) -> bool {
+    route.table == u32::from(libc::RT_TABLE_MAIN)
+        && !matches!(
+            route.route_type,
+            libc::RTN_LOCAL | libc::RTN_BROADCAST | libc::RTN_ANYCAST | libc::RTN_MULTICAST
+        )
+}
+
+fn agrees(ours: Option<&RouteEntry>, kernel: Option<&KernelRoute>) -> bool {
+    let ours = ours.filter(|route| !route.is_reject());
+    let kernel = kernel.filter(|route| !route.next_hops.is_empty());
+    match (ours, kernel) {
+        (None, None) => true,
+        (Some(ours), Some(kernel)) => {
+            ours.network() == kernel.destination
+                && kernel
+                    .next_hops
+                    .iter()
+                    .any(|hop| hop.gateway == ours.gateway() && hop.iface == ours.interface())
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,110 @@
+//! Audit of the routes that let traffic escape a VPN tunnel
+
+use crate::{Ipv6RouteFlags, Route, RouteEntry};
+use alloc::vec::Vec;
+use core::{cmp::Reverse, net::IpAddr};
+
+/// How a route lets traffic bypass the tunnel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LeakKind {
+    /// The route outranks the tunnel route covering its destination, by a longer prefix or a lower
+    /// metric, so its traffic leaves through another interface
+    Bypass,
+    /// A default route of an address family the tunnel does not carry at all, such as an IPv6
+    /// default next to an IPv4-only tunnel
+    UntunneledFamily,
+}
+
+/// A route sending traffic past the tunnel
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Leak {
+    pub route: RouteEntry,
+    pub kind: LeakKind,
+}
+
+/// Outcome of [`detect_leaks`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeakReport {
+    /// Whether the tunnel has a catch-all route for IPv4, `0.0.0.0/0` or the `0.0.0.0/1` and
+    /// `128.0.0.0/1` pair
+    pub ipv4_tunneled: bool,
+    pub ipv6_tunneled: bool,
+    /// Leaking routes in input order
+    pub leaks: Vec<Leak>,
+}
+
+impl LeakReport {
+    pub fn is_empty(&self) -> bool {
+        self.leaks.is_empty()
+    }
+}
+
+/// Find the routes that send traffic outside the tunnel interface `vpn_iface`.
+///
+/// Routes that are down, reject routes, loopback and local routes, and link-local or multicast
+/// destinations never carry tunneled traffic and are ignored. The route to the VPN server itself
+/// is reported as a [`LeakKind::Bypass`] like any other, since it cannot be told apart.
+pub fn detect_leaks(entries: impl IntoIterator<Item = RouteEntry>, vpn_iface: &str) -> LeakReport {
+    let entries: Vec<RouteEntry> = entries.into_iter().filter(can_leak).collect();
+    let (tunnel, outside): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|entry| entry.interface() == vpn_iface);
+    let tunneled = |ipv4: bool| {
+        tunnel
+            .iter()
+            .any(|entry| entry.destination().is_ipv4() == ipv4 && entry.prefix_len() <= 1)
+    };
+    let mut report = LeakReport {
+        ipv4_tunneled: tunneled(true),
+        ipv6_tunneled: tunneled(false),
+        leaks: Vec::new(),
+    };
+
+    let rank = |route: &RouteEntry| (route.prefix_len(), Reverse(route.metric()));
+    for route in outside {
+        let network = route.network();
+        let best_tunnel = tunnel
+            .iter()
+            .filter(|tunnel| tunnel.network().contains_net(&network))
+            .map(rank)
+            .max();
+        let family_tunneled = match route {
+            RouteEntry::V4(_) => report.ipv4_tunneled,
+            RouteEntry::V6(_) => report.ipv6_tunneled,
+        };
+        let kind = match best_tunnel {
+            Some(best) if rank(&route) > best => LeakKind::Bypass,
+            Some(_) => continue,
+            None if route.prefix_len() == 0
+                && !family_tunneled
+                && (report.ipv4_tunneled || report.ipv6_tunneled) =>
+            {
+                LeakKind::UntunneledFamily
+            }
+            None => continue,
+        };
+        report.leaks.push(Leak { route, kind });
+    }
+    report
+}
+
+/// Whether traffic following the route could have gone through a tunnel instead
+fn can_leak(entry: &RouteEntry) -> bool {
+    let excluded = match entry {
+        RouteEntry::V4(entry) => entry.is_reject(),
+        RouteEntry::V6(entry) => {
+            entry.flags.contains(Ipv6RouteFlags::REJECT)
+                || entry.flags.contains(Ipv6RouteFlags::LOCAL)
+                || entry.is_link_local()
+                || entry.is_multicast_route()
+        }
+    };
+    let scoped = match entry.destination() {
+        IpAddr::V4(dest) => dest.is_link_local() || dest.is_multicast() || dest.is_loopback(),
+        IpAddr::V6(dest) => dest.is_loopback(),
+    };
+    entry.is_up() && !excluded && !scoped && entry.interface() != "lo"
+}
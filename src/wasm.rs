@@ -0,0 +1,90 @@
+//! Route tables injected by a host agent, for WASM and WASI targets that have no `/proc`.
+//!
+//! The host reads `/proc/net/route` and `/proc/net/ipv6_route` and passes their contents in with
+//! [`inject_ipv4_route_table`] and [`inject_ipv6_route_table`]. On `wasm` targets the crate's
+//! `get_ipv4_route_table` and `get_ipv6_route_table` are [`ipv4_route_table`] and
+//! [`ipv6_route_table`], so code written against `/proc` runs unchanged.
+
+use crate::{ArpTable, Ipv4RouteTable, Ipv6RouteTable, RouteParseError, RouteTableOptions};
+use std::{
+    io::{self, Cursor},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Contents of the injected files, shared with every table opened over them
+#[derive(Default)]
+struct Injected {
+    route: Option<Arc<[u8]>>,
+    ipv6_route: Option<Arc<[u8]>>,
+}
+
+static INJECTED: Mutex<Injected> = Mutex::new(Injected {
+    route: None,
+    ipv6_route: None,
+});
+
+/// Set the contents of `/proc/net/route` read by [`ipv4_route_table`], replacing earlier ones
+pub fn inject_ipv4_route_table(route: impl Into<Vec<u8>>) {
+    injected().route = Some(route.into().into());
+}
+
+/// Set the contents of `/proc/net/ipv6_route` read by [`ipv6_route_table`], replacing earlier
+/// ones. An empty file is a host without IPv6 routes, not one with IPv6 disabled.
+pub fn inject_ipv6_route_table(ipv6_route: impl Into<Vec<u8>>) {
+    injected().ipv6_route = Some(ipv6_route.into().into());
+}
+
+/// Forget the injected tables
+pub fn clear_injected_tables() {
+    *injected() = Injected::default();
+}
+
+/// Read the injected IPv4 table, a not found error until one is injected
+pub fn ipv4_route_table() -> io::Result<Ipv4RouteTable<Cursor<Arc<[u8]>>>> {
+    let route = injected()
+        .route
+        .clone()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no IPv4 route table injected"))?;
+    Ok(Ipv4RouteTable::from_reader(
+        Cursor::new(route),
+        RouteTableOptions::default(),
+    ))
+}
+
+/// Read the injected IPv6 table, [`RouteParseError::Ipv6Unavailable`] until one is injected
+pub fn ipv6_route_table() -> Result<Ipv6RouteTable<Cursor<Arc<[u8]>>>, RouteParseError> {
+    let ipv6_route = injected()
+        .ipv6_route
+        .clone()
+        .ok_or(RouteParseError::Ipv6Unavailable)?;
+    Ok(Ipv6RouteTable::from_reader(
+        Cursor::new(ipv6_route),
+        RouteTableOptions::default(),
+    ))
+}
+
+fn injected() -> MutexGuard<'static, Injected> {
+    // the contents are replaced whole, so a panic elsewhere cannot leave them half written
+    INJECTED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Ipv4RouteTable<Cursor<Vec<u8>>> {
+    /// Read the table from the contents of a `/proc/net/route` file, header line included
+    pub fn from_bytes(route: impl Into<Vec<u8>>, options: RouteTableOptions) -> Self {
+        Self::from_reader(Cursor::new(route.into()), options)
+    }
+}
+
+impl Ipv6RouteTable<Cursor<Vec<u8>>> {
+    /// Read the table from the contents of a `/proc/net/ipv6_route` file
+    pub fn from_bytes(ipv6_route: impl Into<Vec<u8>>, options: RouteTableOptions) -> Self {
+        Self::from_reader(Cursor::new(ipv6_route.into()), options)
+    }
+}
+
+impl ArpTable<Cursor<Vec<u8>>> {
+    /// Read the table from the contents of a `/proc/net/arp` file, header line included
+    pub fn from_bytes(arp: impl Into<Vec<u8>>) -> Self {
+        Self::from_reader(Cursor::new(arp.into()))
+    }
+}
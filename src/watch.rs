@@ -0,0 +1,273 @@
+use crate::{ProcRoot, RouteEvent, RouteFilter, Snapshot, SnapshotDiff, SnapshotError};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Watches the route tables by re-reading `/proc/net` on a timer, for systems without netlink access.
+///
+/// Each poll is compared with the last reported state and the differences are sent as a
+/// [`SnapshotDiff`]. With a debounce delay, a change is only reported once the tables have stayed
+/// the same for that long, so a burst of updates arrives as one diff.
+#[derive(Debug, Clone)]
+pub struct PollingRouteWatcher {
+    interval: Duration,
+    debounce: Duration,
+    proc_net: PathBuf,
+    hooks: Vec<Hook>,
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    watchdog: Option<Arc<crate::SystemdNotifier>>,
+}
+
+/// A callback registered with [`PollingRouteWatcher::on_change`]
+#[derive(Clone)]
+struct Hook {
+    filter: Option<RouteFilter>,
+    callback: Arc<dyn Fn(&RouteEvent) + Send + Sync>,
+}
+
+impl fmt::Debug for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hook")
+            .field("filter", &self.filter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PollingRouteWatcher {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            debounce: Duration::ZERO,
+            proc_net: ProcRoot::default().net(),
+            hooks: Vec::new(),
+            #[cfg(all(feature = "systemd", target_os = "linux"))]
+            watchdog: None,
+        }
+    }
+
+    /// Wait until the tables have been stable for `debounce` before reporting a change
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Poll a directory laid out like `/proc/net` instead of the real one
+    pub fn proc_net(mut self, proc_net: impl Into<PathBuf>) -> Self {
+        self.proc_net = proc_net.into();
+        self
+    }
+
+    /// Call `callback` with every route change, on the watcher thread and before the change is
+    /// delivered to the channel.
+    ///
+    /// A slow callback delays the next poll, so hand long work such as restarting a tunnel off to
+    /// another thread.
+    pub fn on_change(self, callback: impl Fn(&RouteEvent) + Send + Sync + 'static) -> Self {
+        self.hook(None, callback)
+    }
+
+    /// Like [`Self::on_change`], for the changes of routes passing `filter` before or after the
+    /// change, such as `dest == 0.0.0.0/0` for the default route
+    pub fn on_matching_change(
+        self,
+        filter: RouteFilter,
+        callback: impl Fn(&RouteEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.hook(Some(filter), callback)
+    }
+
+    /// Ping the systemd watchdog of `notifier` from the poll loop, so the pings stop when the
+    /// watcher hangs. Polls no further apart than half of `WatchdogSec=`, or the watchdog fires.
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    pub fn systemd_watchdog(mut self, notifier: Arc<crate::SystemdNotifier>) -> Self {
+        self.watchdog = Some(notifier);
+        self
+    }
+
+    fn hook(
+        mut self,
+        filter: Option<RouteFilter>,
+        callback: impl Fn(&RouteEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.push(Hook {
+            filter,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
+    /// Start polling on a background thread, delivering changes over a std channel.
+    ///
+    /// The initial read has to succeed; later failed reads are skipped.
+    pub fn start(self) -> Result<(WatcherHandle, Receiver<SnapshotDiff>), SnapshotError> {
+        let (tx, rx) = mpsc::channel();
+        let handle = self.spawn(move |diff| tx.send(diff).is_ok())?;
+        Ok((handle, rx))
+    }
+
+    /// Like [`PollingRouteWatcher::start`], delivering changes to a tokio broadcast channel
+    #[cfg(feature = "tokio")]
+    pub fn start_broadcast(
+        self,
+        capacity: usize,
+    ) -> Result<
+        (
+            WatcherHandle,
+            tokio::sync::broadcast::Receiver<SnapshotDiff>,
+        ),
+        SnapshotError,
+    > {
+        let (tx, rx) = tokio::sync::broadcast::channel(capacity);
+        let handle = self.spawn(move |diff| tx.send(diff).is_ok())?;
+        Ok((handle, rx))
+    }
+
+    /// Run the poll loop until `emit` reports that nobody is listening or the handle is stopped
+    fn spawn(
+        self,
+        mut emit: impl FnMut(SnapshotDiff) -> bool + Send + 'static,
+    ) -> Result<WatcherHandle, SnapshotError> {
+        let mut reported = Snapshot::capture_from(&self.proc_net)?;
+        let (stop, stopped) = mpsc::channel();
+        let sleep = move |duration| {
+            !matches!(
+                stopped.recv_timeout(duration),
+                Ok(()) | Err(RecvTimeoutError::Disconnected)
+            )
+        };
+        let thread = thread::Builder::new()
+            .name("route-watcher".into())
+            .spawn(move || {
+                #[cfg(feature = "tracing")]
+                let _entered =
+                    tracing::info_span!("route_watcher", proc_net = %self.proc_net.display())
+                        .entered();
+                #[cfg(all(feature = "systemd", target_os = "linux"))]
+                let mut last_ping = std::time::Instant::now();
+                while sleep(self.interval) {
+                    #[cfg(all(feature = "systemd", target_os = "linux"))]
+                    ping_watchdog(self.watchdog.as_deref(), &mut last_ping);
+                    let Some(mut current) = capture(&self.proc_net) else {
+                        continue;
+                    };
+                    if reported.diff(&current).is_empty() {
+                        continue;
+                    }
+                    // keep re-reading until nothing changed for a whole debounce period
+                    while !self.debounce.is_zero() {
+                        if !sleep(self.debounce) {
+                            return;
+                        }
+                        #[cfg(all(feature = "systemd", target_os = "linux"))]
+                        ping_watchdog(self.watchdog.as_deref(), &mut last_ping);
+                        let Some(next) = capture(&self.proc_net) else {
+                            break;
+                        };
+                        let settled = current.diff(&next).is_empty();
+                        current = next;
+                        if settled {
+                            break;
+                        }
+                    }
+                    let diff = reported.diff(&current);
+                    reported = current;
+                    #[cfg(feature = "tracing")]
+                    log_changes(&diff);
+                    run_hooks(&self.hooks, &diff);
+                    if !diff.is_empty() && !emit(diff) {
+                        return;
+                    }
+                }
+            })?;
+        Ok(WatcherHandle {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Read the tables for a poll, which is skipped if that fails
+fn capture(proc_net: &Path) -> Option<Snapshot> {
+    let snapshot = Snapshot::capture_from(proc_net);
+    #[cfg(feature = "tracing")]
+    if let Err(err) = &snapshot {
+        tracing::warn!(error = %err, "failed to read the route tables, skipping this poll");
+    }
+    snapshot.ok()
+}
+
+fn run_hooks(hooks: &[Hook], diff: &SnapshotDiff) {
+    if hooks.is_empty() {
+        return;
+    }
+    for event in diff.events() {
+        for hook in hooks {
+            if hook
+                .filter
+                .as_ref()
+                .is_none_or(|filter| event.change.matches(filter))
+            {
+                (hook.callback)(&event);
+            }
+        }
+    }
+}
+
+/// Ping the watchdog if half its timeout passed since `last_ping`
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+fn ping_watchdog(notifier: Option<&crate::SystemdNotifier>, last_ping: &mut std::time::Instant) {
+    if let Some(notifier) = notifier
+        && let Some(interval) = notifier.watchdog_interval()
+        && last_ping.elapsed() >= interval
+    {
+        let _ = notifier.ping_watchdog();
+        *last_ping = std::time::Instant::now();
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn log_changes(diff: &SnapshotDiff) {
+    tracing::info!(
+        ipv4 = diff.ipv4.len(),
+        ipv6 = diff.ipv6.len(),
+        "route tables changed"
+    );
+    for change in &diff.ipv4 {
+        tracing::debug!(family = "ipv4", ?change, "route changed");
+    }
+    for change in &diff.ipv6 {
+        tracing::debug!(family = "ipv6", ?change, "route changed");
+    }
+}
+
+/// Stops the watcher thread when dropped
+#[derive(Debug)]
+pub struct WatcherHandle {
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatcherHandle {
+    /// Stop polling and wait for the watcher thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatcherHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
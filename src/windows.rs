@@ -0,0 +1,154 @@
+//! Route tables read through the IP Helper API on Windows
+
+use crate::{
+    IfName, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, LineError,
+    RouteParseError, utils::NameInterner,
+};
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr},
+    ptr, slice,
+};
+use windows_sys::Win32::{
+    Foundation::NO_ERROR,
+    NetworkManagement::{
+        IpHelper::{
+            ConvertInterfaceLuidToNameW, FreeMibTable, GetIpForwardTable2, MIB_IPFORWARD_ROW2,
+            MIB_IPFORWARD_TABLE2,
+        },
+        Ndis::IF_MAX_STRING_SIZE,
+    },
+    Networking::WinSock::{ADDRESS_FAMILY, AF_INET, AF_INET6},
+};
+
+/// Get the IPv4 route table via `GetIpForwardTable2`.
+///
+/// Windows has no reference or use counters, so those are zero, and there is no MTU, window or IRTT.
+pub fn get_ipv4_route_table() -> io::Result<impl Iterator<Item = Result<Ipv4RouteEntry, LineError>>>
+{
+    let table = ForwardTable::get(AF_INET)?;
+    let mut names = NameInterner::default();
+    let entries: Vec<_> = table
+        .rows()
+        .iter()
+        .map(|row| Ok(ipv4_entry(row, &mut names)))
+        .collect();
+    Ok(entries.into_iter())
+}
+
+/// Get the IPv6 route table via `GetIpForwardTable2`, see [`get_ipv4_route_table`]
+pub fn get_ipv6_route_table()
+-> Result<impl Iterator<Item = Result<Ipv6RouteEntry, LineError>>, RouteParseError> {
+    let table = ForwardTable::get(AF_INET6)?;
+    let mut names = NameInterner::default();
+    let entries: Vec<_> = table
+        .rows()
+        .iter()
+        .map(|row| Ok(ipv6_entry(row, &mut names)))
+        .collect();
+    Ok(entries.into_iter())
+}
+
+fn ipv4_entry(row: &MIB_IPFORWARD_ROW2, names: &mut NameInterner) -> Ipv4RouteEntry {
+    // SAFETY: every row of an AF_INET table holds IPv4 socket addresses
+    let (dest, gateway) = unsafe {
+        (
+            row.DestinationPrefix.Prefix.Ipv4.sin_addr.S_un.S_addr,
+            row.NextHop.Ipv4.sin_addr.S_un.S_addr,
+        )
+    };
+    // S_addr is in network byte order
+    let dest = Ipv4Addr::from(u32::from_be(dest));
+    let gateway = Ipv4Addr::from(u32::from_be(gateway));
+    let prefix_len = row.DestinationPrefix.PrefixLength.min(32);
+    let mut flags = Ipv4RouteFlags::UP;
+    flags.set(Ipv4RouteFlags::GATEWAY, !gateway.is_unspecified());
+    flags.set(Ipv4RouteFlags::HOST, prefix_len == 32);
+    Ipv4RouteEntry {
+        name: interface_name(row, names),
+        dest,
+        gateway,
+        flags,
+        ref_count: 0,
+        use_count: 0,
+        metric: row.Metric,
+        mask: Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)),
+        mtu: None,
+        window: None,
+        irtt: None,
+    }
+}
+
+fn ipv6_entry(row: &MIB_IPFORWARD_ROW2, names: &mut NameInterner) -> Ipv6RouteEntry {
+    // SAFETY: every row of an AF_INET6 table holds IPv6 socket addresses
+    let (dest, next_hop) = unsafe {
+        (
+            row.DestinationPrefix.Prefix.Ipv6.sin6_addr.u.Byte,
+            row.NextHop.Ipv6.sin6_addr.u.Byte,
+        )
+    };
+    let next_hop = Ipv6Addr::from(next_hop);
+    let mut flags = Ipv6RouteFlags::UP;
+    flags.set(Ipv6RouteFlags::GATEWAY, !next_hop.is_unspecified());
+    Ipv6RouteEntry {
+        dest: Ipv6Addr::from(dest),
+        dest_prefix: row.DestinationPrefix.PrefixLength,
+        src: Ipv6Addr::UNSPECIFIED,
+        src_prefix: 0,
+        next_hop,
+        metric: row.Metric,
+        ref_count: 0,
+        use_count: 0,
+        flags,
+        name: Some(interface_name(row, names)),
+    }
+}
+
+/// The interface name such as `ethernet_32768`, which unlike the alias never contains spaces.
+///
+/// Falls back to the interface index for interfaces that disappeared since the table was read,
+/// and for names longer than [`IFNAME_MAX_LEN`](crate::IFNAME_MAX_LEN).
+fn interface_name(row: &MIB_IPFORWARD_ROW2, names: &mut NameInterner) -> IfName {
+    let mut buf = [0u16; IF_MAX_STRING_SIZE as usize + 1];
+    // SAFETY: the length is given in characters, as the API expects
+    let status =
+        unsafe { ConvertInterfaceLuidToNameW(&row.InterfaceLuid, buf.as_mut_ptr(), buf.len()) };
+    if status != NO_ERROR {
+        return names.intern(&row.InterfaceIndex.to_string());
+    }
+    let len = buf.iter().position(|unit| *unit == 0).unwrap_or(buf.len());
+    let name = String::from_utf16_lossy(&buf[..len]);
+    if IfName::validate(&name).is_err() {
+        return names.intern(&row.InterfaceIndex.to_string());
+    }
+    names.intern(&name)
+}
+
+/// A `MIB_IPFORWARD_TABLE2` allocated by `GetIpForwardTable2`, freed on drop
+struct ForwardTable(*mut MIB_IPFORWARD_TABLE2);
+
+impl ForwardTable {
+    fn get(family: ADDRESS_FAMILY) -> io::Result<Self> {
+        let mut table = ptr::null_mut();
+        // SAFETY: on success the table is ours until it is handed to FreeMibTable
+        match unsafe { GetIpForwardTable2(family, &mut table) } {
+            NO_ERROR => Ok(Self(table)),
+            code => Err(io::Error::from_raw_os_error(code as i32)),
+        }
+    }
+
+    fn rows(&self) -> &[MIB_IPFORWARD_ROW2] {
+        // SAFETY: NumEntries rows follow the header within the same allocation
+        unsafe {
+            let table = &*self.0;
+            slice::from_raw_parts(table.Table.as_ptr(), table.NumEntries as usize)
+        }
+    }
+}
+
+impl Drop for ForwardTable {
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from GetIpForwardTable2 and is freed exactly once
+        unsafe { FreeMibTable(self.0.cast()) }
+    }
+}
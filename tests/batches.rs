@@ -0,0 +1,34 @@
+use proc_route_parser::{Ipv4RouteEntry, Ipv4RouteTable, RouteIteratorExt, RouteTableOptions};
+use std::io::Cursor;
+
+fn table(routes: u32) -> Ipv4RouteTable<Cursor<String>> {
+    let mut text = format!("{}\n", Ipv4RouteEntry::PROC_HEADER);
+    for metric in 0..routes {
+        let entry = Ipv4RouteEntry::builder("eth0")
+            .metric(metric)
+            .build()
+            .unwrap();
+        text += &entry.to_proc_line();
+        text.push('\n');
+    }
+    Ipv4RouteTable::from_reader(Cursor::new(text), RouteTableOptions::new())
+}
+
+#[test]
+fn batches_cover_the_table_in_order() {
+    let sizes: Vec<usize> = table(5)
+        .read_in_batches(2)
+        .map(|batch| batch.len())
+        .collect();
+    assert_eq!(sizes, [2, 2, 1]);
+}
+
+#[test]
+fn cancelling_stops_before_the_next_batch() {
+    let mut batches = table(10).read_in_batches(3);
+    let token = batches.token();
+    let first = batches.next().unwrap();
+    assert_eq!(first[2].as_ref().unwrap().metric, 2);
+    token.cancel();
+    assert!(batches.next().is_none());
+}
@@ -0,0 +1,30 @@
+use proc_route_parser::{Ipv4RouteTable, Ipv6RouteTable};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn compressed_captures_match_plain_ones() {
+    let plain: Vec<_> = Ipv4RouteTable::open(format!("{FIXTURES}/route.linux-6.x"))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let compressed: Vec<_> =
+        Ipv4RouteTable::open_compressed(format!("{FIXTURES}/route.linux-6.x.gz"))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+    assert!(!plain.is_empty());
+    assert_eq!(plain, compressed);
+
+    let plain: Vec<_> = Ipv6RouteTable::open(format!("{FIXTURES}/ipv6_route.linux-6.x"))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let compressed: Vec<_> =
+        Ipv6RouteTable::open_compressed(format!("{FIXTURES}/ipv6_route.linux-6.x.gz"))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+    assert!(!plain.is_empty());
+    assert_eq!(plain, compressed);
+}
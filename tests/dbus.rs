@@ -0,0 +1,64 @@
+use proc_route_parser::{Ipv4RouteEntry, RouteChange, RouteEntry, RouteEvent, RouteMonitorService};
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    time::SystemTime,
+};
+use zbus::{
+    MatchRule,
+    blocking::{MessageIterator, connection::Builder},
+};
+
+#[test]
+fn route_changes_are_broadcast_as_signals() {
+    // a private bus, so the test needs neither a session nor the system bus
+    let Ok(mut daemon) = Command::new("dbus-daemon")
+        .args(["--session", "--nofork", "--print-address"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        eprintln!("dbus-daemon is not installed, skipping");
+        return;
+    };
+    let mut address = String::new();
+    BufReader::new(daemon.stdout.take().unwrap())
+        .read_line(&mut address)
+        .unwrap();
+    let connect = || Builder::address(address.trim()).unwrap().build().unwrap();
+
+    let listener = connect();
+    let rule = MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .interface(RouteMonitorService::NAME)
+        .unwrap()
+        .member(RouteMonitorService::SIGNAL)
+        .unwrap()
+        .build();
+    let mut signals = MessageIterator::for_match_rule(rule, &listener, None).unwrap();
+
+    let service = RouteMonitorService::with_connection(connect());
+    let default =
+        Ipv4RouteEntry::default_via("192.0.2.1".parse().unwrap(), "eth0".parse().unwrap());
+    service
+        .emit(&RouteEvent {
+            captured_at: SystemTime::now(),
+            change: RouteChange::Added(RouteEntry::V4(default)),
+        })
+        .unwrap();
+
+    let signal = signals.next().unwrap().unwrap();
+    let body: (String, String, String, String, String, u32) = signal.body().deserialize().unwrap();
+    daemon.kill().unwrap();
+    assert_eq!(
+        body,
+        (
+            "added".into(),
+            "ipv4".into(),
+            "0.0.0.0/0".into(),
+            "192.0.2.1".into(),
+            "eth0".into(),
+            0
+        )
+    );
+}
@@ -0,0 +1,121 @@
+use proc_route_parser::{
+    DefaultRouteLoss, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags,
+    Ipv6RoutePref, effective_default_route_v4, effective_default_route_v6, select_best_from,
+};
+
+fn ipv4_default(iface: &str, metric: u32, flags: Ipv4RouteFlags) -> Ipv4RouteEntry {
+    Ipv4RouteEntry::builder(iface)
+        .gateway("192.0.2.1".parse().unwrap())
+        .flags(flags | Ipv4RouteFlags::GATEWAY)
+        .metric(metric)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn lowest_metric_up_route_wins() {
+    let up = Ipv4RouteFlags::UP;
+    let entries = [
+        ipv4_default("wlan0", 600, up),
+        ipv4_default("tun0", 50, Ipv4RouteFlags::empty()),
+        ipv4_default("eth0", 100, up),
+        ipv4_default("eth1", 100, up),
+        Ipv4RouteEntry::builder("eth0")
+            .dest("192.0.2.0".parse().unwrap())
+            .prefix_len(24)
+            .flags(up)
+            .build()
+            .unwrap(),
+    ];
+    let selection = effective_default_route_v4(entries);
+    assert_eq!(&*selection.winner.unwrap().name, "eth0");
+    let losers: Vec<_> = selection
+        .losers
+        .iter()
+        .map(|loser| (&*loser.route.name, loser.reason))
+        .collect();
+    assert_eq!(
+        losers,
+        [
+            ("wlan0", DefaultRouteLoss::HigherMetric),
+            ("tun0", DefaultRouteLoss::Down),
+            ("eth1", DefaultRouteLoss::ListedLater),
+        ]
+    );
+}
+
+#[test]
+fn router_preference_breaks_metric_ties() {
+    let default = |iface: &str, preference| {
+        Ipv6RouteEntry::builder(iface)
+            .next_hop("fe80::1".parse().unwrap())
+            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+            .metric(1024)
+            .preference(preference)
+            .build()
+            .unwrap()
+    };
+    let selection = effective_default_route_v6([
+        default("eth0", Ipv6RoutePref::Medium),
+        default("wlan0", Ipv6RoutePref::High),
+    ]);
+    assert_eq!(selection.winner.unwrap().name.as_deref(), Some("wlan0"));
+    assert_eq!(
+        selection.losers[0].reason,
+        DefaultRouteLoss::LowerPreference
+    );
+}
+
+#[test]
+fn no_winner_without_an_up_default_route() {
+    let selection = effective_default_route_v4([ipv4_default("eth0", 0, Ipv4RouteFlags::empty())]);
+    assert!(selection.winner.is_none());
+    assert_eq!(selection.losers[0].reason, DefaultRouteLoss::Down);
+}
+
+#[test]
+fn canonical_entries_match_the_kernel() {
+    let eth0 = "eth0".parse().unwrap();
+    let default = Ipv4RouteEntry::default_via("192.0.2.1".parse().unwrap(), eth0);
+    let line = "eth0\t00000000\t010200C0\t0003\t0\t0\t0\t00000000\t0\t0\t0";
+    assert_eq!(line.parse::<Ipv4RouteEntry>().unwrap(), default);
+
+    let eth0 = "eth0".parse().unwrap();
+    let link_local = Ipv6RouteEntry::link_local_route(eth0);
+    let line = "fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000000 00000000 00000001     eth0";
+    assert_eq!(link_local.to_proc_line(), line);
+
+    let eth0 = "eth0".parse().unwrap();
+    let default = Ipv6RouteEntry::default_via("fe80::1".parse().unwrap(), eth0);
+    assert_eq!(default.metric, Ipv6RouteEntry::USER_METRIC);
+    assert!(default.flags.contains(Ipv6RouteFlags::GATEWAY));
+}
+
+#[test]
+fn source_specific_routes_need_a_matching_source() {
+    let default = |iface: &str, src: &str, src_prefix, metric| {
+        Ipv6RouteEntry::builder(iface)
+            .src(src.parse().unwrap())
+            .src_prefix(src_prefix)
+            .next_hop("fe80::1".parse().unwrap())
+            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+            .metric(metric)
+            .build()
+            .unwrap()
+    };
+    let routes = [
+        default("eth0", "::", 0, 1024),
+        // a second uplink for the addresses it delegated, as `ip -6 route add default from`
+        default("wwan0", "2001:db8:100::", 48, 2048),
+    ];
+    let dest = "2606:4700::1111".parse().unwrap();
+    let via = |source: Option<&str>| {
+        select_best_from(routes.clone(), dest, source.map(|s| s.parse().unwrap()))
+            .and_then(|route| route.name.as_deref().map(String::from))
+    };
+    assert_eq!(via(None).as_deref(), Some("eth0"));
+    assert_eq!(via(Some("2001:db8:1::7")).as_deref(), Some("eth0"));
+    // the longer source prefix wins over the lower metric
+    assert_eq!(via(Some("2001:db8:100::7")).as_deref(), Some("wwan0"));
+    assert_eq!(select_best_from(routes[1..].to_vec(), dest, None), None);
+}
@@ -0,0 +1,80 @@
+use proc_route_parser::{
+    AddressFamily, IpNet, Ipv4RouteEntry, Ipv4RouteFlags, RouteExpectation, Snapshot,
+};
+use std::{net::Ipv4Addr, time::SystemTime};
+
+fn snapshot() -> Snapshot {
+    let up_gateway = Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY;
+    Snapshot {
+        captured_at: SystemTime::UNIX_EPOCH,
+        ipv4: vec![
+            Ipv4RouteEntry::builder("wg0")
+                .gateway(Ipv4Addr::new(10, 8, 0, 1))
+                .flags(up_gateway)
+                .build()
+                .unwrap(),
+            Ipv4RouteEntry::builder("eth0")
+                .gateway(Ipv4Addr::new(192, 0, 2, 1))
+                .flags(up_gateway)
+                .metric(100)
+                .build()
+                .unwrap(),
+            Ipv4RouteEntry::builder("eth0")
+                .dest(Ipv4Addr::new(169, 254, 0, 0))
+                .prefix_len(16)
+                .build()
+                .unwrap(),
+        ],
+        ipv6: Vec::new(),
+        arp: Vec::new(),
+    }
+}
+
+fn network(addr: [u8; 4], prefix_len: u8) -> IpNet {
+    IpNet::new(Ipv4Addr::from(addr).into(), prefix_len).unwrap()
+}
+
+#[test]
+fn reports_each_check_in_order() {
+    let report = RouteExpectation::default_via_iface("wg0")
+        .and(RouteExpectation::no_route_to(network([169, 254, 0, 0], 16)))
+        .and(RouteExpectation::reaches(Ipv4Addr::new(8, 8, 8, 8).into()))
+        .evaluate(&snapshot());
+    assert!(!report.passed());
+    let passed: Vec<_> = report
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.passed)
+        .collect();
+    assert_eq!(passed, [true, false, true]);
+    let failure = report.failures().next().unwrap();
+    assert_eq!(
+        failure.to_string(),
+        "no route to 169.254.0.0/16: 169.254.0.0/16 dev eth0"
+    );
+}
+
+#[test]
+fn default_via_reports_the_default_in_use() {
+    let report = RouteExpectation::default_via_iface("eth0").evaluate(&snapshot());
+    assert!(!report.passed());
+    assert_eq!(
+        report.outcomes[0].to_string(),
+        "default route via eth0: 0.0.0.0/0 via 10.8.0.1 dev wg0"
+    );
+}
+
+#[test]
+fn family_limits_the_table_checks() {
+    let report = RouteExpectation::has_default_route()
+        .family(AddressFamily::Ipv6)
+        .and(RouteExpectation::has_default_route())
+        .evaluate(&snapshot());
+    let passed: Vec<_> = report
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.passed)
+        .collect();
+    assert_eq!(passed, [false, true]);
+    assert_eq!(report.outcomes[0].to_string(), "IPv6 default route");
+}
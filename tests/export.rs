@@ -0,0 +1,56 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, export, export::to_csv,
+};
+
+#[test]
+fn csv_quotes_interface_names() {
+    let entries = [Ipv6RouteEntry::builder("we,ird\"0")
+        .dest("2001:db8::".parse().unwrap())
+        .dest_prefix(32)
+        .next_hop("fe80::1".parse().unwrap())
+        .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+        .metric(1024)
+        .build()
+        .unwrap()];
+    let mut csv = Vec::new();
+    to_csv(&entries, &mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "iface,destination,prefix_len,source,source_prefix_len,next_hop,flags,metric,ref_count,use_count\r\n\
+         \"we,ird\"\"0\",2001:db8::,32,::,0,fe80::1,UP | GATEWAY,1024,0,0\r\n"
+    );
+}
+
+#[test]
+fn dot_groups_routes_per_next_hop() {
+    let route = |dest: &str, prefix_len, gateway: Option<&str>| {
+        let builder = Ipv4RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .prefix_len(prefix_len);
+        match gateway {
+            Some(gateway) => builder
+                .gateway(gateway.parse().unwrap())
+                .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+    let entries = [
+        route("0.0.0.0", 0, Some("192.0.2.1")),
+        route("192.0.2.0", 24, None),
+        route("198.51.100.0", 24, Some("192.0.2.1")),
+    ];
+    assert_eq!(
+        export::dot(&entries),
+        r#"digraph routes {
+    rankdir=LR;
+    "host" [shape=box];
+    "iface:eth0" [label="eth0"];
+    "gw:eth0:192.0.2.1" [label="192.0.2.1", shape=diamond];
+    "host" -> "iface:eth0" [label="192.0.2.0/24 metric 0"];
+    "iface:eth0" -> "gw:eth0:192.0.2.1" [label="0.0.0.0/0 metric 0\n198.51.100.0/24 metric 0"];
+}
+"#
+    );
+}
@@ -0,0 +1,75 @@
+use proc_route_parser::{ProcRoot, RouteMetricsCollector};
+use prometheus::{Encoder, TextEncoder, core::Collector};
+use std::{fs, path::Path};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// Samples of one scrape in the text format, without the `# HELP`/`# TYPE` lines
+fn scrape(collector: &RouteMetricsCollector) -> Vec<String> {
+    let mut text = Vec::new();
+    TextEncoder::new()
+        .encode(&collector.collect(), &mut text)
+        .unwrap();
+    let mut samples: Vec<_> = String::from_utf8(text)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect();
+    samples.sort();
+    samples
+}
+
+fn install(root: &Path, fixture: &str, table: &str) {
+    fs::copy(
+        format!("{FIXTURES}/{fixture}"),
+        root.join("net").join(table),
+    )
+    .unwrap();
+}
+
+#[test]
+fn collector_counts_routes_below_its_root() {
+    let root = std::env::temp_dir().join(format!("exporter_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    fs::create_dir_all(root.join("sys/net/ipv6")).unwrap();
+    install(&root, "route.linux-6.x", "route");
+    install(&root, "ipv6_route.linux-6.x", "ipv6_route");
+
+    let collector = RouteMetricsCollector::with_root(ProcRoot::new(&root)).unwrap();
+    assert_eq!(
+        scrape(&collector),
+        [
+            r#"default_route_present{family="ipv4"} 1"#,
+            r#"default_route_present{family="ipv6"} 1"#,
+            r#"route_entries_total{family="ipv4",iface="eth0",table="main"} 2"#,
+            r#"route_entries_total{family="ipv6",iface="eth0",table="main"} 6"#,
+            r#"route_entries_total{family="ipv6",iface="lo",table="main"} 2"#,
+        ]
+    );
+
+    // only the unreachable ::/0 placeholder is left, and IPv4 loses its default route
+    fs::write(
+        root.join("net/route"),
+        "Iface\tDestination\tGateway\tFlags\tRefCnt\tUse\tMetric\tMask\tMTU\tWindow\tIRTT\n\
+         eth0\t000200C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n",
+    )
+    .unwrap();
+    let placeholder = fs::read_to_string(format!("{FIXTURES}/ipv6_route.linux-6.x"))
+        .unwrap()
+        .lines()
+        .last()
+        .unwrap()
+        .to_owned();
+    fs::write(root.join("net/ipv6_route"), placeholder + "\n").unwrap();
+    assert_eq!(
+        scrape(&collector),
+        [
+            r#"default_route_present{family="ipv4"} 0"#,
+            r#"default_route_present{family="ipv6"} 0"#,
+            r#"route_entries_total{family="ipv4",iface="eth0",table="main"} 1"#,
+            r#"route_entries_total{family="ipv6",iface="lo",table="main"} 1"#,
+        ]
+    );
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,120 @@
+use proc_route_parser::{
+    FilterError, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, RouteEntry,
+    RouteIteratorExt, parse_filter,
+};
+
+fn routes() -> Vec<RouteEntry> {
+    vec![
+        RouteEntry::V4(Ipv4RouteEntry::default_via(
+            "192.0.2.1".parse().unwrap(),
+            "eth0".parse().unwrap(),
+        )),
+        RouteEntry::V4(
+            Ipv4RouteEntry::builder("eth0")
+                .dest("10.1.0.0".parse().unwrap())
+                .prefix_len(16)
+                .gateway("192.0.2.2".parse().unwrap())
+                .metric(50)
+                .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+                .build()
+                .unwrap(),
+        ),
+        RouteEntry::V4(
+            Ipv4RouteEntry::builder("wg0")
+                .dest("10.2.0.0".parse().unwrap())
+                .prefix_len(16)
+                .flags(Ipv4RouteFlags::UP)
+                .build()
+                .unwrap(),
+        ),
+        RouteEntry::V6(
+            Ipv6RouteEntry::builder("eth0")
+                .dest("2001:db8::".parse().unwrap())
+                .dest_prefix(64)
+                .metric(256)
+                .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::ADDR_CONF)
+                .build()
+                .unwrap(),
+        ),
+    ]
+}
+
+fn matching(filter: &str) -> Vec<usize> {
+    let filter = parse_filter(filter).unwrap();
+    let routes = routes();
+    (0..routes.len())
+        .filter(|&i| filter.matches(&routes[i]))
+        .collect()
+}
+
+#[test]
+fn filters_combine_comparisons() {
+    assert_eq!(
+        matching("iface == eth0 && flags has GATEWAY && dest within 10.0.0.0/8"),
+        [1]
+    );
+    assert_eq!(
+        matching("dest contains 10.2.3.4 || family == ipv6"),
+        [0, 2, 3]
+    );
+    assert_eq!(matching("!(iface == eth0) || metric >= 256"), [2, 3]);
+    assert_eq!(matching("gateway == none && prefix < 64"), [2]);
+    assert_eq!(matching("gateway within 192.0.2.0/24 && metric != 0"), [1]);
+    assert_eq!(matching("flags has addr_conf"), [3]);
+
+    let filter = "dest == 0.0.0.0/0".parse().unwrap();
+    let entries: Vec<Ipv4RouteEntry> = routes()
+        .into_iter()
+        .filter_map(|route| match route {
+            RouteEntry::V4(entry) => Some(entry),
+            RouteEntry::V6(_) => None,
+        })
+        .matching(&filter)
+        .collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(filter.to_string(), "dest == 0.0.0.0/0");
+}
+
+#[test]
+fn on_link_routes_differ_from_every_gateway() {
+    assert_eq!(matching("gateway != 192.0.2.1"), [1, 2, 3]);
+    assert_eq!(matching("gateway == 192.0.2.1"), [0]);
+    // no gateway is within or contains anything
+    assert_eq!(matching("gateway within 0.0.0.0/0"), [0, 1]);
+    assert_eq!(matching("!(gateway within 0.0.0.0/0)"), [2, 3]);
+}
+
+#[test]
+fn malformed_filters_are_rejected() {
+    assert_eq!(
+        parse_filter("iface = eth0").unwrap_err(),
+        FilterError::UnexpectedToken {
+            token: "=".into(),
+            position: 6,
+            expected: "an operator such as && or ==",
+        }
+    );
+    assert_eq!(
+        parse_filter("flags has BOGUS").unwrap_err(),
+        FilterError::UnknownFlag("BOGUS".into())
+    );
+    assert_eq!(
+        parse_filter("metric within 10.0.0.0/8").unwrap_err(),
+        FilterError::UnsupportedOperator {
+            field: "metric",
+            op: "within".into(),
+        }
+    );
+    assert!(matches!(
+        parse_filter("iface == eth0 &&"),
+        Err(FilterError::UnexpectedEnd { .. })
+    ));
+    assert!(matches!(
+        parse_filter("(iface == eth0"),
+        Err(FilterError::UnexpectedEnd { expected: ")" })
+    ));
+    assert!(matches!(
+        parse_filter("table == main"),
+        Err(FilterError::UnknownField(_))
+    ));
+}
@@ -0,0 +1,2 @@
+Group                            Origin                           Iif      Pkts  Bytes     Wrong  Oifs
+ff0e:0000:0000:0000:0000:0000:0001:0003 fd00:0000:0000:0000:0000:0000:0000:0005 0          0        0        0  1:1  
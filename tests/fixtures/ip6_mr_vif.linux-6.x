@@ -0,0 +1,3 @@
+Interface      BytesIn  PktsIn  BytesOut PktsOut Flags
+ 0 eth0              0       0         0       0 00000
+ 1 lo                0       0         0       0 00000
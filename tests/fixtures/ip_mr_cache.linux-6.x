@@ -0,0 +1,5 @@
+Group    Origin   Iif     Pkts    Bytes    Wrong Oifs
+030201EF 0500000A 0          0        0        0  1:1  
+090000E8 1407A8C0 0          0        0        0  1:2  
+090909EF 020200C0 -1         0        0        0
+030201EF 020200C0 -1         0        0        0
@@ -0,0 +1,3 @@
+Interface      BytesIn  PktsIn  BytesOut PktsOut Flags Local    Remote
+ 0 eth0              0       0         0       0 00008 00000004 00000000
+ 1 lo                0       0         0       0 00008 00000001 00000000
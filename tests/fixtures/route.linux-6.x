@@ -0,0 +1,3 @@
+Iface	Destination	Gateway 	Flags	RefCnt	Use	Metric	Mask		MTU	Window	IRTT                                                       
+eth0	00000000	010200C0	0003	0	0	0	00000000	0	0	0                                                                               
+eth0	000200C0	00000000	0001	0	0	0	00FFFFFF	0	0	0                                                                               
@@ -0,0 +1,6 @@
+Iface	Destination	Gateway 	Flags	RefCnt	Use	Metric	Mask		MTU	Window	IRTT                                                       
+pppoe-wan	00000000	01004064	0003	0	0	0	00000000	0	0	0                                                                          
+pppoe-wan	01004064	00000000	0005	0	0	0	FFFFFFFF	0	0	0                                                                          
+br-lan	0001A8C0	00000000	0001	0	0	0	00FFFFFF	0	0	0                                                                             
+br-guest	0003A8C0	00000000	0001	0	0	0	00FFFFFF	0	0	0                                                                           
+wg-site-frankfurt	0000140A	00000000	0001	0	0	0	0000FFFF	0	0	0                                                                  
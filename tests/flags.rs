@@ -0,0 +1,145 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteEntryRef, Ipv4RouteFlags, Ipv4RouteTable, Ipv6RouteFlags,
+    Ipv6RouteTable, Metric, Route, RouteParseError, RouteTableOptions, UnknownFlagBits,
+};
+use std::{io::Cursor, num::NonZeroU32, time::Duration};
+
+#[test]
+fn shared_flags_match_between_families() {
+    for (name, flag) in Ipv4RouteFlags::all().iter_names() {
+        let ipv6 = Ipv6RouteFlags::from_name(name).unwrap();
+        assert_eq!(Ipv6RouteFlags::from(flag), ipv6, "{name}");
+    }
+}
+
+#[test]
+fn ipv6_only_flags_follow_the_kernel_header() {
+    // values from include/uapi/linux/ipv6_route.h
+    for (flag, bits) in [
+        (Ipv6RouteFlags::DEFAULT, 0x00010000),
+        (Ipv6RouteFlags::ADDR_CONF, 0x00040000),
+        (Ipv6RouteFlags::PREFIX_ONLY, 0x00080000),
+        (Ipv6RouteFlags::ANYCAST, 0x00100000),
+        (Ipv6RouteFlags::NO_NEXT_HOP, 0x00200000),
+        (Ipv6RouteFlags::EXPIRES, 0x00400000),
+        (Ipv6RouteFlags::ROUTE_INFO, 0x00800000),
+        (Ipv6RouteFlags::CACHE, 0x01000000),
+        (Ipv6RouteFlags::PER_CPU, 0x40000000),
+        (Ipv6RouteFlags::LOCAL, 0x80000000),
+    ] {
+        assert_eq!(flag.bits(), bits);
+    }
+}
+
+#[test]
+fn conversion_keeps_unknown_bits() {
+    let flags = Ipv4RouteFlags::from_bits_retain(0x8001);
+    assert_eq!(Ipv6RouteFlags::from(flags).bits(), 0x8001);
+}
+
+#[test]
+fn unknown_bits_follow_the_table_options() {
+    let route = format!(
+        "{}\neth0\t00000000\t00000000\t8001\t0\t0\t0\t00000000\t0\t0\t0\n",
+        Ipv4RouteEntry::PROC_HEADER
+    );
+    let read = |handling| {
+        let options = RouteTableOptions::new().unknown_flag_bits(handling);
+        Ipv4RouteTable::from_reader(Cursor::new(&route), options)
+            .next()
+            .unwrap()
+    };
+    let retained = read(UnknownFlagBits::Retain).unwrap();
+    assert_eq!(retained.flags.unknown_bits(), 0x8000);
+    assert_eq!(retained.flags_raw(), 0x8001);
+    assert_eq!(retained.unknown_flag_bits(), 0x8000);
+    assert_eq!(
+        read(UnknownFlagBits::Drop).unwrap().flags,
+        Ipv4RouteFlags::UP
+    );
+    assert!(matches!(
+        read(UnknownFlagBits::Reject).unwrap_err().source,
+        RouteParseError::UnknownFlagBits(0x8000)
+    ));
+
+    // the router preference is modelled, so it never counts as unknown
+    assert_eq!(Ipv6RouteFlags::from(0x0800_0001).unknown_bits(), 0);
+    assert_eq!(Ipv6RouteFlags::from(0x0000_8001).unknown_bits(), 0x8000);
+}
+
+#[test]
+fn display_lists_names_and_unknown_bits() {
+    let flags = Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY | Ipv4RouteFlags::from(0x8000);
+    assert_eq!(flags.to_string(), "UP | GATEWAY | 0x8000");
+    assert_eq!(Ipv6RouteFlags::from(0x0).to_string(), "");
+}
+
+#[test]
+fn presets_drop_local_entries() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/ipv6_route.linux-6.x"
+    );
+    let entries: Vec<_> = Ipv6RouteTable::open(path)
+        .unwrap()
+        .without_local()
+        .without_cache_entries()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(entries.len(), 5);
+    assert!(
+        entries
+            .iter()
+            .all(|entry| !entry.flags.contains(Ipv6RouteFlags::LOCAL))
+    );
+}
+
+#[test]
+fn mtu_window_and_irtt_keep_their_printed_values() {
+    let line = "eth0\t00000000\t0102000A\t0043\t0\t0\t100\t00000000\t1500\t64\t3";
+    let entry: Ipv4RouteEntry = line.parse().unwrap();
+    assert_eq!(entry.mtu, Some(1500));
+    assert_eq!(entry.window, Some(64));
+    assert_eq!(entry.irtt, Some(3));
+
+    // 0 without the flag is "unset", 0 with it is a value
+    let line = "eth0\t00000000\t0102000A\t0043\t0\t0\t100\t00000000\t0\t0\t0";
+    let entry: Ipv4RouteEntry = line.parse().unwrap();
+    assert_eq!((entry.mtu, entry.window, entry.irtt), (Some(0), None, None));
+
+    let raw = Ipv4RouteEntryRef::parse(line).unwrap();
+    assert_eq!((raw.mtu, raw.window, raw.irtt), (0, 0, 0));
+}
+
+#[test]
+fn mtu_without_its_flag_round_trips() {
+    // the kernel prints the MTU of `ip route add ... mtu 1400` without setting RTF_MTU
+    let line = "eth0\t0000000A\t0102000A\t0003\t0\t0\t0\t000000FF\t1400\t0\t0";
+    let entry: Ipv4RouteEntry = line.parse().unwrap();
+    assert!(!entry.flags.contains(Ipv4RouteFlags::MTU));
+    assert_eq!(entry.mtu_raw(), 1400);
+    assert_eq!(entry.path_mtu(), NonZeroU32::new(1400));
+
+    let printed = entry.to_proc_line();
+    assert_eq!(printed.trim_end(), line);
+    assert_eq!(printed.parse::<Ipv4RouteEntry>().unwrap(), entry);
+}
+
+#[test]
+fn accessors_carry_units() {
+    let entry = Ipv4RouteEntry::builder("eth0")
+        .mtu(0)
+        .irtt(300)
+        .metric(100)
+        .build()
+        .unwrap();
+    assert_eq!(entry.path_mtu(), None);
+    assert_eq!(entry.initial_rtt(), Some(Duration::from_millis(300)));
+    assert_eq!(entry.metric(), Metric(100));
+    assert!(entry.metric().is_preferred_over(Metric(600)));
+
+    let line = "eth0\t00000000\t0102000A\t0043\t0\t0\t100\t00000000\t1500\t64\t3";
+    let entry: Ipv4RouteEntry = line.parse().unwrap();
+    assert_eq!(entry.path_mtu(), NonZeroU32::new(1500));
+    assert_eq!(entry.initial_rtt(), Some(Duration::from_millis(3)));
+}
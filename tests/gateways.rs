@@ -0,0 +1,306 @@
+use proc_route_parser::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, RouteConflict,
+    RouteEntry, find_conflicts, gateways, summarize, summarize_table, top_routes_by_refcnt,
+    top_routes_by_use,
+};
+
+/// An `UP` route out of `iface`, on-link unless it has a gateway
+fn route(iface: &str, dest: &str, prefix_len: u8, gateway: Option<&str>) -> Ipv4RouteEntry {
+    let mut builder = Ipv4RouteEntry::builder(iface)
+        .dest(dest.parse().unwrap())
+        .prefix_len(prefix_len);
+    if let Some(gateway) = gateway {
+        builder = builder
+            .gateway(gateway.parse().unwrap())
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY);
+    }
+    builder.build().unwrap()
+}
+
+#[test]
+fn gateways_are_deduplicated_and_ranked() {
+    let via = |dest: &str, gateway: &str, metric| {
+        Ipv4RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .prefix_len(24)
+            .gateway(gateway.parse().unwrap())
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+            .metric(metric)
+            .build()
+            .unwrap()
+            .into()
+    };
+    let entries: [RouteEntry; 4] = [
+        via("198.51.100.0", "192.0.2.2", 100),
+        via("203.0.113.0", "192.0.2.1", 100),
+        via("198.51.100.0", "192.0.2.1", 600),
+        Ipv6RouteEntry::builder("eth0")
+            .next_hop("fe80::1".parse().unwrap())
+            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+            .metric(1024)
+            .build()
+            .unwrap()
+            .into(),
+    ];
+    let ranked: Vec<(String, usize, u32)> = gateways(entries)
+        .into_iter()
+        .map(|info| {
+            (
+                info.addr.to_string(),
+                info.route_count,
+                info.lowest_metric.get(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ranked,
+        [
+            ("192.0.2.1".to_string(), 2, 100),
+            ("192.0.2.2".to_string(), 1, 100),
+            ("fe80::1".to_string(), 1, 1024),
+        ]
+    );
+}
+
+#[test]
+fn table_summary_counts_flags_interfaces_and_prefixes() {
+    let entries: [RouteEntry; 4] = [
+        Ipv4RouteEntry::default_via("192.0.2.1".parse().unwrap(), "eth0".parse().unwrap()).into(),
+        Ipv4RouteEntry::builder("eth0")
+            .dest("192.0.2.0".parse().unwrap())
+            .prefix_len(24)
+            .flags(Ipv4RouteFlags::UP)
+            .build()
+            .unwrap()
+            .into(),
+        Ipv4RouteEntry::builder("lo")
+            .dest("203.0.113.7".parse().unwrap())
+            .prefix_len(32)
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::HOST | Ipv4RouteFlags::REJECT)
+            .build()
+            .unwrap()
+            .into(),
+        Ipv6RouteEntry::builder("eth0")
+            .dest("2001:db8::".parse().unwrap())
+            .dest_prefix(64)
+            .flags(Ipv6RouteFlags::empty())
+            .build()
+            .unwrap()
+            .into(),
+    ];
+    let summary = summarize_table(entries);
+    assert_eq!(summary.total, 4);
+    assert_eq!(summary.up, 3);
+    assert_eq!((summary.gateway, summary.host, summary.reject), (1, 1, 1));
+    assert_eq!(summary.by_interface["eth0"], 3);
+    assert_eq!(
+        summary.by_prefix_len.into_iter().collect::<Vec<_>>(),
+        [(0, 1), (24, 1), (32, 1), (64, 1)]
+    );
+    assert!(summary.ipv4_default);
+    assert!(!summary.ipv6_default);
+}
+
+#[test]
+fn top_routes_rank_by_counters() {
+    let route = |dest: &str, use_count, ref_count| {
+        Ipv6RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .dest_prefix(64)
+            .use_count(use_count)
+            .ref_count(ref_count)
+            .build()
+            .unwrap()
+    };
+    let entries = [
+        route("2001:db8:1::", 5, 1),
+        route("2001:db8:2::", 40, 0),
+        route("2001:db8:3::", 5, 7),
+    ];
+    let dests = |routes: Vec<Ipv6RouteEntry>| -> Vec<String> {
+        routes.iter().map(|route| route.dest.to_string()).collect()
+    };
+    assert_eq!(
+        dests(top_routes_by_use(entries.clone(), 2)),
+        ["2001:db8:2::", "2001:db8:1::"]
+    );
+    assert_eq!(
+        dests(top_routes_by_refcnt(entries, 5)),
+        ["2001:db8:3::", "2001:db8:1::", "2001:db8:2::"]
+    );
+}
+
+#[test]
+fn duplicate_prefixes_through_other_hops_conflict() {
+    let with_metric = |mut entry: Ipv4RouteEntry, metric| {
+        entry.metric = metric;
+        entry
+    };
+    let routes = [
+        with_metric(route("eth0", "10.0.0.0", 8, Some("192.0.2.1")), 100),
+        with_metric(route("eth0", "10.0.0.0", 8, Some("192.0.2.2")), 100),
+        with_metric(route("eth0", "10.0.0.0", 8, Some("192.0.2.3")), 200),
+        route("eth0", "192.0.2.0", 24, None),
+        // the same hop as the route before, only with another metric
+        with_metric(route("eth0", "192.0.2.0", 24, None), 50),
+    ];
+    assert_eq!(
+        find_conflicts(&routes),
+        [
+            RouteConflict::DuplicatePrefix {
+                first: 0,
+                second: 1,
+                same_metric: true,
+            },
+            RouteConflict::DuplicatePrefix {
+                first: 0,
+                second: 2,
+                same_metric: false,
+            },
+            RouteConflict::DuplicatePrefix {
+                first: 1,
+                second: 2,
+                same_metric: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn ipv6_routes_of_equal_metric_are_multipath_not_conflicts() {
+    let via = |gateway: &str, metric, src: Option<&str>| {
+        let mut builder = Ipv6RouteEntry::builder("eth0")
+            .dest("2001:db8::".parse().unwrap())
+            .dest_prefix(32)
+            .next_hop(gateway.parse().unwrap())
+            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+            .metric(metric);
+        if let Some(src) = src {
+            builder = builder.src(src.parse().unwrap()).src_prefix(48);
+        }
+        builder.build().unwrap()
+    };
+    let on_link = Ipv6RouteEntry::builder("eth0")
+        .dest("fe80::".parse().unwrap())
+        .dest_prefix(64)
+        .flags(Ipv6RouteFlags::UP)
+        .metric(256)
+        .build()
+        .unwrap();
+    // ECMP siblings the kernel spreads flows over
+    let routes = [
+        on_link.clone(),
+        via("fe80::1", 1024, None),
+        via("fe80::2", 1024, None),
+    ];
+    assert_eq!(find_conflicts(&routes), []);
+
+    // a fallback through another router
+    let routes = [
+        on_link.clone(),
+        via("fe80::1", 1024, None),
+        via("fe80::2", 2048, None),
+    ];
+    assert_eq!(
+        find_conflicts(&routes),
+        [RouteConflict::DuplicatePrefix {
+            first: 1,
+            second: 2,
+            same_metric: false,
+        }]
+    );
+
+    // source-specific routes apply to different sources
+    let routes = [
+        on_link,
+        via("fe80::1", 1024, None),
+        via("fe80::2", 2048, Some("2001:db8:1::")),
+    ];
+    assert_eq!(find_conflicts(&routes), []);
+}
+
+#[test]
+fn default_route_is_shadowed_by_a_pair_of_halves() {
+    // the 0.0.0.0/1 and 128.0.0.0/1 pair a VPN client installs to override the default route
+    let routes = [
+        route("eth0", "192.0.2.0", 24, None),
+        route("eth0", "0.0.0.0", 0, Some("192.0.2.1")),
+        route("tun0", "10.8.0.0", 24, None),
+        route("tun0", "0.0.0.0", 1, Some("10.8.0.1")),
+        route("tun0", "128.0.0.0", 1, Some("10.8.0.1")),
+    ];
+    assert_eq!(
+        find_conflicts(&routes),
+        [RouteConflict::Shadowed {
+            route: 1,
+            by: vec![0, 2, 3, 4],
+        }]
+    );
+
+    // one half alone leaves the default route in use
+    assert!(find_conflicts(&routes[..4]).is_empty());
+}
+
+#[test]
+fn gateway_needs_an_on_link_route_on_its_own_interface() {
+    let mut down = route("eth0", "198.51.100.0", 24, Some("203.0.113.1"));
+    down.flags.remove(Ipv4RouteFlags::UP);
+    let routes = [
+        route("eth0", "192.0.2.0", 24, None),
+        route("eth0", "172.16.0.0", 12, Some("192.0.2.1")),
+        // 192.0.2.1 is only on-link through eth0
+        route("eth1", "10.0.0.0", 8, Some("192.0.2.1")),
+        down,
+    ];
+    assert_eq!(
+        find_conflicts(&routes),
+        [RouteConflict::UnreachableGateway {
+            route: 2,
+            gateway: "192.0.2.1".parse().unwrap(),
+        }]
+    );
+}
+
+fn net(addr: &str, prefix_len: u8) -> IpNet {
+    IpNet::new(addr.parse().unwrap(), prefix_len).unwrap()
+}
+
+#[test]
+fn summarize_absorbs_nested_prefixes_and_joins_chained_siblings() {
+    let nested = [
+        route("eth0", "10.1.0.0", 16, None),
+        route("eth0", "10.0.0.0", 8, None),
+        route("eth0", "10.1.2.0", 24, None),
+    ];
+    assert_eq!(summarize(nested), [net("10.0.0.0", 8)]);
+
+    // the /25 halves join into a /24, which then joins its sibling
+    let chained = [
+        route("eth0", "10.0.1.0", 24, None),
+        route("eth0", "10.0.0.128", 25, None),
+        route("eth0", "10.0.0.0", 25, None),
+    ];
+    assert_eq!(summarize(chained), [net("10.0.0.0", 23)]);
+
+    assert_eq!(summarize(Vec::<RouteEntry>::new()), []);
+}
+
+#[test]
+fn summarize_keeps_families_apart() {
+    let v6 = |dest: &str| -> RouteEntry {
+        Ipv6RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .dest_prefix(1)
+            .flags(Ipv6RouteFlags::UP)
+            .build()
+            .unwrap()
+            .into()
+    };
+    let entries = [
+        v6("8000::"),
+        route("eth0", "128.0.0.0", 1, None).into(),
+        v6("::"),
+        route("eth0", "0.0.0.0", 1, None).into(),
+    ];
+    assert_eq!(summarize(entries), [net("0.0.0.0", 0), net("::", 0)]);
+}
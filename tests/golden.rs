@@ -0,0 +1,77 @@
+//! Golden renderings of the captures under `tests/fixtures/samples`, one directory per system.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite the `.golden` files after an intended format change.
+
+use proc_route_parser::{Column, Ipv4RouteTable, Ipv6RouteTable, TableRenderer};
+use std::{env, fs, path::Path};
+
+const SAMPLES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/samples");
+
+const IPV4_COLUMNS: [Column; 11] = [
+    Column::Destination,
+    Column::Gateway,
+    Column::Genmask,
+    Column::Flags,
+    Column::Metric,
+    Column::Ref,
+    Column::Use,
+    Column::Iface,
+    Column::Mtu,
+    Column::Window,
+    Column::Irtt,
+];
+
+const IPV6_COLUMNS: [Column; 8] = [
+    Column::Destination,
+    Column::Source,
+    Column::Gateway,
+    Column::Flags,
+    Column::Metric,
+    Column::Ref,
+    Column::Use,
+    Column::Iface,
+];
+
+fn check_golden(path: &Path, rendered: &str) {
+    let golden = path.with_extension("golden");
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, rendered).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&golden)
+        .unwrap_or_else(|err| panic!("{}: {err}, run with UPDATE_GOLDEN=1", golden.display()));
+    assert_eq!(rendered, expected, "{} differs", golden.display());
+}
+
+#[test]
+fn samples_match_their_golden_renderings() {
+    let mut systems: Vec<_> = fs::read_dir(SAMPLES)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    systems.sort();
+    assert!(!systems.is_empty());
+
+    for system in systems {
+        let route = system.join("route");
+        let ipv4: Vec<_> = Ipv4RouteTable::open(&route)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|err| panic!("{}: {err}", route.display()));
+        check_golden(
+            &route,
+            &TableRenderer::new().columns(IPV4_COLUMNS).render(&ipv4),
+        );
+
+        let ipv6_route = system.join("ipv6_route");
+        let ipv6: Vec<_> = Ipv6RouteTable::open(&ipv6_route)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|err| panic!("{}: {err}", ipv6_route.display()));
+        check_golden(
+            &ipv6_route,
+            &TableRenderer::new().columns(IPV6_COLUMNS).render(&ipv6),
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use proc_route_parser::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Snapshot, history::RouteHistory,
+};
+use std::time::{Duration, SystemTime};
+
+fn snapshot(seconds: u64, gateway: &str, vpn_up: bool) -> Snapshot {
+    let mut ipv4 = vec![
+        Ipv4RouteEntry::builder("wlan0")
+            .gateway(gateway.parse().unwrap())
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+            .build()
+            .unwrap(),
+    ];
+    if vpn_up {
+        ipv4.push(
+            Ipv4RouteEntry::builder("tun0")
+                .dest("10.8.0.0".parse().unwrap())
+                .prefix_len(16)
+                .build()
+                .unwrap(),
+        );
+    }
+    Snapshot {
+        captured_at: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        ipv4,
+        ipv6: Vec::new(),
+        arp: Vec::new(),
+    }
+}
+
+#[test]
+fn history_reports_default_route_changes_and_flaps() {
+    let mut history = RouteHistory::new(4);
+    history.push(snapshot(0, "192.0.2.1", true));
+    history.push(snapshot(10, "192.0.2.1", false));
+    history.push(snapshot(20, "192.0.2.254", true));
+    history.push(snapshot(30, "192.0.2.254", false));
+    history.push(snapshot(40, "192.0.2.254", true));
+    // the first snapshot was evicted
+    assert_eq!(history.len(), 4);
+
+    let at = |seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+    assert_eq!(history.default_route_changes(), [at(20)]);
+
+    let vpn = IpNet::new("10.8.0.0".parse().unwrap(), 16).unwrap();
+    let report = history.flaps(vpn).unwrap();
+    assert_eq!(report.transitions, 3);
+    assert_eq!(report.first_change, at(20));
+    assert_eq!(report.last_change, at(40));
+
+    let reports = history.flap_reports(2);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].network, vpn);
+}
+
+#[test]
+fn history_reports_use_count_deltas() {
+    let capture = |seconds, use_count, metric| Snapshot {
+        captured_at: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        ipv4: Vec::new(),
+        ipv6: vec![
+            Ipv6RouteEntry::builder("eth0")
+                .dest("2001:db8::".parse().unwrap())
+                .dest_prefix(64)
+                .metric(metric)
+                .use_count(use_count)
+                .build()
+                .unwrap(),
+        ],
+        arp: Vec::new(),
+    };
+    let mut history = RouteHistory::new(4);
+    history.push(capture(0, 100, 256));
+    history.push(capture(10, 600, 256));
+    // the counter wrapped
+    history.push(capture(20, 99, 256));
+    // the route was replaced, restarting its counter
+    history.push(capture(30, 5, 1024));
+
+    let deltas = history.use_deltas();
+    assert_eq!(deltas.len(), 2);
+    assert_eq!(
+        deltas[0].network,
+        IpNet::new("2001:db8::".parse().unwrap(), 64).unwrap()
+    );
+    assert_eq!(deltas[0].interface, "eth0");
+    assert_eq!(deltas[0].delta, 500);
+    assert_eq!(deltas[0].interval, Duration::from_secs(10));
+    assert_eq!(deltas[0].rate(), Some(50.0));
+    assert_eq!(deltas[1].delta, u32::MAX - 500);
+}
@@ -0,0 +1,46 @@
+use proc_route_parser::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Route, RouteEntry, RouteIndex,
+};
+
+#[test]
+fn index_groups_both_families_per_interface() {
+    let ipv4 = [
+        Ipv4RouteEntry::builder("eth0")
+            .gateway("192.0.2.1".parse().unwrap())
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+            .build()
+            .unwrap(),
+        Ipv4RouteEntry::builder("eth0")
+            .dest("192.0.2.0".parse().unwrap())
+            .prefix_len(24)
+            .build()
+            .unwrap(),
+        Ipv4RouteEntry::builder("wg0")
+            .dest("10.8.0.0".parse().unwrap())
+            .prefix_len(16)
+            .build()
+            .unwrap(),
+    ];
+    let ipv6 = [
+        Ipv6RouteEntry::builder("wg0").build().unwrap(),
+        Ipv6RouteEntry::builder("eth0")
+            .dest("fe80::".parse().unwrap())
+            .dest_prefix(64)
+            .build()
+            .unwrap(),
+    ];
+    let index = RouteIndex::new(ipv4, ipv6);
+
+    let networks: Vec<String> = index
+        .routes("eth0")
+        .iter()
+        .map(|route| route.network().to_string())
+        .collect();
+    assert_eq!(networks, ["0.0.0.0/0", "192.0.2.0/24", "fe80::/64"]);
+    assert!(matches!(index.routes("wg0")[1], RouteEntry::V6(_)));
+    assert!(index.routes("lo").is_empty());
+
+    assert_eq!(index.interfaces_with_default_route(), ["eth0", "wg0"]);
+    let tunnel = IpNet::new("10.8.0.0".parse().unwrap(), 16).unwrap();
+    assert_eq!(index.interfaces_for(tunnel), ["wg0"]);
+}
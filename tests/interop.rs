@@ -0,0 +1,42 @@
+use proc_route_parser::{InterfaceResolver, Ipv4RouteEntry, Ipv6RouteEntry, RouteEntry};
+use std::fs;
+
+#[test]
+fn entries_round_trip_through_net_route() {
+    let root = std::env::temp_dir().join(format!("interop_sys_{}", std::process::id()));
+    for (name, index) in [("eth0", "2"), ("wg0", "7")] {
+        fs::create_dir_all(root.join(name)).unwrap();
+        fs::write(root.join(name).join("ifindex"), index).unwrap();
+        fs::write(root.join(name).join("mtu"), "1500").unwrap();
+    }
+    let mut resolver = InterfaceResolver::with_root(&root);
+
+    let default = RouteEntry::V4(Ipv4RouteEntry::default_via(
+        "192.0.2.1".parse().unwrap(),
+        "eth0".parse().unwrap(),
+    ));
+    let route = resolver.to_net_route(&default).unwrap();
+    assert_eq!(route.gateway, Some("192.0.2.1".parse().unwrap()));
+    assert_eq!(route.ifindex, Some(2));
+    assert_eq!(route.metric, Some(0));
+    assert_eq!(resolver.from_net_route(&route), Some(default));
+
+    let tunnel = RouteEntry::V6(
+        Ipv6RouteEntry::builder("wg0")
+            .dest("2001:db8:42::".parse().unwrap())
+            .dest_prefix(48)
+            .src("2001:db8:1::".parse().unwrap())
+            .src_prefix(64)
+            .metric(1024)
+            .build()
+            .unwrap(),
+    );
+    let route = net_route::Route::from(&tunnel);
+    assert_eq!(route.ifindex, None);
+    assert_eq!(route.source_prefix, 64);
+    let route = route.with_ifindex(7);
+    assert_eq!(resolver.from_net_route(&route), Some(tunnel));
+
+    assert_eq!(resolver.from_net_route(&route.with_ifindex(9)), None);
+    fs::remove_dir_all(root).unwrap();
+}
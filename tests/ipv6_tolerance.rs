@@ -0,0 +1,101 @@
+use proc_route_parser::{
+    FormatProfile, IfNameError, Ipv4RouteTable, Ipv6RouteEntryRef, Ipv6RouteFlags, Ipv6RouteTable,
+    KernelVersion, RouteParseError, RouteTableOptions,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn kernel_captures_parse_strictly() {
+    for kernel in ["4.x", "6.x"] {
+        let path = format!("{FIXTURES}/ipv6_route.linux-{kernel}");
+        for entry in Ipv6RouteTable::open(&path).unwrap() {
+            let entry = entry.unwrap();
+            assert!(entry.name.is_some());
+        }
+    }
+}
+
+#[test]
+fn quirks_need_tolerant_mode() {
+    let path = format!("{FIXTURES}/ipv6_route.quirks");
+    let strict: Vec<_> = Ipv6RouteTable::open(&path).unwrap().collect();
+    assert!(matches!(
+        strict[0].as_ref().unwrap_err().source,
+        RouteParseError::InvalidFieldCount {
+            expected: 10,
+            found: 11
+        }
+    ));
+    assert!(matches!(
+        strict[1].as_ref().unwrap_err().source,
+        RouteParseError::InvalidFieldCount {
+            expected: 10,
+            found: 9
+        }
+    ));
+    assert!(strict[2].is_ok());
+
+    let options = RouteTableOptions::new().tolerant(true);
+    let tolerant: Vec<_> = Ipv6RouteTable::open_with(&path, options)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(tolerant[0].name.as_deref(), Some("eth0"));
+    assert_eq!(tolerant[1].name, None);
+    assert!(tolerant[1].flags.contains(Ipv6RouteFlags::REJECT));
+    assert_eq!(tolerant[2].dest_prefix, 64);
+}
+
+#[test]
+fn tolerant_mode_still_rejects_truncated_lines() {
+    let line = "fd000000000000000000000000000000 40 00000000000000000000000000000000 00";
+    assert!(matches!(
+        Ipv6RouteEntryRef::parse_tolerant(line),
+        Err(RouteParseError::InvalidFieldCount {
+            expected: 10,
+            found: 4
+        })
+    ));
+}
+
+#[test]
+fn openwrt_profile_reads_router_captures() {
+    let route = format!("{FIXTURES}/route.openwrt-3.x");
+    let ipv6_route = format!("{FIXTURES}/ipv6_route.openwrt-3.x");
+    let strict: Vec<_> = Ipv4RouteTable::open(&route).unwrap().collect();
+    assert!(matches!(
+        &strict[4].as_ref().unwrap_err().source,
+        RouteParseError::InvalidIfName(IfNameError::TooLong(name))
+            if name == "wg-site-frankfurt"
+    ));
+    assert!(
+        Ipv6RouteTable::open(&ipv6_route)
+            .unwrap()
+            .any(|entry| entry.is_err())
+    );
+
+    let profile = FormatProfile::openwrt(KernelVersion::new(3, 18, 45));
+    let options = RouteTableOptions::new().format_profile(&profile);
+    let ipv4: Vec<_> = Ipv4RouteTable::open_with(&route, options.clone())
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(&*ipv4[4].name, "wg-site-frankfurt");
+    let ipv6: Vec<_> = Ipv6RouteTable::open_with(&ipv6_route, options)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let names: Vec<_> = ipv6.iter().map(|entry| entry.name.as_deref()).collect();
+    assert_eq!(
+        names,
+        [
+            Some("lo"),
+            Some("br-lan"),
+            Some("6in4-henet"),
+            Some("wg-site-frankfurt"),
+            Some("pppoe-wan"),
+            None
+        ]
+    );
+}
@@ -0,0 +1,107 @@
+use proc_route_parser::{
+    AddressFamily, Ipv4RouteTable, Ipv6RouteTable, RouteParseError, RouteTableOptions,
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    net::{Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn lenient_ipv4_table_skips_malformed_lines() {
+    let mut table = Ipv4RouteTable::open_lenient(format!("{FIXTURES}/route.malformed")).unwrap();
+    let entries: Vec<_> = table.by_ref().collect();
+    let dests: Vec<_> = entries.iter().map(|entry| entry.dest).collect();
+    assert_eq!(dests, [Ipv4Addr::UNSPECIFIED, Ipv4Addr::new(192, 0, 2, 0)]);
+
+    let report = table.report();
+    assert!(!report.is_clean());
+    let lines: Vec<_> = report
+        .malformed()
+        .iter()
+        .map(|error| error.line_number)
+        .collect();
+    assert_eq!(lines, [3, 5]);
+}
+
+#[test]
+fn lenient_ipv6_table_skips_malformed_lines() {
+    let mut table =
+        Ipv6RouteTable::open_lenient(format!("{FIXTURES}/ipv6_route.malformed")).unwrap();
+    let entries: Vec<_> = table.by_ref().collect();
+    let dests: Vec<_> = entries.iter().map(|entry| entry.dest).collect();
+    assert_eq!(
+        dests,
+        ["fd00::".parse::<Ipv6Addr>().unwrap(), Ipv6Addr::UNSPECIFIED]
+    );
+
+    let report = table.report();
+    assert_eq!(report.malformed().len(), 1);
+    assert_eq!(report.malformed()[0].line_number, 2);
+}
+
+#[test]
+fn line_errors_carry_their_context() {
+    let path = format!("{FIXTURES}/route.malformed");
+    let error = Ipv4RouteTable::open(&path)
+        .unwrap()
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(error.line_number, 3);
+    assert_eq!(
+        error.raw,
+        "eth0\t0002ZZC0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0"
+    );
+    assert!(matches!(
+        error.source,
+        RouteParseError::InvalidField {
+            column: "Destination",
+            ..
+        }
+    ));
+    assert_eq!(error.family, Some(AddressFamily::Ipv4));
+    assert_eq!(error.path.as_deref(), Some(Path::new(&path)));
+    assert!(error.to_string().starts_with("Failed to parse line 3: "));
+
+    // a table read from memory has no path
+    let reader = BufReader::new(File::open(&path).unwrap());
+    let error = Ipv4RouteTable::from_reader(reader, RouteTableOptions::default())
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(error.line_number, 3);
+    assert_eq!(error.path, None);
+}
+
+#[test]
+fn lossy_entries_skip_and_report_malformed_lines() {
+    let path = format!("{FIXTURES}/route.malformed");
+    let dests: Vec<_> = Ipv4RouteTable::open(&path)
+        .unwrap()
+        .entries_lossy()
+        .map(|entry| entry.dest)
+        .collect();
+    assert_eq!(dests, [Ipv4Addr::UNSPECIFIED, Ipv4Addr::new(192, 0, 2, 0)]);
+
+    let mut skipped = Vec::new();
+    let count = Ipv4RouteTable::open(&path)
+        .unwrap()
+        .entries_lossy_with(|error| skipped.push(error.line_number))
+        .count();
+    assert_eq!(count, 2);
+    assert_eq!(skipped, [3, 5]);
+
+    let mut skipped = Vec::new();
+    let dests: Vec<_> = Ipv6RouteTable::open(format!("{FIXTURES}/ipv6_route.malformed"))
+        .unwrap()
+        .entries_lossy_with(|error| skipped.push(error.line_number))
+        .map(|entry| entry.dest)
+        .collect();
+    assert_eq!(
+        dests,
+        ["fd00::".parse::<Ipv6Addr>().unwrap(), Ipv6Addr::UNSPECIFIED]
+    );
+    assert_eq!(skipped, [2]);
+}
@@ -0,0 +1,42 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteEntry, Ipv6RouteTable, MappedCapture,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn mapped_captures_match_the_buffered_tables() {
+    let path = format!("{FIXTURES}/route.linux-6.x");
+    let capture = MappedCapture::open(&path).unwrap();
+    let mapped: Vec<Ipv4RouteEntry> = capture
+        .ipv4_entries()
+        .map(|entry| entry.unwrap().to_owned())
+        .collect();
+    let buffered: Vec<_> = Ipv4RouteTable::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(mapped, buffered);
+
+    let path = format!("{FIXTURES}/ipv6_route.linux-6.x");
+    let capture = MappedCapture::open(&path).unwrap();
+    let mapped: Vec<Ipv6RouteEntry> = capture
+        .ipv6_entries()
+        .map(|entry| Ipv6RouteEntry::from(entry.unwrap()))
+        .collect();
+    let buffered: Vec<_> = Ipv6RouteTable::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(mapped, buffered);
+}
+
+#[test]
+fn malformed_lines_keep_their_line_numbers() {
+    let capture = MappedCapture::open(format!("{FIXTURES}/ipv6_route.quirks")).unwrap();
+    let lines: Vec<_> = capture
+        .ipv6_entries()
+        .map(|entry| entry.err().map(|err| err.line_number))
+        .collect();
+    assert_eq!(lines, [Some(1), Some(2), None]);
+}
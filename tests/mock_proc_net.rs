@@ -0,0 +1,112 @@
+use proc_route_parser::{
+    CacheStats, CachedRouteTable, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteTable,
+    LiveRouteTable, MockProcNet, Route, RouteSource,
+};
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn tables_read_back_fixture_entries() {
+    let mock = MockProcNet::new().unwrap();
+    assert_eq!(mock.ipv4_table().unwrap().count(), 0);
+    assert_eq!(mock.ipv6_table().unwrap().count(), 0);
+
+    let v4 = Ipv4RouteEntry::builder("eth0")
+        .gateway(Ipv4Addr::new(192, 168, 1, 1))
+        .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+        .metric(100)
+        .build()
+        .unwrap();
+    let v6 = Ipv6RouteEntry::builder("eth0")
+        .dest(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0))
+        .dest_prefix(64)
+        .metric(256)
+        .build()
+        .unwrap();
+    mock.set_ipv4_routes(std::slice::from_ref(&v4)).unwrap();
+    mock.set_ipv6_routes(std::slice::from_ref(&v6)).unwrap();
+
+    let v4_read: Vec<_> = mock.ipv4_table().unwrap().map(Result::unwrap).collect();
+    let v6_read: Vec<_> = mock.ipv6_table().unwrap().map(Result::unwrap).collect();
+    assert_eq!(v4_read, [v4]);
+    assert_eq!(v6_read, [v6]);
+}
+
+#[test]
+fn raw_fixtures_surface_malformed_lines() {
+    let mock = MockProcNet::new().unwrap();
+    mock.set_ipv6_raw("not a route line\n").unwrap();
+    let mut table = Ipv6RouteTable::open_lenient(mock.ipv6_route_path()).unwrap();
+    assert_eq!(table.by_ref().count(), 0);
+    assert_eq!(table.report().malformed().len(), 1);
+}
+
+#[test]
+fn mock_stands_in_as_a_route_source() {
+    fn default_gateway(source: &dyn RouteSource) -> Option<Ipv4Addr> {
+        source
+            .ipv4_routes()
+            .unwrap()
+            .map(Result::unwrap)
+            .find(Ipv4RouteEntry::is_default)
+            .map(|entry| entry.gateway)
+    }
+
+    let mock = MockProcNet::new().unwrap();
+    assert_eq!(default_gateway(&mock), None);
+    let gateway = Ipv4Addr::new(192, 0, 2, 1);
+    let eth0 = "eth0".parse().unwrap();
+    mock.set_ipv4_routes(&[Ipv4RouteEntry::default_via(gateway, eth0)])
+        .unwrap();
+    assert_eq!(default_gateway(&mock), Some(gateway));
+    assert_eq!(mock.ipv6_routes().unwrap().count(), 0);
+}
+
+#[test]
+fn cache_reads_the_source_once_per_ttl() {
+    let mock = MockProcNet::new().unwrap();
+    let eth0 = "eth0".parse().unwrap();
+    mock.set_ipv4_routes(&[Ipv4RouteEntry::default_via(
+        Ipv4Addr::new(192, 0, 2, 1),
+        eth0,
+    )])
+    .unwrap();
+    let cache = CachedRouteTable::new(&mock, Duration::from_secs(3600));
+    let dest = Ipv4Addr::new(198, 51, 100, 7).into();
+    for _ in 0..3 {
+        let route = cache.lookup(dest).unwrap().unwrap();
+        assert_eq!(route.interface(), "eth0");
+    }
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 1 });
+
+    // changes stay hidden until the cache is invalidated
+    mock.set_ipv4_routes(&[]).unwrap();
+    assert!(cache.lookup(dest).unwrap().is_some());
+    cache.invalidate();
+    assert!(cache.lookup(dest).unwrap().is_none());
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 2 });
+}
+
+#[test]
+fn live_table_follows_the_source() {
+    let mock = Arc::new(MockProcNet::new().unwrap());
+    let live = LiveRouteTable::spawn_with(mock.clone(), Duration::from_secs(3600)).unwrap();
+    assert_eq!(live.default_gateway(), None);
+
+    let gateway = Ipv4Addr::new(192, 0, 2, 1);
+    let eth0 = "eth0".parse().unwrap();
+    mock.set_ipv4_routes(&[Ipv4RouteEntry::default_via(gateway, eth0)])
+        .unwrap();
+    live.clone().refresh_now();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while live.default_gateway().is_none() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(live.default_gateway(), Some(gateway.into()));
+    let route = live.lookup(Ipv4Addr::new(198, 51, 100, 7).into()).unwrap();
+    assert_eq!(route.interface(), "eth0");
+}
@@ -0,0 +1,60 @@
+use proc_route_parser::{
+    MulticastCacheEntry, MulticastCacheTable, MulticastOif, MulticastVifTable,
+};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn ipv4_cache_with_unresolved_entries() {
+    let path = format!("{FIXTURES}/ip_mr_cache.linux-6.x");
+    let entries: Vec<MulticastCacheEntry> = MulticastCacheTable::open(&path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(
+        entries[0],
+        MulticastCacheEntry {
+            group: Ipv4Addr::new(239, 1, 2, 3),
+            origin: Ipv4Addr::new(10, 0, 0, 5),
+            iif: Some(0),
+            packets: 0,
+            bytes: 0,
+            wrong_if: 0,
+            oifs: vec![MulticastOif { vif: 1, ttl: 1 }],
+        }
+    );
+    assert!(!entries[2].is_resolved());
+    assert!(entries[2].oifs.is_empty());
+}
+
+#[test]
+fn ipv6_cache_uses_colon_notation() {
+    let path = format!("{FIXTURES}/ip6_mr_cache.linux-6.x");
+    let entries: Vec<MulticastCacheEntry<Ipv6Addr>> = MulticastCacheTable::open(&path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(entries[0].group, "ff0e::1:3".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(entries[0].origin, "fd00::5".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(entries[0].oifs, [MulticastOif { vif: 1, ttl: 1 }]);
+}
+
+#[test]
+fn vif_tables_of_both_families() {
+    let ipv4: Vec<_> = MulticastVifTable::open(format!("{FIXTURES}/ip_mr_vif.linux-6.x"))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(&*ipv4[0].name, "eth0");
+    assert_eq!(ipv4[1].index, 1);
+    assert_eq!(ipv4[0].remote, Some(Ipv4Addr::UNSPECIFIED));
+
+    let ipv6: Vec<_> = MulticastVifTable::open(format!("{FIXTURES}/ip6_mr_vif.linux-6.x"))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(&*ipv6[1].name, "lo");
+    assert_eq!(ipv6[1].local, None);
+}
@@ -0,0 +1,466 @@
+use proc_route_parser::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, KernelRoute, NextHop,
+    RouteEntry, RouteManager, RouteSpec, reconcile_kernel,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn network(addr: &str, prefix_len: u8) -> IpNet {
+    IpNet::new(addr.parse().unwrap(), prefix_len).unwrap()
+}
+
+fn kernel_route(spec: &RouteSpec, table: u32, route_type: u8) -> KernelRoute {
+    KernelRoute {
+        destination: spec.destination,
+        table,
+        route_type,
+        metric: spec.metric,
+        preferred_source: None,
+        next_hops: vec![NextHop {
+            gateway: spec.gateway,
+            iface: spec.iface.clone(),
+            weight: 1,
+        }],
+    }
+}
+
+#[test]
+fn reconcile_leaves_other_tables_alone() {
+    const MAIN: u32 = libc::RT_TABLE_MAIN as u32;
+    const LOCAL: u32 = libc::RT_TABLE_LOCAL as u32;
+    let gateway: IpAddr = "fd00::1".parse().unwrap();
+    let default = RouteSpec::new(network("::", 0), "eth0")
+        .via(gateway)
+        .metric(1024);
+    let stale = RouteSpec::new(network("2001:db8:1::", 48), "eth0")
+        .via(gateway)
+        .metric(1024);
+    // a policy table route via a gateway on the managed interface
+    let policy = RouteSpec::new(network("2001:db8:2::", 48), "eth0")
+        .via(gateway)
+        .metric(1024);
+    let desired_elsewhere = RouteSpec::new(network("2001:db8:3::", 48), "eth0")
+        .via(gateway)
+        .metric(1024);
+    let local = RouteSpec::new(IpNet::new(Ipv6Addr::LOCALHOST.into(), 128).unwrap(), "lo");
+    let actual = [
+        kernel_route(&default, MAIN, libc::RTN_UNICAST),
+        kernel_route(&stale, MAIN, libc::RTN_UNICAST),
+        kernel_route(&policy, 100, libc::RTN_UNICAST),
+        kernel_route(&desired_elsewhere, 100, libc::RTN_UNICAST),
+        kernel_route(&local, LOCAL, libc::RTN_LOCAL),
+    ];
+
+    let plan = reconcile_kernel(&[default, desired_elsewhere.clone()], &actual);
+    // a desired route found only in another table is still missing from the main one
+    assert_eq!(plan.add, [desired_elsewhere]);
+    assert!(plan.replace.is_empty());
+    assert_eq!(plan.delete, [stale]);
+}
+
+/// An `rtattr` padded to 4 bytes
+fn attr(attr_type: u16, data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(4 + data.len() as u16).to_ne_bytes());
+    buf.extend_from_slice(&attr_type.to_ne_bytes());
+    buf.extend_from_slice(data);
+    buf.resize(buf.len().next_multiple_of(4), 0);
+    buf
+}
+
+/// The `rtmsg` of a route added to the main table, followed by `attrs`
+fn rtmsg(family: u8, dst_len: u8, scope: u8, route_type: u8, attrs: &[Vec<u8>]) -> Vec<u8> {
+    let header = [
+        family,
+        dst_len,
+        0,
+        0,
+        libc::RT_TABLE_MAIN,
+        libc::RTPROT_BOOT,
+        scope,
+        route_type,
+    ];
+    [&header[..], &0u32.to_ne_bytes(), &attrs.concat()].concat()
+}
+
+/// The `rtmsg` of a delete from `table`, which leaves the protocol and type unspecified so that
+/// it matches routes of any origin
+fn delete_rtmsg(family: u8, dst_len: u8, table: u8, attrs: &[Vec<u8>]) -> Vec<u8> {
+    let header = [
+        family,
+        dst_len,
+        0,
+        0,
+        table,
+        libc::RTPROT_UNSPEC,
+        libc::RT_SCOPE_NOWHERE,
+        libc::RTN_UNSPEC,
+    ];
+    [&header[..], &0u32.to_ne_bytes(), &attrs.concat()].concat()
+}
+
+/// A `nlmsghdr` for `payload`, with port id 0
+fn nlmsg(msg_type: u16, flags: libc::c_int, seq: u32, payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(16 + payload.len() as u32).to_ne_bytes());
+    buf.extend_from_slice(&msg_type.to_ne_bytes());
+    buf.extend_from_slice(&((flags | libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16).to_ne_bytes());
+    buf.extend_from_slice(&seq.to_ne_bytes());
+    buf.extend_from_slice(&0u32.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+/// `lo` is the first interface of every network namespace
+const LO_INDEX: u32 = 1;
+
+#[test]
+fn ipv4_changes_encode_header_and_attributes() {
+    let entry = Ipv4RouteEntry::builder("lo")
+        .dest(Ipv4Addr::new(10, 1, 2, 0))
+        .prefix_len(24)
+        .gateway(Ipv4Addr::new(127, 0, 0, 2))
+        .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+        .metric(100)
+        .build()
+        .unwrap();
+    let attrs = [
+        attr(libc::RTA_DST, &[10, 1, 2, 0]),
+        attr(libc::RTA_GATEWAY, &[127, 0, 0, 2]),
+        attr(libc::RTA_OIF, &LO_INDEX.to_ne_bytes()),
+        attr(libc::RTA_PRIORITY, &100u32.to_ne_bytes()),
+    ];
+    let inet = libc::AF_INET as u8;
+    let added = rtmsg(inet, 24, libc::RT_SCOPE_UNIVERSE, libc::RTN_UNICAST, &attrs);
+    let deleted = delete_rtmsg(inet, 24, libc::RT_TABLE_MAIN, &attrs);
+
+    assert_eq!(
+        RouteManager::add_request(&entry, 7).unwrap(),
+        nlmsg(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            7,
+            &added
+        )
+    );
+    assert_eq!(
+        RouteManager::replace_request(&entry, 8).unwrap(),
+        nlmsg(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_REPLACE,
+            8,
+            &added
+        )
+    );
+    assert_eq!(
+        RouteManager::delete_request(&entry, 9).unwrap(),
+        nlmsg(libc::RTM_DELROUTE, 0, 9, &deleted)
+    );
+    assert_eq!(
+        RouteManager::add_request(&entry, 7).unwrap().len(),
+        16 + 12 + 4 * 8
+    );
+}
+
+#[test]
+fn on_link_and_reject_routes_encode_their_scope_and_type() {
+    let on_link = Ipv4RouteEntry::builder("lo")
+        .dest(Ipv4Addr::new(10, 1, 2, 0))
+        .prefix_len(24)
+        .build()
+        .unwrap();
+    let payload = rtmsg(
+        libc::AF_INET as u8,
+        24,
+        libc::RT_SCOPE_LINK,
+        libc::RTN_UNICAST,
+        &[
+            attr(libc::RTA_DST, &[10, 1, 2, 0]),
+            attr(libc::RTA_OIF, &LO_INDEX.to_ne_bytes()),
+            attr(libc::RTA_PRIORITY, &0u32.to_ne_bytes()),
+        ],
+    );
+    assert_eq!(
+        RouteManager::add_request(&on_link, 1).unwrap(),
+        nlmsg(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            1,
+            &payload
+        )
+    );
+
+    // a reject route has no interface, so it needs none that exists
+    let reject = Ipv4RouteEntry::builder("no-such-if")
+        .dest(Ipv4Addr::new(192, 0, 2, 0))
+        .prefix_len(24)
+        .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::REJECT)
+        .build()
+        .unwrap();
+    let payload = rtmsg(
+        libc::AF_INET as u8,
+        24,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNREACHABLE,
+        &[
+            attr(libc::RTA_DST, &[192, 0, 2, 0]),
+            attr(libc::RTA_PRIORITY, &0u32.to_ne_bytes()),
+        ],
+    );
+    assert_eq!(
+        RouteManager::add_request(&reject, 2).unwrap(),
+        nlmsg(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            2,
+            &payload
+        )
+    );
+
+    let missing = Ipv4RouteEntry::builder("no-such-if").build().unwrap();
+    assert!(RouteManager::add_request(&missing, 3).is_err());
+}
+
+#[test]
+fn ipv6_default_route_encodes_without_a_destination() {
+    let gateway: Ipv6Addr = "fe80::1".parse().unwrap();
+    let entry = Ipv6RouteEntry::builder("lo")
+        .next_hop(gateway)
+        .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+        .metric(1024)
+        .build()
+        .unwrap();
+    let payload = rtmsg(
+        libc::AF_INET6 as u8,
+        0,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNICAST,
+        &[
+            attr(libc::RTA_GATEWAY, &gateway.octets()),
+            attr(libc::RTA_OIF, &LO_INDEX.to_ne_bytes()),
+            attr(libc::RTA_PRIORITY, &1024u32.to_ne_bytes()),
+        ],
+    );
+    assert_eq!(
+        RouteManager::replace_request(&entry, 5).unwrap(),
+        nlmsg(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_REPLACE,
+            5,
+            &payload
+        )
+    );
+}
+
+/// An `rtnexthop` followed by the `RTA_GATEWAY` of its path
+fn rtnexthop(hops: u8, ifindex: u32, gateway: &[u8]) -> Vec<u8> {
+    let gateway = attr(libc::RTA_GATEWAY, gateway);
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(8 + gateway.len() as u16).to_ne_bytes());
+    buf.push(0); // flags
+    buf.push(hops);
+    buf.extend_from_slice(&ifindex.to_ne_bytes());
+    buf.extend(gateway);
+    buf
+}
+
+#[test]
+fn multipath_routes_decode_every_next_hop() {
+    // an interface index that does not exist is reported as the number
+    let gone = 999_999u32;
+    let multipath = [
+        rtnexthop(0, LO_INDEX, &[127, 0, 0, 2]),
+        rtnexthop(2, gone, &[127, 0, 0, 3]),
+    ]
+    .concat();
+    let payload = rtmsg(
+        libc::AF_INET as u8,
+        8,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNICAST,
+        &[
+            attr(libc::RTA_TABLE, &100u32.to_ne_bytes()),
+            attr(libc::RTA_DST, &[10, 0, 0, 0]),
+            attr(libc::RTA_PRIORITY, &20u32.to_ne_bytes()),
+            attr(libc::RTA_PREFSRC, &[127, 0, 0, 1]),
+            attr(libc::RTA_MULTIPATH, &multipath),
+        ],
+    );
+    assert_eq!(
+        KernelRoute::decode(&payload).unwrap(),
+        KernelRoute {
+            destination: network("10.0.0.0", 8),
+            table: 100,
+            route_type: libc::RTN_UNICAST,
+            metric: 20,
+            preferred_source: Some(Ipv4Addr::LOCALHOST.into()),
+            next_hops: vec![
+                NextHop {
+                    gateway: Some(Ipv4Addr::new(127, 0, 0, 2).into()),
+                    iface: "lo".into(),
+                    weight: 1,
+                },
+                NextHop {
+                    gateway: Some(Ipv4Addr::new(127, 0, 0, 3).into()),
+                    iface: gone.to_string(),
+                    weight: 3,
+                },
+            ],
+        }
+    );
+
+    // a truncated path ends the list rather than the route
+    let truncated = [&multipath[..], &[24, 0, 0, 0]].concat();
+    let payload = rtmsg(
+        libc::AF_INET as u8,
+        8,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNICAST,
+        &[
+            attr(libc::RTA_DST, &[10, 0, 0, 0]),
+            attr(libc::RTA_MULTIPATH, &truncated),
+        ],
+    );
+    assert_eq!(KernelRoute::decode(&payload).unwrap().next_hops.len(), 2);
+}
+
+#[test]
+fn single_path_routes_and_other_families_decode() {
+    let payload = rtmsg(
+        libc::AF_INET6 as u8,
+        0,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNICAST,
+        &[
+            attr(
+                libc::RTA_GATEWAY,
+                &"fe80::1".parse::<Ipv6Addr>().unwrap().octets(),
+            ),
+            attr(libc::RTA_OIF, &LO_INDEX.to_ne_bytes()),
+            attr(libc::RTA_PRIORITY, &1024u32.to_ne_bytes()),
+        ],
+    );
+    let route = KernelRoute::decode(&payload).unwrap();
+    assert_eq!(route.destination, network("::", 0));
+    assert_eq!(route.table, libc::RT_TABLE_MAIN as u32);
+    assert_eq!(
+        route.next_hops,
+        [NextHop {
+            gateway: Some("fe80::1".parse().unwrap()),
+            iface: "lo".into(),
+            weight: 1,
+        }]
+    );
+
+    // an unreachable route has no path
+    let payload = rtmsg(
+        libc::AF_INET as u8,
+        24,
+        libc::RT_SCOPE_UNIVERSE,
+        libc::RTN_UNREACHABLE,
+        &[attr(libc::RTA_DST, &[192, 0, 2, 0])],
+    );
+    assert!(KernelRoute::decode(&payload).unwrap().next_hops.is_empty());
+
+    let bridge = rtmsg(libc::AF_BRIDGE as u8, 0, 0, libc::RTN_UNICAST, &[]);
+    assert_eq!(KernelRoute::decode(&bridge), None);
+    assert_eq!(KernelRoute::decode(&[libc::AF_INET as u8; 4]), None);
+}
+
+#[test]
+fn kernel_routes_are_changed_in_their_own_table() {
+    let route = KernelRoute {
+        destination: network("2001:db8::", 32),
+        table: 100,
+        route_type: libc::RTN_UNICAST,
+        metric: 1024,
+        preferred_source: None,
+        next_hops: vec![NextHop {
+            gateway: Some("fe80::1".parse().unwrap()),
+            iface: "lo".into(),
+            weight: 1,
+        }],
+    };
+    let attrs = [
+        attr(
+            libc::RTA_DST,
+            &"2001:db8::".parse::<Ipv6Addr>().unwrap().octets(),
+        ),
+        attr(
+            libc::RTA_GATEWAY,
+            &"fe80::1".parse::<Ipv6Addr>().unwrap().octets(),
+        ),
+        attr(libc::RTA_OIF, &LO_INDEX.to_ne_bytes()),
+        attr(libc::RTA_PRIORITY, &1024u32.to_ne_bytes()),
+    ];
+    assert_eq!(
+        RouteManager::delete_request(&route, 4).unwrap(),
+        nlmsg(
+            libc::RTM_DELROUTE,
+            0,
+            4,
+            &delete_rtmsg(libc::AF_INET6 as u8, 32, 100, &attrs)
+        )
+    );
+
+    // an id beyond rtm_table goes into RTA_TABLE
+    let route = KernelRoute {
+        table: 1000,
+        ..route
+    };
+    let request = RouteManager::delete_request(&route, 5).unwrap();
+    let payload = &request[16..];
+    assert_eq!(payload[4], libc::RT_TABLE_UNSPEC);
+    assert_eq!(KernelRoute::decode(payload).unwrap().table, 1000);
+}
+
+#[test]
+fn multipath_kernel_routes_round_trip_through_a_request() {
+    let route = KernelRoute {
+        destination: network("10.0.0.0", 8),
+        table: libc::RT_TABLE_MAIN as u32,
+        route_type: libc::RTN_UNICAST,
+        metric: 20,
+        preferred_source: Some(Ipv4Addr::LOCALHOST.into()),
+        next_hops: vec![
+            NextHop {
+                gateway: Some(Ipv4Addr::new(127, 0, 0, 2).into()),
+                iface: "lo".into(),
+                weight: 1,
+            },
+            NextHop {
+                gateway: Some(Ipv4Addr::new(127, 0, 0, 3).into()),
+                iface: "lo".into(),
+                weight: 3,
+            },
+        ],
+    };
+    let request = RouteManager::add_request(&route, 1).unwrap();
+    let payload = &request[16..];
+    assert_eq!(payload[5], libc::RTPROT_BOOT);
+    assert_eq!(KernelRoute::decode(payload).unwrap(), route);
+}
+
+#[test]
+fn plan_deletes_match_routes_of_any_origin_in_the_main_table() {
+    const MAIN: u32 = libc::RT_TABLE_MAIN as u32;
+    let gateway: IpAddr = Ipv4Addr::new(127, 0, 0, 2).into();
+    let desired = RouteSpec::new(network("10.1.0.0", 16), "lo").via(gateway);
+    // such as one a DHCP client installed, which a delete naming a protocol would miss
+    let stale = RouteSpec::new(network("10.2.0.0", 16), "lo")
+        .via(gateway)
+        .metric(100);
+    let plan = reconcile_kernel(
+        std::slice::from_ref(&desired),
+        &[kernel_route(&stale, MAIN, libc::RTN_UNICAST)],
+    );
+    assert_eq!(plan.delete, [stale]);
+
+    let RouteEntry::V4(entry) = plan.delete[0].to_entry().unwrap() else {
+        panic!("an IPv4 spec makes an IPv4 entry");
+    };
+    let request = RouteManager::delete_request(&entry, 1).unwrap();
+    let rtmsg = &request[16..28];
+    assert_eq!(rtmsg[4], libc::RT_TABLE_MAIN);
+    assert_eq!(rtmsg[5], libc::RTPROT_UNSPEC);
+    assert_eq!(rtmsg[7], libc::RTN_UNSPEC);
+}
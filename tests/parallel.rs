@@ -0,0 +1,43 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteTable, RouteParseError, SnapshotError,
+};
+use std::fs;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn parallel_parse_keeps_the_file_order() {
+    let path = std::env::temp_dir().join(format!("parallel_route_{}", std::process::id()));
+    let mut text = format!("{}\n", Ipv4RouteEntry::PROC_HEADER);
+    for metric in 0..20_000 {
+        let entry = Ipv4RouteEntry::builder("eth0")
+            .metric(metric)
+            .build()
+            .unwrap();
+        text += &entry.to_proc_line();
+        text.push('\n');
+    }
+    fs::write(&path, text).unwrap();
+
+    let entries = Ipv4RouteTable::parse_file_parallel(&path).unwrap();
+    let buffered: Vec<_> = Ipv4RouteTable::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries, buffered);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn first_malformed_line_is_reported() {
+    let err =
+        Ipv6RouteTable::parse_file_parallel(format!("{FIXTURES}/ipv6_route.quirks")).unwrap_err();
+    let SnapshotError::Parse(err) = err else {
+        panic!("unexpected error {err:?}");
+    };
+    assert_eq!(err.line_number, 1);
+    assert!(matches!(
+        err.source,
+        RouteParseError::InvalidFieldCount { .. }
+    ));
+}
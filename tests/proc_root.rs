@@ -0,0 +1,349 @@
+use proc_route_parser::{
+    AccessDenied, AddressFamily, ErrorCode, FormatProfile, Ipv4RouteEntry, Ipv4RouteFlags,
+    Ipv6RouteTable, KernelVersion, MacAddr, ProcRoot, RouteParseError, RouteTableOptions,
+};
+use std::{
+    fs::{self, File},
+    io,
+    net::IpAddr,
+    path::PathBuf,
+};
+
+#[test]
+fn tables_are_read_below_the_root() {
+    let root = std::env::temp_dir().join(format!("proc_root_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    let entry = Ipv4RouteEntry::builder("eth0").metric(100).build().unwrap();
+    fs::write(
+        root.join("net/route"),
+        format!(
+            "{}\n{}\n",
+            Ipv4RouteEntry::PROC_HEADER,
+            entry.to_proc_line()
+        ),
+    )
+    .unwrap();
+
+    let proc_root = ProcRoot::new(&root);
+    let entries: Vec<_> = proc_root
+        .ipv4_route_table()
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries, [entry]);
+    assert!(proc_root.ipv6_route_table().is_err());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn route_tables_tolerate_a_missing_ipv6_table() {
+    let root = std::env::temp_dir().join(format!("proc_root_no_ipv6_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    fs::write(
+        root.join("net/route"),
+        format!("{}\n", Ipv4RouteEntry::PROC_HEADER),
+    )
+    .unwrap();
+
+    let (ipv4, ipv6) = ProcRoot::new(&root).route_tables().unwrap();
+    assert!(ipv4.is_empty());
+    assert!(ipv6.is_empty());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn missing_ipv6_table_is_an_error_only_with_ipv6_enabled() {
+    let root = std::env::temp_dir().join(format!("proc_root_ipv6_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    let proc_root = ProcRoot::new(&root);
+    assert!(matches!(
+        proc_root.ipv6_route_table(),
+        Err(RouteParseError::Ipv6Unavailable)
+    ));
+
+    fs::create_dir_all(root.join("sys/net/ipv6")).unwrap();
+    assert!(proc_root.ipv6_enabled());
+    assert!(matches!(
+        proc_root.ipv6_route_table(),
+        Err(RouteParseError::Io(_))
+    ));
+
+    // WSL may leave the table out with IPv6 enabled
+    fs::create_dir_all(root.join("sys/kernel")).unwrap();
+    fs::write(
+        root.join("sys/kernel/osrelease"),
+        "5.15.153.1-microsoft-standard-WSL2\n",
+    )
+    .unwrap();
+    assert!(proc_root.is_wsl());
+    assert!(matches!(
+        proc_root.ipv6_route_table(),
+        Err(RouteParseError::Ipv6Unavailable)
+    ));
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn preferred_source_follows_the_route_and_interface_addresses() {
+    let root = std::env::temp_dir().join(format!("proc_root_source_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    let link = |iface: &str, dest: &str| {
+        Ipv4RouteEntry::builder(iface)
+            .dest(dest.parse().unwrap())
+            .prefix_len(24)
+            .flags(Ipv4RouteFlags::UP)
+            .build()
+            .unwrap()
+            .to_proc_line()
+    };
+    let default = Ipv4RouteEntry::builder("eth1")
+        .gateway("198.51.100.1".parse().unwrap())
+        .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+        .build()
+        .unwrap();
+    fs::write(
+        root.join("net/route"),
+        [
+            Ipv4RouteEntry::PROC_HEADER.to_string(),
+            default.to_proc_line(),
+            link("eth0", "192.0.2.0"),
+            link("eth1", "198.51.100.0"),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    fs::write(
+        root.join("net/fib_trie"),
+        "Local:\n  |-- 192.0.2.2\n     /32 host LOCAL\n  |-- 198.51.100.7\n     /32 host LOCAL\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("net/ipv6_route"),
+        "00000000000000000000000000000000 00 00000000000000000000000000000000 00 fd000000000000000000000000000001 00000400 00000001 00000000 00000003     eth0\n\
+         fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0\n",
+    )
+    .unwrap();
+    // a deprecated, a tentative and a usable global address next to the link-local one
+    fs::write(
+        root.join("net/if_inet6"),
+        "fd000000000000000000000000000002 02 40 00 20     eth0\n\
+         fd000000000000000000000000000003 02 40 00 80     eth0\n\
+         fd000000000000000000000000000004 02 40 00 40     eth0\n\
+         fe800000000000000000000000000001 02 40 20 80     eth0\n",
+    )
+    .unwrap();
+
+    let proc_root = ProcRoot::new(&root);
+    let source = |dest: &str| {
+        proc_root
+            .preferred_source(dest.parse().unwrap())
+            .unwrap()
+            .map(|addr| addr.to_string())
+    };
+    assert_eq!(source("203.0.113.5").as_deref(), Some("198.51.100.7"));
+    assert_eq!(source("192.0.2.9").as_deref(), Some("192.0.2.2"));
+    assert_eq!(source("2001:db8::1").as_deref(), Some("fd00::3"));
+    assert_eq!(source("fe80::9").as_deref(), Some("fe80::1"));
+    assert_eq!(
+        proc_root
+            .preferred_source("127.0.0.5".parse().unwrap())
+            .unwrap(),
+        Some(IpAddr::from([127, 0, 0, 1]))
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn route_table_health_compares_dst_entries_with_the_limits() {
+    let root = std::env::temp_dir().join(format!("proc_root_health_{}", std::process::id()));
+    fs::create_dir_all(root.join("net/stat")).unwrap();
+    fs::create_dir_all(root.join("sys/net/ipv4/route")).unwrap();
+    fs::create_dir_all(root.join("sys/net/ipv6/route")).unwrap();
+    let entry = Ipv4RouteEntry::builder("eth0").metric(100).build().unwrap();
+    fs::write(
+        root.join("net/route"),
+        format!(
+            "{}\n{}\n",
+            Ipv4RouteEntry::PROC_HEADER,
+            entry.to_proc_line()
+        ),
+    )
+    .unwrap();
+    fs::write(root.join("net/ipv6_route"), "").unwrap();
+    fs::write(
+        root.join("net/stat/rt_cache"),
+        "entries  in_hit\n00000004 00000000\n00000004 00000000\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("net/rt6_stats"),
+        "0009 0006 000e 0007 0000 0400 0002\n",
+    )
+    .unwrap();
+    fs::write(root.join("sys/net/ipv4/route/max_size"), "2147483647\n").unwrap();
+    fs::write(root.join("sys/net/ipv4/route/gc_thresh"), "-1\n").unwrap();
+    fs::write(root.join("sys/net/ipv6/route/max_size"), "4096\n").unwrap();
+    fs::write(root.join("sys/net/ipv6/route/gc_thresh"), "1024\n").unwrap();
+
+    let health = ProcRoot::new(&root).route_table_health().unwrap();
+    assert_eq!(health.ipv4.routes, 1);
+    assert_eq!(health.ipv4.dst_entries, Some(4));
+    assert_eq!(health.ipv4.gc_thresh, None);
+    assert!(!health.ipv4.gc_pressure());
+    assert_eq!(health.ipv6.routes, 0);
+    assert_eq!(health.ipv6_stats.unwrap().route_entries, 7);
+    assert_eq!(health.ipv6.utilization(), Some(25.0));
+    assert!(health.ipv6.gc_pressure());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn line_errors_name_the_file_and_field() {
+    let root = std::env::temp_dir().join(format!("proc_root_errors_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    fs::write(
+        root.join("net/route"),
+        format!(
+            "{}\neth0\t00000000\t0101A8C0\t0003\t0\t0\tlow\t00000000\t0\t0\t0\n",
+            Ipv4RouteEntry::PROC_HEADER
+        ),
+    )
+    .unwrap();
+
+    let err = ProcRoot::new(&root)
+        .ipv4_route_table()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.line_number, 2);
+    assert_eq!(err.family, Some(AddressFamily::Ipv4));
+    assert_eq!(err.path.as_deref(), Some(root.join("net/route").as_path()));
+    assert!(matches!(
+        err.source,
+        RouteParseError::InvalidField {
+            column: "Metric",
+            index: 6,
+            ..
+        }
+    ));
+    assert_eq!(err.error_code(), ErrorCode::InvalidValue);
+    assert_eq!(err.error_code().code(), 11);
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn gateway_mac_is_read_from_the_arp_table() {
+    let root = std::env::temp_dir().join(format!("proc_root_arp_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    fs::write(
+        root.join("net/arp"),
+        "IP address       HW type     Flags       HW address            Mask     Device\n\
+         192.168.1.1      0x1         0x2         52:54:00:12:34:56     *        eth0\n\
+         192.168.1.2      0x1         0x0         00:00:00:00:00:00     *        eth0\n",
+    )
+    .unwrap();
+    let proc_root = ProcRoot::new(&root);
+
+    let via = |gateway: &str, iface: &str| {
+        Ipv4RouteEntry::default_via(gateway.parse().unwrap(), iface.parse().unwrap())
+    };
+    assert_eq!(
+        proc_root
+            .resolve_gateway_mac(&via("192.168.1.1", "eth0"))
+            .unwrap(),
+        Some(MacAddr::new([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]))
+    );
+    // incomplete entry, and a gateway on another interface
+    assert_eq!(
+        proc_root
+            .resolve_gateway_mac(&via("192.168.1.2", "eth0"))
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        proc_root
+            .resolve_gateway_mac(&via("192.168.1.1", "eth1"))
+            .unwrap(),
+        None
+    );
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn format_profile_follows_the_kernel_release() {
+    let root = std::env::temp_dir().join(format!("proc_root_kernel_{}", std::process::id()));
+    fs::create_dir_all(root.join("sys/kernel")).unwrap();
+    fs::write(root.join("sys/kernel/osrelease"), "3.10.14+\n").unwrap();
+    let proc_root = ProcRoot::new(&root);
+    assert_eq!(proc_root.kernel_release().unwrap(), "3.10.14+");
+
+    let profile = proc_root.format_profile().unwrap();
+    assert_eq!(profile.kernel, Some(KernelVersion::new(3, 10, 14)));
+    assert!(profile.ipv6_cached_routes);
+    let quirks = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/ipv6_route.quirks"
+    );
+    let options = RouteTableOptions::new().format_profile(&profile);
+    assert!(
+        Ipv6RouteTable::open_with(quirks, options.clone())
+            .unwrap()
+            .all(|entry| entry.is_ok())
+    );
+
+    // a clone the kernel made for a PMTU exception is left out, as current kernels do
+    let cached = "20010db8000000000000000000000001 80 00000000000000000000000000000000 00 \
+                  fe800000000000000000000000000001 00000400 00000001 00000000 01000003 eth0\n";
+    assert_eq!(
+        Ipv6RouteTable::from_reader(cached.as_bytes(), options).count(),
+        0
+    );
+    let current = FormatProfile::for_kernel(KernelVersion::new(6, 8, 0));
+    let options = RouteTableOptions::new().format_profile(&current);
+    assert_eq!(
+        Ipv6RouteTable::from_reader(cached.as_bytes(), options).count(),
+        1
+    );
+
+    assert_eq!("2.6.36.4brcmarm+".parse(), Ok(KernelVersion::new(2, 6, 36)));
+    assert!("generic".parse::<KernelVersion>().is_err());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn permission_errors_map_to_access_denied() {
+    let path = PathBuf::from("/proc/net/route");
+    let denied = io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        AccessDenied { path: path.clone() },
+    );
+    assert_eq!(AccessDenied::find(&denied).unwrap().path, path);
+    let err = RouteParseError::from(denied);
+    assert_eq!(err.error_code(), ErrorCode::PermissionDenied);
+    assert_eq!(err.error_code().code(), 2);
+    assert_eq!(err.error_code().name(), "permission_denied");
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_tables_are_access_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = std::env::temp_dir().join(format!("proc_root_denied_{}", std::process::id()));
+    fs::create_dir_all(root.join("net")).unwrap();
+    let route = root.join("net/route");
+    fs::write(&route, format!("{}\n", Ipv4RouteEntry::PROC_HEADER)).unwrap();
+    fs::set_permissions(&route, fs::Permissions::from_mode(0o000)).unwrap();
+    // a privileged process reads the file anyway
+    if File::open(&route).is_err() {
+        let err = ProcRoot::new(&root).ipv4_route_table().err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(AccessDenied::find(&err).unwrap().path, route);
+        assert_eq!(
+            RouteParseError::from(err).error_code(),
+            ErrorCode::PermissionDenied
+        );
+    }
+    fs::remove_dir_all(root).unwrap();
+}
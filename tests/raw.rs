@@ -0,0 +1,55 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv6RouteEntry, RawIpv4RouteEntry, RawIpv6RouteEntry, RouteParseError,
+};
+
+const IPV4_ROUTES: &str = include_str!("fixtures/route.linux-6.x");
+const IPV6_ROUTES: &str = include_str!("fixtures/ipv6_route.linux-6.x");
+
+#[test]
+fn raw_ipv4_entries_parse_like_the_line_parser() {
+    let lines: Vec<_> = IPV4_ROUTES.lines().skip(1).collect();
+    assert!(!lines.is_empty());
+    for line in lines {
+        let raw = RawIpv4RouteEntry::split(line).unwrap();
+        let entry = raw.parse().unwrap().to_owned();
+        assert_eq!(entry, line.parse::<Ipv4RouteEntry>().unwrap(), "{line}");
+    }
+}
+
+#[test]
+fn raw_ipv6_entries_parse_like_the_line_parser() {
+    let lines: Vec<_> = IPV6_ROUTES.lines().collect();
+    assert!(!lines.is_empty());
+    for line in lines {
+        let raw = RawIpv6RouteEntry::split(line).unwrap();
+        let entry = raw.parse().unwrap().to_owned();
+        assert_eq!(entry, line.parse::<Ipv6RouteEntry>().unwrap(), "{line}");
+    }
+}
+
+#[test]
+fn malformed_raw_fields_name_their_column() {
+    let line = "eth0\t00000000\t010200C0\t0003\t0\t0\tlots\t00000000\t0\t0\t0";
+    let raw = RawIpv4RouteEntry::split(line).unwrap();
+    assert_eq!(raw.metric, "lots");
+    assert!(matches!(
+        raw.parse(),
+        Err(RouteParseError::InvalidField {
+            column: "Metric",
+            index: 6,
+            ..
+        })
+    ));
+
+    let line = "fe80000000000000000000000000000g 40 00000000000000000000000000000000 00 \
+                00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0";
+    let raw = RawIpv6RouteEntry::split(line).unwrap();
+    assert!(matches!(
+        raw.parse(),
+        Err(RouteParseError::InvalidField {
+            column: "Destination",
+            index: 0,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,106 @@
+use proc_route_parser::{IpNet, Ipv4RouteFlags, RouteEntry, RouteSpec, Snapshot, reconcile};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::SystemTime,
+};
+
+fn network(addr: [u8; 4], prefix_len: u8) -> IpNet {
+    IpNet::new(Ipv4Addr::from(addr).into(), prefix_len).unwrap()
+}
+
+fn gateway(addr: [u8; 4]) -> IpAddr {
+    Ipv4Addr::from(addr).into()
+}
+
+fn snapshot(routes: &[RouteSpec]) -> Snapshot {
+    let mut snapshot = Snapshot {
+        captured_at: SystemTime::UNIX_EPOCH,
+        ipv4: Vec::new(),
+        ipv6: Vec::new(),
+        arp: Vec::new(),
+    };
+    for spec in routes {
+        match spec.to_entry().unwrap() {
+            RouteEntry::V4(entry) => snapshot.ipv4.push(entry),
+            RouteEntry::V6(entry) => snapshot.ipv6.push(entry),
+        }
+    }
+    snapshot
+}
+
+#[test]
+fn plans_adds_replaces_and_deletes() {
+    let lan = RouteSpec::new(network([192, 0, 2, 0], 24), "eth0");
+    let default = RouteSpec::new(network([0, 0, 0, 0], 0), "eth0").via(gateway([192, 0, 2, 1]));
+    let stale = RouteSpec::new(network([10, 1, 0, 0], 16), "eth0").via(gateway([192, 0, 2, 9]));
+    let other_iface =
+        RouteSpec::new(network([10, 2, 0, 0], 16), "eth1").via(gateway([198, 51, 100, 1]));
+    let ipv6_default = RouteSpec::new(IpNet::new(Ipv6Addr::UNSPECIFIED.into(), 0).unwrap(), "eth0")
+        .via("fd00::1".parse().unwrap())
+        .metric(1024);
+    let actual = snapshot(&[
+        lan.clone(),
+        default,
+        stale.clone(),
+        other_iface,
+        ipv6_default,
+    ]);
+
+    let new_default = RouteSpec::new(network([0, 0, 0, 0], 0), "wg0").via(gateway([10, 8, 0, 1]));
+    let internal = RouteSpec::new(network([10, 0, 0, 0], 8), "eth0")
+        .via(gateway([192, 0, 2, 254]))
+        .metric(100);
+    let plan = reconcile(&[lan, new_default.clone(), internal.clone()], &actual);
+
+    assert_eq!(plan.add, [internal]);
+    assert_eq!(plan.replace, [new_default]);
+    // the on-link route stays although it is not desired, and neither eth1 nor the IPv6 routes of
+    // eth0 are managed
+    assert_eq!(plan.delete, [stale]);
+}
+
+#[test]
+fn matching_tables_need_no_changes() {
+    let routes = [
+        RouteSpec::new(network([192, 0, 2, 0], 24), "eth0"),
+        RouteSpec::new(network([0, 0, 0, 0], 0), "eth0")
+            .via(gateway([192, 0, 2, 1]))
+            .metric(100),
+    ];
+    assert!(reconcile(&routes, &snapshot(&routes)).is_empty());
+}
+
+#[test]
+fn ipv6_routes_of_a_snapshot_are_never_deleted() {
+    // /proc/net/ipv6_route mixes the main table with policy tables, such as that of a VPN
+    let default = RouteSpec::new(IpNet::new(Ipv6Addr::UNSPECIFIED.into(), 0).unwrap(), "eth0")
+        .via("fd00::1".parse().unwrap())
+        .metric(1024);
+    let policy = RouteSpec::new(
+        IpNet::new("2001:db8::".parse().unwrap(), 32).unwrap(),
+        "eth0",
+    )
+    .via("fd00::2".parse().unwrap())
+    .metric(1024);
+    let actual = snapshot(&[default.clone(), policy]);
+    assert!(reconcile(&[default], &actual).is_empty());
+}
+
+#[test]
+fn spec_round_trips_through_an_entry() {
+    let spec = RouteSpec::new(network([10, 0, 0, 0], 8), "eth0")
+        .via(gateway([192, 0, 2, 254]))
+        .metric(100);
+    let RouteEntry::V4(entry) = spec.to_entry().unwrap() else {
+        panic!("an IPv4 spec makes an IPv4 entry");
+    };
+    assert_eq!(entry.flags, Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY);
+    assert_eq!(RouteSpec::of(&entry), spec);
+    assert_eq!(
+        spec.to_string(),
+        "10.0.0.0/8 via 192.0.2.254 dev eth0 metric 100"
+    );
+
+    let mixed = RouteSpec::new(network([10, 0, 0, 0], 8), "eth0").via("fe80::1".parse().unwrap());
+    assert!(mixed.to_entry().is_err());
+}
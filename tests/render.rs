@@ -0,0 +1,79 @@
+use proc_route_parser::{Column, Ipv4RouteEntry, Ipv4RouteFlags, TableRenderer};
+use std::net::Ipv4Addr;
+
+fn routes() -> Vec<Ipv4RouteEntry> {
+    vec![
+        Ipv4RouteEntry::builder("eth0")
+            .gateway(Ipv4Addr::new(192, 0, 2, 1))
+            .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+            .metric(100)
+            .build()
+            .unwrap(),
+        Ipv4RouteEntry::builder("eth0")
+            .dest(Ipv4Addr::new(192, 0, 2, 0))
+            .prefix_len(24)
+            .build()
+            .unwrap(),
+    ]
+}
+
+#[test]
+fn default_columns_follow_route_n() {
+    assert_eq!(
+        TableRenderer::new().render(&routes()),
+        "\
+Destination Gateway   Genmask       Flags Metric Ref Use Iface
+0.0.0.0     192.0.2.1 0.0.0.0       UG       100   0   0 eth0
+192.0.2.0   0.0.0.0   255.255.255.0 U          0   0   0 eth0
+"
+    );
+}
+
+#[test]
+fn colors_do_not_shift_columns() {
+    let rendered = TableRenderer::new()
+        .columns([Column::Flags, Column::Iface])
+        .color(true)
+        .render(&routes());
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[0], "Flags Iface");
+    assert_eq!(lines[2], "\x1b[32mU\x1b[0m     eth0");
+}
+
+#[test]
+fn groups_routes_by_interface() {
+    let mut routes = routes();
+    routes.insert(
+        1,
+        Ipv4RouteEntry::builder("lo")
+            .dest(Ipv4Addr::new(127, 0, 0, 0))
+            .prefix_len(8)
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        TableRenderer::new()
+            .columns([Column::Destination, Column::Iface])
+            .group_by_interface(true)
+            .render(&routes),
+        "\
+Destination Iface
+eth0:
+0.0.0.0     eth0
+192.0.2.0   eth0
+lo:
+127.0.0.0   lo
+"
+    );
+}
+
+#[test]
+fn default_routes_are_bold() {
+    let rendered = TableRenderer::new()
+        .columns([Column::Destination])
+        .color(true)
+        .render(&routes());
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[1], "\x1b[1m0.0.0.0\x1b[0m");
+    assert_eq!(lines[2], "192.0.2.0");
+}
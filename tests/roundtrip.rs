@@ -0,0 +1,120 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteEntryRef, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteEntryRef,
+    Ipv6RouteFlags, MacAddr,
+};
+use proptest::prelude::*;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Interface names as the kernel allows them: up to 15 characters without whitespace
+fn iface_name() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9._-]{0,14}"
+}
+
+fn ipv4_entry() -> impl Strategy<Value = Ipv4RouteEntry> {
+    (
+        iface_name(),
+        any::<u32>().prop_map(Ipv4Addr::from),
+        0u8..=32,
+        any::<u32>().prop_map(Ipv4Addr::from),
+        any::<u16>().prop_map(Ipv4RouteFlags::from_bits_truncate),
+        any::<[u32; 6]>(),
+    )
+        .prop_map(|(name, dest, prefix_len, gateway, mut flags, counters)| {
+            let [ref_count, use_count, metric, mtu, window, irtt] = counters;
+            if !gateway.is_unspecified() {
+                flags |= Ipv4RouteFlags::GATEWAY;
+            }
+            Ipv4RouteEntry::builder(name)
+                .dest(dest)
+                .prefix_len(prefix_len)
+                .gateway(gateway)
+                .flags(flags)
+                .ref_count(ref_count)
+                .use_count(use_count)
+                .metric(metric)
+                .mtu(mtu)
+                .window(window)
+                .irtt(irtt)
+                .build()
+                .unwrap()
+        })
+}
+
+fn ipv6_entry() -> impl Strategy<Value = Ipv6RouteEntry> {
+    (
+        iface_name(),
+        any::<u128>().prop_map(Ipv6Addr::from),
+        0u8..=128,
+        any::<u128>().prop_map(Ipv6Addr::from),
+        0u8..=128,
+        any::<u128>().prop_map(Ipv6Addr::from),
+        any::<u32>().prop_map(Ipv6RouteFlags::from_bits_truncate),
+        any::<[u32; 3]>(),
+    )
+        .prop_map(
+            |(name, dest, dest_prefix, src, src_prefix, next_hop, mut flags, counters)| {
+                let [metric, ref_count, use_count] = counters;
+                if !next_hop.is_unspecified() {
+                    flags |= Ipv6RouteFlags::GATEWAY;
+                }
+                Ipv6RouteEntry::builder(name)
+                    .dest(dest)
+                    .dest_prefix(dest_prefix)
+                    .src(src)
+                    .src_prefix(src_prefix)
+                    .next_hop(next_hop)
+                    .metric(metric)
+                    .ref_count(ref_count)
+                    .use_count(use_count)
+                    .flags(flags)
+                    .build()
+                    .unwrap()
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn ipv4_entry_round_trips(entry in ipv4_entry()) {
+        let line = entry.to_proc_line();
+        prop_assert_eq!(line.parse::<Ipv4RouteEntry>().unwrap(), entry.clone());
+        prop_assert_eq!(Ipv4RouteEntryRef::parse(&line).unwrap().to_owned(), entry);
+    }
+
+    #[test]
+    fn ipv6_entry_round_trips(entry in ipv6_entry()) {
+        let line = entry.to_proc_line();
+        prop_assert_eq!(line.parse::<Ipv6RouteEntry>().unwrap(), entry.clone());
+        prop_assert_eq!(Ipv6RouteEntryRef::parse(&line).unwrap().to_owned(), entry);
+    }
+
+    #[test]
+    fn mac_addr_round_trips(octets in any::<[u8; 6]>()) {
+        let mac = MacAddr::new(octets);
+        prop_assert_eq!(mac.to_string().parse::<MacAddr>().unwrap(), mac);
+        prop_assert_eq!(mac.to_string().to_uppercase().parse::<MacAddr>().unwrap(), mac);
+    }
+
+    #[test]
+    fn ipv4_mask_is_a_prefix(entry in ipv4_entry()) {
+        let mask = u32::from(entry.mask);
+        prop_assert_eq!(mask.leading_ones() + mask.trailing_zeros(), 32);
+    }
+
+    #[test]
+    fn ipv6_prefixes_are_bounded(entry in ipv6_entry()) {
+        prop_assert!(entry.dest_prefix <= 128);
+        prop_assert!(entry.src_prefix <= 128);
+    }
+
+    #[test]
+    fn ipv4_builder_rejects_long_prefixes(prefix_len in 33u8..) {
+        prop_assert!(Ipv4RouteEntry::builder("eth0").prefix_len(prefix_len).build().is_err());
+    }
+
+    #[test]
+    fn ipv6_builder_rejects_long_prefixes(prefix_len in 129u8..) {
+        prop_assert!(Ipv6RouteEntry::builder("eth0").dest_prefix(prefix_len).build().is_err());
+        prop_assert!(Ipv6RouteEntry::builder("eth0").src_prefix(prefix_len).build().is_err());
+    }
+}
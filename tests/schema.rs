@@ -0,0 +1,81 @@
+use proc_route_parser::{
+    HeaderError, Ipv4RouteColumn, Ipv4RouteEntry, Ipv4RouteFlags, Ipv4RouteLayout, Ipv4RouteTable,
+    Ipv6RouteColumn, Ipv6RouteLayout, Ipv6RouteTable, RouteParseError, RouteTableOptions,
+};
+use std::io::Cursor;
+
+#[test]
+fn kernel_header_has_the_standard_layout() {
+    let layout = Ipv4RouteLayout::from_header(Ipv4RouteEntry::PROC_HEADER).unwrap();
+    assert!(layout.is_standard());
+    assert_eq!(layout.position(Ipv4RouteColumn::Mask), 7);
+}
+
+#[test]
+fn reordered_and_extra_columns_are_detected() {
+    let header = "Iface Destination Gateway Flags RefCnt Use Metric Mask MTU IRTT Window Proto";
+    let layout = Ipv4RouteLayout::from_header(header).unwrap();
+    assert!(!layout.is_standard());
+    assert_eq!(layout.position(Ipv4RouteColumn::Irtt), 9);
+    assert_eq!(layout.columns().len(), 12);
+    assert_eq!(layout.columns()[11], None);
+
+    assert_eq!(
+        Ipv4RouteLayout::from_header("Iface Destination Gateway"),
+        Err(HeaderError::MissingColumn("Flags"))
+    );
+}
+
+#[test]
+fn table_reports_a_malformed_header_and_keeps_parsing() {
+    let entry = Ipv4RouteEntry::builder("eth0").metric(100).build().unwrap();
+    let input = format!("Iface Destination Gateway\n{}\n", entry.to_proc_line());
+    let mut table = Ipv4RouteTable::from_reader(Cursor::new(input), RouteTableOptions::new());
+    assert!(table.layout().is_none());
+
+    let err = table.next().unwrap().unwrap_err();
+    assert_eq!(err.line_number, 1);
+    assert!(matches!(err.source, RouteParseError::InvalidHeader(_)));
+    assert!(table.layout().unwrap().is_standard());
+    assert_eq!(table.next().unwrap().unwrap(), entry);
+    assert_eq!(table.stats().parse_errors, 1);
+}
+
+#[test]
+fn reordered_ipv4_columns_parse_through_the_header() {
+    let entry = Ipv4RouteEntry::builder("eth0")
+        .gateway("192.0.2.1".parse().unwrap())
+        .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY)
+        .metric(100)
+        .build()
+        .unwrap();
+    // Metric moved to the end and an unknown column appended
+    let input = "Iface Destination Gateway Flags RefCnt Use Mask MTU Window IRTT Metric Proto\n\
+                 eth0 00000000 010200C0 0003 0 0 00000000 0 0 0 100 static\n";
+    let entries: Vec<_> = Ipv4RouteTable::from_reader(Cursor::new(input), RouteTableOptions::new())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries, [entry]);
+}
+
+#[test]
+fn ipv6_layout_comes_from_the_registry_or_the_options() {
+    let line = "fd000000000000000000000000000000 40 00000000000000000000000000000000 00 \
+                00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0 00000007";
+    assert!(Ipv6RouteLayout::for_field_count(10).unwrap().is_standard());
+    assert_eq!(Ipv6RouteLayout::for_field_count(11), None);
+
+    let mut strict = Ipv6RouteTable::from_reader(Cursor::new(line), RouteTableOptions::new());
+    assert!(strict.next().unwrap().is_err());
+    assert!(strict.layout().unwrap().is_standard());
+
+    let layout =
+        Ipv6RouteLayout::from_columns(Ipv6RouteColumn::ALL.into_iter().map(Some).chain([None]))
+            .unwrap();
+    let options = RouteTableOptions::new().ipv6_layout(layout);
+    let entries: Vec<_> = Ipv6RouteTable::from_reader(Cursor::new(line), options)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries[0].dest_prefix, 64);
+    assert_eq!(entries[0].name.as_deref(), Some("eth0"));
+}
@@ -0,0 +1,67 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteFlags, Ipv4RouteScope, Ipv6RouteEntry, Ipv6RouteFlags, Ipv6RouteScope,
+};
+
+#[test]
+fn ipv4_scope_follows_the_next_hop() {
+    use {Ipv4RouteFlags as F, Ipv4RouteScope as S};
+    for (dest, prefix_len, gateway, flags, scope) in [
+        ("127.0.0.0", 8, None, F::UP, S::Host),
+        ("192.0.2.0", 24, None, F::UP, S::Link),
+        ("192.0.2.7", 32, None, F::UP | F::HOST, S::Link),
+        (
+            "0.0.0.0",
+            0,
+            Some("192.0.2.1"),
+            F::UP | F::GATEWAY,
+            S::Universe,
+        ),
+        (
+            "198.51.100.0",
+            24,
+            Some("192.0.2.1"),
+            F::UP | F::GATEWAY,
+            S::Universe,
+        ),
+        ("203.0.113.0", 24, None, F::UP | F::REJECT, S::Universe),
+    ] {
+        let mut builder = Ipv4RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .prefix_len(prefix_len)
+            .flags(flags);
+        if let Some(gateway) = gateway {
+            builder = builder.gateway(gateway.parse().unwrap());
+        }
+        let entry = builder.build().unwrap();
+        assert_eq!(entry.scope(), scope, "{dest}/{prefix_len}");
+    }
+}
+
+#[test]
+fn ipv6_scope_follows_the_destination() {
+    use {Ipv6RouteFlags as F, Ipv6RouteScope as S};
+    for (dest, dest_prefix, flags, scope) in [
+        ("::1", 128, F::UP, S::Host),
+        ("2001:db8::1", 128, F::UP | F::LOCAL, S::Host),
+        ("2001:db8::", 128, F::UP | F::ANYCAST, S::Host),
+        ("ff01::", 16, F::UP, S::Host),
+        ("fe80::", 64, F::UP, S::Link),
+        ("ff02::", 16, F::UP, S::Link),
+        ("fd00::", 8, F::UP, S::Site),
+        ("fec0::", 10, F::UP, S::Site),
+        ("ff05::", 16, F::UP, S::Site),
+        ("2001:db8::", 32, F::UP, S::Global),
+        ("ff0e::", 16, F::UP, S::Global),
+        ("::", 0, F::UP | F::GATEWAY, S::Global),
+        // wider than fe80::/10, so not entirely link-local
+        ("fe80::", 9, F::UP, S::Global),
+    ] {
+        let entry = Ipv6RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .dest_prefix(dest_prefix)
+            .flags(flags)
+            .build()
+            .unwrap();
+        assert_eq!(entry.scope(), scope, "{dest}/{dest_prefix}");
+    }
+}
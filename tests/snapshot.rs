@@ -0,0 +1,115 @@
+use proc_route_parser::{ErrorCode, Ipv4RouteEntry, Snapshot, SnapshotError, SnapshotV1};
+use std::{
+    fs,
+    time::{Duration, SystemTime},
+};
+
+#[test]
+fn versioned_snapshots_round_trip_and_tolerate_new_fields() {
+    let path = std::env::temp_dir().join(format!("snapshot_v1_{}.json", std::process::id()));
+    let saved = SnapshotV1 {
+        hostname: Some("edge-1".into()),
+        kernel_version: Some("6.8.0-45-generic".into()),
+        snapshot: Snapshot {
+            captured_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            ipv4: vec![Ipv4RouteEntry::default_via(
+                "192.0.2.1".parse().unwrap(),
+                "eth0".parse().unwrap(),
+            )],
+            ipv6: Vec::new(),
+            arp: Vec::new(),
+        },
+    };
+    saved.save(&path).unwrap();
+    assert_eq!(SnapshotV1::load(&path).unwrap(), saved);
+    assert_eq!(Snapshot::load(&path).unwrap(), saved.snapshot);
+
+    // a field added by a newer release of the crate
+    let json = fs::read_to_string(&path).unwrap();
+    let newer = json.replacen('{', r#"{"netns": "default","#, 1);
+    fs::write(&path, &newer).unwrap();
+    assert_eq!(SnapshotV1::load(&path).unwrap(), saved);
+
+    fs::write(&path, json.replace(r#""version": 1"#, r#""version": 2"#)).unwrap();
+    let err = SnapshotV1::load(&path).unwrap_err();
+    assert!(matches!(err, SnapshotError::UnsupportedVersion(2)));
+    assert_eq!(err.error_code(), ErrorCode::UnsupportedSnapshotVersion);
+
+    fs::write(&path, json.replace(SnapshotV1::MAGIC, "route-dump")).unwrap();
+    assert!(matches!(
+        SnapshotV1::load(&path),
+        Err(SnapshotError::BadMagic)
+    ));
+
+    // as saved before the format was versioned
+    let unversioned: String = json
+        .lines()
+        .filter(|line| {
+            !["magic", "version", "hostname"]
+                .iter()
+                .any(|key| line.contains(key))
+        })
+        .collect();
+    fs::write(&path, unversioned).unwrap();
+    assert_eq!(
+        SnapshotV1::load(&path).unwrap(),
+        SnapshotV1::from(saved.snapshot)
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn iproute2_json_converts_to_entries_and_back() {
+    use proc_route_parser::{Ipv4RouteFlags, Ipv6RouteFlags, Ipv6RoutePref, Route, iproute2};
+
+    let ipv4 = r#"[
+        {"dst":"default","gateway":"192.0.2.1","dev":"eth0","protocol":"dhcp","metric":100,"flags":[]},
+        {"dst":"192.0.2.0/24","dev":"eth0","protocol":"kernel","scope":"link","prefsrc":"192.0.2.10","flags":[]},
+        {"type":"unreachable","dst":"198.51.100.7","flags":[]},
+        {"dst":"10.0.0.0/8","table":"100","dev":"wg0","flags":[]},
+        {"dst":"203.0.113.0/24","metrics":[{"mtu":["lock",1400]}],"flags":[],
+         "nexthops":[{"gateway":"192.0.2.2","dev":"eth0","weight":1,"flags":["dead"]},
+                     {"gateway":"192.0.2.3","dev":"eth0","weight":1,"flags":[]}]}
+    ]"#;
+    let ipv6 = r#"[
+        {"dst":"2001:db8::/64","dev":"eth0","protocol":"kernel","metric":256,"flags":[],"pref":"medium"},
+        {"dst":"default","gateway":"fe80::1","dev":"eth0","protocol":"ra","metric":1024,"expires":1790,"flags":[],"pref":"high"},
+        {"type":"unreachable","dst":"2001:db8:dead::/48","dev":"lo","metric":1024,"flags":[],"pref":"medium"}
+    ]"#;
+    let snapshot = Snapshot::from_ip_json(ipv4, ipv6).unwrap();
+
+    // the route of table 100 is not one /proc/net/route lists
+    assert_eq!(snapshot.ipv4.len(), 4);
+    let default = &snapshot.ipv4[0];
+    assert_eq!(default.gateway(), Some("192.0.2.1".parse().unwrap()));
+    assert_eq!(default.metric().get(), 100);
+    assert!(
+        snapshot.ipv4[2]
+            .flags
+            .contains(Ipv4RouteFlags::HOST | Ipv4RouteFlags::REJECT)
+    );
+    // only the first, dead, next hop of a multipath route
+    let multipath = &snapshot.ipv4[3];
+    assert_eq!(multipath.gateway(), Some("192.0.2.2".parse().unwrap()));
+    assert!(!multipath.is_up());
+    assert_eq!(multipath.mtu, Some(1400));
+
+    let ra = &snapshot.ipv6[1];
+    assert!(
+        ra.flags.contains(
+            Ipv6RouteFlags::ADDR_CONF | Ipv6RouteFlags::DEFAULT | Ipv6RouteFlags::EXPIRES
+        )
+    );
+    assert_eq!(ra.preference(), Ipv6RoutePref::High);
+    assert!(snapshot.ipv6[2].is_reject());
+
+    let ipv4_again = iproute2::parse_ipv4(&iproute2::ipv4_to_json(&snapshot.ipv4)).unwrap();
+    assert_eq!(ipv4_again, snapshot.ipv4);
+    let ipv6_again = iproute2::parse_ipv6(&iproute2::ipv6_to_json(&snapshot.ipv6)).unwrap();
+    assert_eq!(ipv6_again, snapshot.ipv6);
+
+    assert!(matches!(
+        iproute2::parse_ipv4(ipv6),
+        Err(iproute2::IpJsonError::InvalidDestination { index: 0, .. })
+    ));
+}
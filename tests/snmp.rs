@@ -0,0 +1,50 @@
+use proc_route_parser::{Ipv4RouteCounters, Ipv6RouteCounters, MissingCounter};
+
+const SNMP: &str = "\
+Ip: Forwarding DefaultTTL InReceives InHdrErrors InAddrErrors ForwDatagrams InUnknownProtos InDiscards InDelivers OutRequests OutDiscards OutNoRoutes
+Ip: 1 64 18749 0 3 120 0 0 18737 18739 0 7
+Icmp: InMsgs InErrors InDestUnreachs InRedirects OutMsgs OutDestUnreachs OutRedirects
+Icmp: 2 0 1 4 3 5 0
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn
+Tcp: 1 200 120000 -1
+";
+
+#[test]
+fn ipv4_counters_combine_snmp_and_netstat() {
+    let netstat = "IpExt: InNoRoutes InTruncatedPkts\nIpExt: 9 0\n";
+    let counters = Ipv4RouteCounters::parse(SNMP, Some(netstat)).unwrap();
+    assert!(counters.forwarding);
+    assert_eq!(counters.forw_datagrams, 120);
+    assert_eq!(counters.out_no_routes, 7);
+    assert_eq!(counters.in_no_routes, Some(9));
+    assert_eq!(counters.in_redirects, 4);
+    assert_eq!(counters.out_dest_unreachs, 5);
+    assert_eq!(counters.no_route_drops(), 16);
+
+    let counters = Ipv4RouteCounters::parse(SNMP, None).unwrap();
+    assert_eq!(counters.in_no_routes, None);
+    assert_eq!(counters.no_route_drops(), 7);
+}
+
+#[test]
+fn ipv6_counters_read_one_counter_per_line() {
+    let snmp6 = [
+        "Ip6InReceives 6",
+        "Ip6InNoRoutes 2",
+        "Ip6InAddrErrors 0",
+        "Ip6OutForwDatagrams 0",
+        "Ip6OutNoRoutes 3",
+        "Icmp6InDestUnreachs 1",
+        "Icmp6OutDestUnreachs 0",
+        "Icmp6InRedirects 0",
+        "Icmp6OutRedirects 0",
+    ]
+    .join("\n");
+    let counters = Ipv6RouteCounters::parse(&snmp6).unwrap();
+    assert_eq!(counters.in_no_routes, 2);
+    assert_eq!(counters.no_route_drops(), 5);
+    assert_eq!(
+        Ipv6RouteCounters::parse("Ip6InReceives 6\n"),
+        Err(MissingCounter("Ip6InNoRoutes"))
+    );
+}
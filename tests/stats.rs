@@ -0,0 +1,34 @@
+use proc_route_parser::{Ipv4RouteTable, Ipv6RouteTable, TableStats};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn stats_count_the_header_and_every_byte() {
+    let path = format!("{FIXTURES}/route.linux-6.x");
+    let mut table = Ipv4RouteTable::open(&path).unwrap();
+    assert_eq!(table.stats(), TableStats::default());
+    table.next().unwrap().unwrap();
+    assert_eq!(table.stats().lines_read, 2);
+    assert_eq!(table.stats().entries_parsed, 1);
+
+    table.by_ref().for_each(drop);
+    assert_eq!(
+        table.stats(),
+        TableStats {
+            lines_read: 3,
+            entries_parsed: 2,
+            parse_errors: 0,
+            bytes_read: std::fs::metadata(&path).unwrap().len(),
+        }
+    );
+}
+
+#[test]
+fn lenient_tables_count_parse_errors() {
+    let mut table = Ipv6RouteTable::open_lenient(format!("{FIXTURES}/ipv6_route.quirks")).unwrap();
+    table.by_ref().for_each(drop);
+    let stats = table.stats();
+    assert_eq!(stats.lines_read, 3);
+    assert_eq!(stats.entries_parsed, 1);
+    assert_eq!(stats.parse_errors, table.report().malformed().len() as u64);
+}
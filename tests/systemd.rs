@@ -0,0 +1,48 @@
+#![cfg(target_os = "linux")]
+
+use proc_route_parser::{ArpEntry, Ipv4RouteEntry, PollingRouteWatcher, SystemdNotifier};
+use std::{fs, os::unix::net::UnixDatagram, sync::Arc, time::Duration};
+
+fn receive(socket: &UnixDatagram) -> String {
+    let mut buf = [0; 64];
+    let len = socket.recv(&mut buf).unwrap();
+    String::from_utf8(buf[..len].to_vec()).unwrap()
+}
+
+#[test]
+fn watcher_pings_the_watchdog_from_its_poll_loop() {
+    let dir = std::env::temp_dir().join(format!("systemd_notify_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("route"),
+        format!("{}\n", Ipv4RouteEntry::PROC_HEADER),
+    )
+    .unwrap();
+    fs::write(dir.join("arp"), format!("{}\n", ArpEntry::PROC_HEADER)).unwrap();
+    let socket_path = dir.join("notify");
+    let systemd = UnixDatagram::bind(&socket_path).unwrap();
+    systemd
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let notifier = SystemdNotifier::connect(socket_path.to_str().unwrap())
+        .unwrap()
+        .watchdog_timeout(Duration::from_millis(40));
+    assert_eq!(
+        notifier.watchdog_interval(),
+        Some(Duration::from_millis(20))
+    );
+    let notifier = Arc::new(notifier);
+    let (handle, _changes) = PollingRouteWatcher::new(Duration::from_millis(10))
+        .proc_net(&dir)
+        .systemd_watchdog(notifier.clone())
+        .start()
+        .unwrap();
+    notifier.ready().unwrap();
+    assert_eq!(receive(&systemd), "READY=1");
+    assert_eq!(receive(&systemd), "WATCHDOG=1");
+    handle.stop();
+    notifier.status("stopped").unwrap();
+    assert_eq!(receive(&systemd), "STATUS=stopped");
+    fs::remove_dir_all(dir).unwrap();
+}
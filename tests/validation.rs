@@ -0,0 +1,147 @@
+use std::io::Cursor;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+use proc_route_parser::{
+    IfName, IfNameError, Ipv4RouteEntry, Ipv4RouteEntryRef, Ipv4RouteTable, Ipv6RouteEntryRef,
+    Ipv6RouteTable, Limit, RouteParseError, RouteTableOptions, ValidationError,
+};
+
+const IPV4_HEADER: &str =
+    "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT";
+
+#[test]
+fn non_contiguous_ipv4_mask_is_rejected() {
+    let line = "eth0\t00000000\t00000000\t0001\t0\t0\t0\t00FF00FF\t0\t0\t0";
+    assert!(matches!(
+        Ipv4RouteEntryRef::parse(line),
+        Err(RouteParseError::InvalidEntry(ValidationError::NonContiguousMask(mask)))
+            if mask == Ipv4Addr::new(255, 0, 255, 0)
+    ));
+    assert!(Ipv4RouteEntryRef::parse_unvalidated(line).is_ok());
+}
+
+#[test]
+fn gateway_needs_its_flag() {
+    let line = "eth0\t00000000\t0101A8C0\t0001\t0\t0\t0\t00000000\t0\t0\t0";
+    let input = format!("{IPV4_HEADER}\n{line}\n");
+
+    let strict: Vec<_> =
+        Ipv4RouteTable::from_reader(Cursor::new(&input), RouteTableOptions::new()).collect();
+    assert!(matches!(
+        strict[0].as_ref().unwrap_err().source,
+        RouteParseError::InvalidEntry(ValidationError::GatewayWithoutFlag(IpAddr::V4(gateway)))
+            if gateway == Ipv4Addr::new(192, 168, 1, 1)
+    ));
+
+    let options = RouteTableOptions::new().validate(false);
+    let relaxed: Vec<_> = Ipv4RouteTable::from_reader(Cursor::new(&input), options).collect();
+    assert_eq!(
+        relaxed[0].as_ref().unwrap().gateway,
+        Ipv4Addr::new(192, 168, 1, 1)
+    );
+}
+
+#[test]
+fn ipv6_prefix_longer_than_128_is_rejected() {
+    let line = "fd000000000000000000000000000000 81 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000003 00000000 00000001     eth0";
+    assert!(matches!(
+        Ipv6RouteEntryRef::parse(line),
+        Err(RouteParseError::InvalidEntry(
+            ValidationError::PrefixTooLong { len: 129, max: 128 }
+        ))
+    ));
+
+    let options = RouteTableOptions::new().validate(false);
+    let entry = Ipv6RouteTable::from_reader(Cursor::new(line), options)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.dest, "fd00::".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(entry.dest_prefix, 129);
+}
+
+#[test]
+fn interface_names_are_bounded_by_ifnamsiz() {
+    assert_eq!("wlp0s20f3".parse::<IfName>().unwrap().as_str(), "wlp0s20f3");
+    assert_eq!(IfName::new(""), Err(IfNameError::Empty));
+    assert!(matches!(
+        IfName::new("veth1234567890ab"),
+        Err(IfNameError::TooLong(_))
+    ));
+    assert!(matches!(
+        IfName::new("eth 0"),
+        Err(IfNameError::Whitespace(_))
+    ));
+
+    let line = "veth1234567890ab\t00000000\t00000000\t0001\t0\t0\t0\t00000000\t0\t0\t0";
+    assert!(matches!(
+        Ipv4RouteEntryRef::parse_unvalidated(line),
+        Err(RouteParseError::InvalidIfName(IfNameError::TooLong(_)))
+    ));
+    assert_eq!(
+        Ipv4RouteEntry::builder("").build(),
+        Err(ValidationError::InvalidIfName(IfNameError::Empty))
+    );
+}
+
+#[test]
+fn interned_names_share_one_allocation() {
+    let name = |entry: &Ipv4RouteEntry| -> Arc<str> { entry.name.clone().into() };
+    let input = format!(
+        "{IPV4_HEADER}\n\
+         eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n\
+         eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+         lo\t0000007F\t00000000\t0001\t0\t0\t0\t000000FF\t0\t0\t0\n"
+    );
+    let read = |intern| -> Vec<_> {
+        let options = RouteTableOptions::new().intern_names(intern);
+        Ipv4RouteTable::from_reader(Cursor::new(input.clone()), options)
+            .map(Result::unwrap)
+            .collect()
+    };
+
+    let entries = read(true);
+    assert!(Arc::ptr_eq(&name(&entries[0]), &name(&entries[1])));
+    assert!(!Arc::ptr_eq(&name(&entries[0]), &name(&entries[2])));
+
+    let entries = read(false);
+    assert_eq!(entries[0].name, entries[1].name);
+    assert!(!Arc::ptr_eq(&name(&entries[0]), &name(&entries[1])));
+}
+
+#[test]
+fn tables_stop_at_their_limits() {
+    let line = "eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0";
+    let input = format!(
+        "{IPV4_HEADER}\n{line}\neth0{}\n{line} extra\n{line}\n{line}\n",
+        " ".repeat(200)
+    );
+    let options = RouteTableOptions::new()
+        .max_line_len(128)
+        .max_fields(11)
+        .max_entries(3);
+    let results: Vec<_> = Ipv4RouteTable::from_reader(Cursor::new(input), options).collect();
+
+    let limits: Vec<_> = results
+        .iter()
+        .map(|result| match result {
+            Err(err) => match err.source {
+                RouteParseError::LimitExceeded { limit, .. } => Some(limit),
+                _ => panic!("unexpected error {err}"),
+            },
+            Ok(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        limits,
+        [
+            None,
+            Some(Limit::LineLength),
+            Some(Limit::FieldCount),
+            Some(Limit::Entries)
+        ]
+    );
+    // the overlong line is skipped as a whole
+    assert_eq!(results[2].as_ref().unwrap_err().line_number, 4);
+}
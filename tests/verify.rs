@@ -0,0 +1,160 @@
+use proc_route_parser::{
+    IpNet, Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, KernelRoute,
+    LookupMismatch, NextHop, RouteEntry, RouteTables, sample_destinations, verify_lookups_with,
+};
+use std::{io, net::IpAddr, time::Instant};
+
+const MAIN: u32 = libc::RT_TABLE_MAIN as u32;
+
+fn addr(addr: &str) -> IpAddr {
+    addr.parse().unwrap()
+}
+
+fn network(addr: &str, prefix_len: u8) -> IpNet {
+    IpNet::new(addr.parse().unwrap(), prefix_len).unwrap()
+}
+
+/// A default route via `192.0.2.1`, its on-link subnet, a host route and two IPv6 routes
+fn tables() -> RouteTables {
+    let ipv4 = |dest: &str, prefix_len, gateway: Option<&str>| {
+        let mut builder = Ipv4RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .prefix_len(prefix_len)
+            .metric(100);
+        if let Some(gateway) = gateway {
+            builder = builder
+                .gateway(gateway.parse().unwrap())
+                .flags(Ipv4RouteFlags::UP | Ipv4RouteFlags::GATEWAY);
+        }
+        builder.build().unwrap()
+    };
+    let ipv6 = |dest: &str, dest_prefix, next_hop: Option<&str>| {
+        let mut builder = Ipv6RouteEntry::builder("eth0")
+            .dest(dest.parse().unwrap())
+            .dest_prefix(dest_prefix)
+            .flags(Ipv6RouteFlags::UP)
+            .metric(256);
+        if let Some(next_hop) = next_hop {
+            builder = builder
+                .next_hop(next_hop.parse().unwrap())
+                .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY);
+        }
+        builder.build().unwrap()
+    };
+    RouteTables {
+        ipv4: vec![
+            ipv4("0.0.0.0", 0, Some("192.0.2.1")),
+            ipv4("192.0.2.0", 24, None),
+            ipv4("198.51.100.7", 32, Some("192.0.2.1")),
+        ],
+        ipv6: vec![
+            ipv6("fe80::", 64, None),
+            ipv6("2001:db8::", 32, Some("fe80::1")),
+        ],
+        read_at: Instant::now(),
+    }
+}
+
+fn kernel_route(
+    destination: IpNet,
+    table: u32,
+    route_type: u8,
+    gateway: Option<&str>,
+) -> KernelRoute {
+    KernelRoute {
+        destination,
+        table,
+        route_type,
+        metric: 0,
+        preferred_source: None,
+        next_hops: vec![NextHop {
+            gateway: gateway.map(addr),
+            iface: "eth0".into(),
+            weight: 1,
+        }],
+    }
+}
+
+#[test]
+fn samples_cover_every_prefix_and_a_public_address_per_family() {
+    let mut tables = tables();
+    // a second route to the same subnet adds no destination
+    tables.ipv4.push(tables.ipv4[1].clone());
+    assert_eq!(
+        sample_destinations(&tables),
+        [
+            addr("1.1.1.1"),
+            addr("192.0.2.1"),
+            addr("198.51.100.7"),
+            addr("2001:db8::1"),
+            addr("2606:4700:4700::1111"),
+            addr("fe80::1"),
+        ]
+    );
+}
+
+#[test]
+fn mismatches_against_the_kernel_are_reported() {
+    let tables = tables();
+    let answer = |dest: IpAddr| match dest.to_string().as_str() {
+        "1.1.1.1" => Some(kernel_route(
+            network("0.0.0.0", 0),
+            MAIN,
+            libc::RTN_UNICAST,
+            Some("192.0.2.254"),
+        )),
+        "192.0.2.1" => Some(kernel_route(
+            network("192.0.2.0", 24),
+            MAIN,
+            libc::RTN_UNICAST,
+            None,
+        )),
+        // the kernel answers from the local table, which /proc/net/route does not list
+        "198.51.100.7" => Some(kernel_route(
+            network("198.51.100.7", 32),
+            libc::RT_TABLE_LOCAL.into(),
+            libc::RTN_LOCAL,
+            None,
+        )),
+        "fe80::1" => Some(kernel_route(
+            network("fe80::", 64),
+            MAIN,
+            libc::RTN_UNICAST,
+            None,
+        )),
+        _ => None,
+    };
+    let report = verify_lookups_with(&tables, sample_destinations(&tables), |dest| {
+        Ok(answer(dest))
+    })
+    .unwrap();
+
+    assert_eq!(report.checked, 5);
+    assert_eq!(report.skipped, 1);
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.mismatches,
+        [
+            LookupMismatch {
+                dest: addr("1.1.1.1"),
+                ours: Some(RouteEntry::V4(tables.ipv4[0].clone())),
+                kernel: answer(addr("1.1.1.1")),
+            },
+            LookupMismatch {
+                dest: addr("2001:db8::1"),
+                ours: Some(RouteEntry::V6(tables.ipv6[1].clone())),
+                kernel: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn lookup_errors_end_the_check() {
+    let tables = tables();
+    let err = verify_lookups_with(&tables, [addr("192.0.2.1")], |_| {
+        Err(io::Error::from(io::ErrorKind::PermissionDenied))
+    })
+    .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
@@ -0,0 +1,78 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteFlags, Ipv6RouteEntry, Ipv6RouteFlags, RouteEntry,
+    vpn::{LeakKind, detect_leaks},
+};
+
+fn ipv4(iface: &str, dest: &str, prefix_len: u8, metric: u32) -> RouteEntry {
+    Ipv4RouteEntry::builder(iface)
+        .dest(dest.parse().unwrap())
+        .prefix_len(prefix_len)
+        .flags(Ipv4RouteFlags::UP)
+        .metric(metric)
+        .build()
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn split_default_tunnel_leaks_bypass_routes_and_ipv6() {
+    let entries = [
+        ipv4("wlan0", "0.0.0.0", 0, 600),
+        ipv4("tun0", "0.0.0.0", 1, 0),
+        ipv4("tun0", "128.0.0.0", 1, 0),
+        ipv4("wlan0", "192.168.1.0", 24, 600),
+        ipv4("wlan0", "169.254.0.0", 16, 1000),
+        Ipv6RouteEntry::builder("wlan0")
+            .next_hop("fe80::1".parse().unwrap())
+            .flags(Ipv6RouteFlags::UP | Ipv6RouteFlags::GATEWAY)
+            .metric(1024)
+            .build()
+            .unwrap()
+            .into(),
+        Ipv6RouteEntry::builder("wlan0")
+            .dest("fe80::".parse().unwrap())
+            .dest_prefix(64)
+            .flags(Ipv6RouteFlags::UP)
+            .metric(256)
+            .build()
+            .unwrap()
+            .into(),
+    ];
+    let report = detect_leaks(entries.clone(), "tun0");
+    assert!(report.ipv4_tunneled);
+    assert!(!report.ipv6_tunneled);
+    let leaks: Vec<_> = report
+        .leaks
+        .iter()
+        .map(|leak| (leak.route.clone(), leak.kind))
+        .collect();
+    assert_eq!(
+        leaks,
+        [
+            (entries[3].clone(), LeakKind::Bypass),
+            (entries[5].clone(), LeakKind::UntunneledFamily),
+        ]
+    );
+}
+
+#[test]
+fn default_with_a_lower_metric_outranks_the_tunnel() {
+    let report = detect_leaks(
+        [
+            ipv4("eth0", "0.0.0.0", 0, 50),
+            ipv4("wg0", "0.0.0.0", 0, 100),
+        ],
+        "wg0",
+    );
+    assert_eq!(report.leaks.len(), 1);
+    assert_eq!(report.leaks[0].kind, LeakKind::Bypass);
+
+    let report = detect_leaks(
+        [
+            ipv4("eth0", "0.0.0.0", 0, 100),
+            ipv4("wg0", "0.0.0.0", 0, 50),
+        ],
+        "wg0",
+    );
+    assert!(report.is_empty());
+}
@@ -0,0 +1,42 @@
+use proc_route_parser::{
+    Ipv4RouteEntry, Ipv4RouteTable, Ipv6RouteTable, RouteParseError, RouteTableOptions, wasm,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+#[test]
+fn injected_tables_read_like_proc_files() {
+    let route = std::fs::read(format!("{FIXTURES}/route.linux-6.x")).unwrap();
+    let ipv6_route = std::fs::read(format!("{FIXTURES}/ipv6_route.linux-6.x")).unwrap();
+    let from_file: Vec<_> = Ipv4RouteTable::open(format!("{FIXTURES}/route.linux-6.x"))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    wasm::clear_injected_tables();
+    assert!(wasm::ipv4_route_table().is_err());
+    assert!(matches!(
+        wasm::ipv6_route_table(),
+        Err(RouteParseError::Ipv6Unavailable)
+    ));
+
+    wasm::inject_ipv4_route_table(route.clone());
+    wasm::inject_ipv6_route_table(ipv6_route.clone());
+    let injected: Vec<Ipv4RouteEntry> = wasm::ipv4_route_table()
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(injected, from_file);
+    // every call reads the injected contents from the start
+    assert_eq!(wasm::ipv4_route_table().unwrap().count(), from_file.len());
+    assert_eq!(
+        wasm::ipv6_route_table().unwrap().count(),
+        Ipv6RouteTable::from_bytes(ipv6_route, RouteTableOptions::new()).count()
+    );
+
+    let from_bytes: Vec<_> = Ipv4RouteTable::from_bytes(route, RouteTableOptions::new())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(from_bytes, from_file);
+    wasm::clear_injected_tables();
+}
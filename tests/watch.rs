@@ -0,0 +1,70 @@
+use proc_route_parser::{
+    ArpEntry, Ipv4RouteEntry, PollingRouteWatcher, RouteChange, RouteEntry, RouteEvent,
+    parse_filter,
+};
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+fn write_routes(proc_net: &Path, gateway: &str) {
+    let default = Ipv4RouteEntry::default_via(gateway.parse().unwrap(), "eth0".parse().unwrap());
+    let lan = Ipv4RouteEntry::builder("eth1")
+        .dest("10.1.0.0".parse().unwrap())
+        .prefix_len(16)
+        .build()
+        .unwrap();
+    let mut text = format!(
+        "{}\n{}\n",
+        Ipv4RouteEntry::PROC_HEADER,
+        default.to_proc_line()
+    );
+    // the LAN route comes and goes with the gateway, to check the filter drops it
+    if gateway.ends_with(".254") {
+        text += &format!("{}\n", lan.to_proc_line());
+    }
+    fs::write(proc_net.join("route"), text).unwrap();
+}
+
+#[test]
+fn hooks_receive_the_changes_passing_their_filter() {
+    let proc_net = std::env::temp_dir().join(format!("watch_hooks_{}", std::process::id()));
+    fs::create_dir_all(&proc_net).unwrap();
+    fs::write(proc_net.join("arp"), format!("{}\n", ArpEntry::PROC_HEADER)).unwrap();
+    write_routes(&proc_net, "192.0.2.1");
+
+    let all = Arc::new(Mutex::new(Vec::<RouteEvent>::new()));
+    let defaults = Arc::new(Mutex::new(Vec::<RouteEvent>::new()));
+    let (handle, changes) = PollingRouteWatcher::new(Duration::from_millis(10))
+        .proc_net(&proc_net)
+        .on_change({
+            let all = all.clone();
+            move |event| all.lock().unwrap().push(event.clone())
+        })
+        .on_matching_change(parse_filter("dest == 0.0.0.0/0").unwrap(), {
+            let defaults = defaults.clone();
+            move |event| defaults.lock().unwrap().push(event.clone())
+        })
+        .start()
+        .unwrap();
+    write_routes(&proc_net, "192.0.2.254");
+    // hooks run before the change reaches the channel
+    let diff = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+    handle.stop();
+    fs::remove_dir_all(&proc_net).unwrap();
+
+    assert_eq!(*all.lock().unwrap(), diff.events().collect::<Vec<_>>());
+    let defaults = defaults.lock().unwrap();
+    assert_eq!(defaults.len(), 1);
+    let RouteChange::Changed {
+        old: RouteEntry::V4(old),
+        new: RouteEntry::V4(new),
+    } = &defaults[0].change
+    else {
+        panic!("expected a changed default route, got {:?}", defaults[0]);
+    };
+    assert_eq!(old.gateway.to_string(), "192.0.2.1");
+    assert_eq!(new.gateway.to_string(), "192.0.2.254");
+}